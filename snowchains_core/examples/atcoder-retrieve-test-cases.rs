@@ -11,6 +11,7 @@ use snowchains_core::{
 use std::{
     env, fmt,
     io::{self, Write as _},
+    num::NonZeroUsize,
 };
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames as _};
@@ -21,6 +22,9 @@ struct Opt {
     #[structopt(long)]
     full: bool,
 
+    #[structopt(long, value_name("NUMBER"), default_value("4"))]
+    parallelism: NonZeroUsize,
+
     #[structopt(short, long, value_name("HUMANTIME"))]
     timeout: Option<humantime::Duration>,
 
@@ -48,6 +52,7 @@ enum CredentialsVia {
 fn main() -> anyhow::Result<()> {
     let Opt {
         full,
+        parallelism,
         timeout,
         credentials,
         problems,
@@ -72,6 +77,9 @@ fn main() -> anyhow::Result<()> {
                         CredentialsVia::Env => env::var("DROPBOX_ACCESS_TOKEN")?,
                     },
                 },
+                available_space: None,
+                saved_path: None,
+                parallelism,
             })
         } else {
             None
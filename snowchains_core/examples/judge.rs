@@ -54,6 +54,8 @@ fn main() -> anyhow::Result<()> {
             env: btreemap!(),
         },
         &test_cases,
+        false,
+        true,
     )?;
 
     let stdout = BufferedStandardStream::stdout(if atty::is(atty::Stream::Stdout) {
@@ -63,7 +65,7 @@ fn main() -> anyhow::Result<()> {
     });
 
     eprintln!();
-    outcome.print_pretty(stdout, None)?;
+    outcome.print_pretty(stdout, None, true)?;
 
     Ok(())
 }
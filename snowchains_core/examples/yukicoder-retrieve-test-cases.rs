@@ -9,6 +9,7 @@ use snowchains_core::{
 use std::{
     env, fmt,
     io::{self, Write as _},
+    num::NonZeroUsize,
     str,
 };
 use structopt::StructOpt;
@@ -20,6 +21,9 @@ struct Opt {
     #[structopt(long)]
     full: bool,
 
+    #[structopt(long, value_name("NUMBER"), default_value("4"))]
+    parallelism: NonZeroUsize,
+
     #[structopt(short, long, value_name("HUMANTIME"))]
     timeout: Option<humantime::Duration>,
 
@@ -53,6 +57,7 @@ enum CredentialsVia {
 fn main() -> anyhow::Result<()> {
     let Opt {
         full,
+        parallelism,
         timeout,
         credentials,
         problems,
@@ -81,6 +86,9 @@ fn main() -> anyhow::Result<()> {
                         CredentialsVia::Env => env::var("YUKICODER_API_KEY")?,
                     },
                 },
+                available_space: None,
+                saved_path: None,
+                parallelism,
             })
         } else {
             None
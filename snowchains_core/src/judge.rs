@@ -1,12 +1,21 @@
-use crate::testsuite::{BatchTestCase, CheckerShell, ExpectedOutput};
-use anyhow::{anyhow, bail};
+mod diff;
+
+use crate::{
+    terminal::escape_control_chars,
+    testsuite::{BatchTestCase, CheckerShell, ExpectedOutput, FuzzyMatchResult, Scorer},
+};
+use anyhow::{anyhow, bail, Context as _};
 use futures_util::{select, FutureExt as _};
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use prettytable::{cell, row};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp,
     collections::BTreeMap,
     env,
     ffi::{OsStr, OsString},
+    fmt,
     future::Future,
     io, iter,
     path::{Path, PathBuf},
@@ -15,7 +24,7 @@ use std::{
     time::{Duration, Instant},
 };
 use termcolor::{Color, WriteColor};
-use tokio::io::AsyncWriteExt as _;
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, AsyncWriteExt as _};
 use unicode_width::UnicodeWidthStr as _;
 
 #[non_exhaustive]
@@ -25,10 +34,13 @@ pub struct JudgeOutcome {
 }
 
 impl JudgeOutcome {
+    /// With `diff`, also prints a unified diff of the expected and actual outputs for each
+    /// `Wrong Answer`.
     pub fn print_pretty<W: WriteColor>(
         &self,
         mut wtr: W,
         display_limit: Option<usize>,
+        diff: bool,
     ) -> io::Result<()> {
         for (i, verdict) in self.verdicts.iter().enumerate() {
             if i > 0 {
@@ -121,10 +133,53 @@ impl JudgeOutcome {
             if let Some(wrong_answer_note) = verdict.wrong_answer_note() {
                 write_text("note: ", &(wrong_answer_note.to_string() + "\n"), false)?;
             }
+            if let (true, Verdict::WrongAnswer { stdout, .. }, Some(expected)) =
+                (diff, verdict, verdict.expected().expected_stdout())
+            {
+                let within_limit = |s: &str| !matches!(display_limit, Some(l) if l < s.len());
+
+                if within_limit(expected) && within_limit(stdout) {
+                    write_diff(&mut wtr, expected, stdout)?;
+                }
+            }
         }
 
         return wtr.flush();
 
+        fn write_diff(wtr: &mut impl WriteColor, expected: &str, actual: &str) -> io::Result<()> {
+            let hunks = match diff::unified(expected, actual, 3) {
+                Some(hunks) if !hunks.is_empty() => hunks,
+                _ => return Ok(()),
+            };
+
+            wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+            writeln!(wtr, "diff:")?;
+            wtr.reset()?;
+
+            for diff::Hunk { header, lines } in hunks {
+                wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
+                writeln!(wtr, "{}", header)?;
+                wtr.reset()?;
+
+                for line in lines {
+                    match line {
+                        diff::Line::Equal(s) => writeln!(wtr, " {}", escape_control_chars(s))?,
+                        diff::Line::Delete(s) => {
+                            wtr.set_color(color_spec!(Fg(Color::Red)))?;
+                            writeln!(wtr, "-{}", escape_control_chars(s))?;
+                            wtr.reset()?;
+                        }
+                        diff::Line::Insert(s) => {
+                            wtr.set_color(color_spec!(Fg(Color::Green)))?;
+                            writeln!(wtr, "+{}", escape_control_chars(s))?;
+                            wtr.reset()?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+
         #[derive(Debug)]
         enum Token<'a> {
             SpcLf(&'a str),
@@ -217,6 +272,69 @@ impl JudgeOutcome {
 
         Ok(())
     }
+
+    /// Summarizes the verdicts. Only failed test cases keep their input and outputs.
+    pub fn summary(&self) -> JudgeSummary {
+        let cases = self
+            .verdicts
+            .iter()
+            .map(|verdict| {
+                let accepted = matches!(verdict, Verdict::Accepted { .. });
+                let keep = |text: Option<&str>| text.filter(|_| !accepted).map(ToOwned::to_owned);
+
+                JudgeSummaryCase {
+                    name: verdict.test_case_name().map(ToOwned::to_owned),
+                    accepted,
+                    summary: verdict.summary(),
+                    stdin: keep(Some(verdict.stdin())),
+                    expected: keep(
+                        verdict
+                            .expected()
+                            .expected_stdout()
+                            .or_else(|| verdict.expected().example()),
+                    ),
+                    stdout: keep(verdict.stdout()),
+                    stderr: keep(verdict.stderr()),
+                }
+            })
+            .collect();
+
+        JudgeSummary { cases }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JudgeSummary {
+    pub cases: Vec<JudgeSummaryCase>,
+}
+
+impl JudgeSummary {
+    pub fn to_table(&self) -> impl fmt::Display {
+        let mut table = crate::web::new_table();
+
+        table.set_titles(row!["#", "Name", "Verdict"]);
+
+        for (i, case) in self.cases.iter().enumerate() {
+            table.add_row(row![r->i + 1, case.name.as_deref().unwrap_or(""), case.summary]);
+        }
+
+        table
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JudgeSummaryCase {
+    pub name: Option<String>,
+    pub accepted: bool,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -258,7 +376,7 @@ pub enum Verdict {
 }
 
 impl Verdict {
-    fn test_case_name(&self) -> Option<&str> {
+    pub fn test_case_name(&self) -> Option<&str> {
         match self {
             Verdict::Accepted { test_case_name, .. }
             | Verdict::WrongAnswer { test_case_name, .. }
@@ -317,9 +435,9 @@ impl Verdict {
         }
     }
 
-    fn wrong_answer_note(&self) -> Option<WrongAnswerNote> {
-        match *self {
-            Self::WrongAnswer { note, .. } => note,
+    fn wrong_answer_note(&self) -> Option<&WrongAnswerNote> {
+        match self {
+            Self::WrongAnswer { note, .. } => note.as_ref(),
             _ => None,
         }
     }
@@ -356,12 +474,37 @@ impl Verdict {
     }
 }
 
-#[derive(Copy, Clone, Debug, derive_more::Display)]
+#[derive(Clone, Debug, derive_more::Display)]
 pub enum WrongAnswerNote {
     #[display(
         fmt = "whitespace-separated words matched. try setting `match` to `SplitWhitespace`"
     )]
     WordsMatched,
+    /// The first mismatch found with `match: Float`.
+    #[display(fmt = "{}", _0)]
+    FloatMismatch(String),
+}
+
+/// Environment variables for the solver, so that it can see its time budget.
+///
+/// - `SNOWCHAINS_JUDGE`: `1`
+/// - `SNOWCHAINS_CASE_INDEX`: 1-based position of the test case, as shown in the progress
+/// - `SNOWCHAINS_CASE_NAME`: name of the test case (empty if unnamed)
+/// - `SNOWCHAINS_TIMELIMIT_MS`: the time limit in milliseconds (unset if none)
+fn case_env(
+    index: usize,
+    name: Option<&str>,
+    timelimit: Option<Duration>,
+) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("SNOWCHAINS_JUDGE", "1".to_owned()),
+        ("SNOWCHAINS_CASE_INDEX", (index + 1).to_string()),
+        ("SNOWCHAINS_CASE_NAME", name.unwrap_or("").to_owned()),
+    ];
+    if let Some(timelimit) = timelimit {
+        env.push(("SNOWCHAINS_TIMELIMIT_MS", timelimit.as_millis().to_string()));
+    }
+    env
 }
 
 #[derive(Debug, Clone)]
@@ -402,6 +545,8 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
     ctrl_c: fn() -> C,
     cmd: &CommandExpression,
     test_cases: &[BatchTestCase],
+    stdin_echo: bool,
+    export_env: bool,
 ) -> anyhow::Result<JudgeOutcome> {
     let cmd = Arc::new(cmd.clone());
     let num_test_cases = test_cases.len();
@@ -430,6 +575,9 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
         .tempdir()?;
     let tempdir_path = tempdir.path().to_owned();
 
+    // `println`s of the progress bars are discarded if the draw target is hidden.
+    let hidden = draw_target.is_hidden();
+
     let mp = MultiProgress::with_draw_target(draw_target);
 
     let mut targets = vec![];
@@ -509,7 +657,7 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
                     let expected = test_case.output.clone();
 
                     let cwd = &cmd.cwd;
-                    let cmd = cmd
+                    let mut cmd = cmd
                         .build(
                             (stdin.len() >= 10 * 1024).then(|| &*stdin_path),
                             &actual_stdout_path,
@@ -517,10 +665,50 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
                         )
                         .await?;
 
+                    if export_env {
+                        cmd.envs(case_env(i, test_case.name.as_deref(), test_case.timelimit));
+                    }
+
+                    // With only one test case, show the output as it comes instead of waiting
+                    // for the process to exit. The files are still written for the comparison.
+                    let stream = num_targets == 1;
+                    if stream {
+                        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+                    }
+
                     let started = Instant::now();
 
                     let mut child = { cmd }.spawn()?;
 
+                    let tees = if stream {
+                        let tee = |src, path: &Path, prefix: &'static str| {
+                            let (path, pb) = (path.to_owned(), pb_clone.clone());
+                            tokio::task::spawn(tee_lines(src, path, move |line| {
+                                pb.println(format!("\x1b[2m{}|\x1b[0m {}", prefix, line));
+                            }))
+                        };
+                        let stdout = child
+                            .stdout
+                            .take()
+                            .map(|src| tee(Box::new(src) as _, &actual_stdout_path, "stdout"));
+                        let stderr = child
+                            .stderr
+                            .take()
+                            .map(|src| tee(Box::new(src) as _, &stderr_path, "stderr"));
+                        stdout.into_iter().chain(stderr).collect()
+                    } else {
+                        vec![]
+                    };
+
+                    if stdin_echo {
+                        let echo = format!("--- INPUT ---\n{}", stdin.trim_end());
+                        if hidden {
+                            eprintln!("{}", echo);
+                        } else {
+                            pb_clone.println(echo);
+                        }
+                    }
+
                     if let Some(mut child_stdin) = child.stdin.take() {
                         child_stdin.write_all((*stdin).as_ref()).await?;
                     }
@@ -567,8 +755,12 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
 
                     let elapsed = Instant::now() - started;
 
-                    let stdout = utf8(tokio::fs::read(&actual_stdout_path).await?)?;
-                    let stderr = utf8(tokio::fs::read(&stderr_path).await?)?;
+                    for tee in tees {
+                        tee.await??;
+                    }
+
+                    let stdout = utf8(tokio::fs::read(&actual_stdout_path).await?);
+                    let stderr = utf8(tokio::fs::read(&stderr_path).await?);
 
                     if matches!(timelimit, Some(t) if t < elapsed) {
                         Ok(Verdict::TimelimitExceeded {
@@ -661,23 +853,298 @@ pub fn judge<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
 
     let outcome = rt.block_on(outcome)??;
     tempdir.close()?;
-    return Ok(outcome);
+    Ok(outcome)
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoringOutcome {
+    pub cases: Vec<ScoringCase>,
+}
 
-    fn progress_style(template: impl AsRef<str>) -> ProgressStyle {
-        ProgressStyle::default_spinner().template(template.as_ref())
+impl ScoringOutcome {
+    /// Sum of the scores. Failed cases count as 0.
+    pub fn total(&self) -> f64 {
+        self.cases
+            .iter()
+            .flat_map(|c| c.score)
+            .fold(0.0, |acc, s| acc + s)
     }
 
-    fn align_left(s: &str, n: usize) -> String {
-        let spaces = n.saturating_sub(s.width());
-        s.chars().chain(itertools::repeat_n(' ', spaces)).collect()
+    pub fn num_failures(&self) -> usize {
+        self.cases.iter().filter(|c| c.score.is_none()).count()
     }
 
-    fn align_right(s: &str, n: usize) -> String {
-        let spaces = n.saturating_sub(s.width());
-        itertools::repeat_n(' ', spaces).chain(s.chars()).collect()
+    pub fn print_pretty<W: WriteColor>(&self, mut wtr: W) -> io::Result<()> {
+        let num_cases = self.cases.len();
+
+        for (i, case) in self.cases.iter().enumerate() {
+            write!(wtr, "{}/{} ({:?}) ", i + 1, num_cases, case.name)?;
+
+            match (case.score, &case.error) {
+                (Some(score), _) => {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Green)))?;
+                    write!(wtr, "{}", score)?;
+                    wtr.reset()?;
+                }
+                (None, error) => {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+                    write!(wtr, "{}", error.as_deref().unwrap_or("Failed"))?;
+                    wtr.reset()?;
+                }
+            }
+
+            if let Some(elapsed) = case.elapsed {
+                write!(wtr, " ({} ms)", elapsed.as_millis())?;
+            }
+            writeln!(wtr)?;
+        }
+
+        wtr.flush()
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoringCase {
+    pub name: String,
+    #[serde(with = "humantime_serde")]
+    pub elapsed: Option<Duration>,
+    pub score: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Runs `cmd` for each input, then `{scorer.command} {input} {output}` to get the score.
+///
+/// A failure of a case is recorded in the outcome instead of aborting the others.
+pub fn score<C: 'static + Future<Output = tokio::io::Result<()>> + Send>(
+    draw_target: ProgressDrawTarget,
+    ctrl_c: fn() -> C,
+    cmd: &CommandExpression,
+    inputs: &[(String, PathBuf)],
+    timelimit: Option<Duration>,
+    scorer: &Scorer,
+    export_env: bool,
+) -> anyhow::Result<ScoringOutcome> {
+    let cmd = Arc::new(cmd.clone());
+    let num_cases = inputs.len();
+
+    let parse = Regex::new(&scorer.parse)
+        .with_context(|| format!("Invalid `parse`: {:?}", scorer.parse))?;
+    let (scorer_program, scorer_args) = scorer
+        .command
+        .split_first()
+        .with_context(|| "`scorer.command` requires at least one argument")?;
+    let scorer_program = Arc::new(OsString::from(scorer_program));
+    let scorer_args = Arc::new(scorer_args.to_owned());
+
+    let quoted_name_width = inputs
+        .iter()
+        .map(|(name, _)| format!("{:?}", name).width())
+        .max()
+        .unwrap_or(0);
+
+    let tempdir = tempfile::Builder::new()
+        .prefix("snowchains-core-scoring-")
+        .tempdir()?;
+    let tempdir_path = tempdir.path().to_owned();
+
+    let mp = MultiProgress::with_draw_target(draw_target);
+
+    let mut targets = vec![];
+
+    for (i, (name, path)) in inputs.iter().enumerate() {
+        let pb = mp.add(ProgressBar::new_spinner());
+
+        pb.set_style(progress_style("{prefix}{spinner} {msg:bold}"));
+
+        pb.set_prefix(&format!(
+            "{}/{} ({} ",
+            align_right(&(i + 1).to_string(), num_cases.to_string().len()),
+            num_cases,
+            align_left(&format!("{:?})", name), quoted_name_width + 1),
+        ));
+
+        pb.set_message("Running...");
+        pb.enable_steady_tick(50);
+
+        targets.push((name.clone(), path.clone(), pb));
+    }
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_io()
+        .enable_time()
+        .build()?;
+
+    let outcome = rt.spawn(async move {
+        let (ctrl_c_tx, _) = tokio::sync::broadcast::channel(1);
+
+        tokio::task::spawn({
+            let ctrl_c_tx = ctrl_c_tx.clone();
+            async move {
+                let err_msg = match ctrl_c().await {
+                    Ok(()) => "Recieved Ctrl-c".to_owned(),
+                    Err(err) => err.to_string(),
+                };
+                let _ = ctrl_c_tx.send(err_msg);
+            }
+        });
+
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(num_cpus::get()));
+
+        let mut results = vec![];
+
+        for (i, (name, input_path, pb)) in targets.into_iter().enumerate() {
+            let cmd = cmd.clone();
+            let parse = parse.clone();
+            let (scorer_program, scorer_args) = (scorer_program.clone(), scorer_args.clone());
+            let output_path = tempdir_path.join(format!("{}-output", i));
+            let stderr_path = tempdir_path.join(format!("{}-stderr", i));
+            let permit = semaphore.clone().acquire_owned().await?;
+            let mut ctrl_c_rx = ctrl_c_tx.subscribe();
+
+            results.push(tokio::task::spawn(async move {
+                let result = async {
+                    let mut command = cmd
+                        .build(Some(&input_path), &output_path, &stderr_path)
+                        .await?;
+                    if export_env {
+                        command.envs(case_env(i, Some(&name), timelimit));
+                    }
+
+                    let started = Instant::now();
+                    let mut child = command.kill_on_drop(true).spawn()?;
+
+                    let wait = async {
+                        match timelimit {
+                            Some(timelimit) => tokio::time::timeout(
+                                timelimit + Duration::from_millis(100),
+                                child.wait(),
+                            )
+                            .await
+                            .ok(),
+                            None => Some(child.wait().await),
+                        }
+                    };
+
+                    let status = select! {
+                        status = wait.fuse() => status,
+                        err_msg = ctrl_c_rx.recv().fuse() => bail!("{}", err_msg?),
+                    };
+                    let elapsed = Instant::now() - started;
+
+                    let status = match status {
+                        Some(status) if !matches!(timelimit, Some(t) if t < elapsed) => status?,
+                        _ => {
+                            return Ok((Some(elapsed), Err("Timelimit Exceeded".to_owned())));
+                        }
+                    };
+                    if !status.success() {
+                        return Ok((Some(elapsed), Err(format!("Runtime Error ({})", status))));
+                    }
+
+                    let args = scorer_args
+                        .iter()
+                        .map(OsStr::new)
+                        .chain(vec![input_path.as_os_str(), output_path.as_os_str()])
+                        .collect::<Vec<_>>();
+
+                    let Output {
+                        status,
+                        stdout,
+                        stderr,
+                    } = tokio::process::Command::new(&*scorer_program)
+                        .args(args)
+                        .envs(vec![
+                            ("INPUT", &input_path),
+                            ("ACTUAL_OUTPUT", &output_path),
+                        ])
+                        .current_dir(&cmd.cwd)
+                        .stdin(Stdio::null())
+                        .stdout(Stdio::piped())
+                        .stderr(Stdio::piped())
+                        .kill_on_drop(true)
+                        .output()
+                        .await
+                        .with_context(|| format!("Could not execute {:?}", scorer_program))?;
+
+                    if !status.success() {
+                        return Ok((Some(elapsed), Err(format!("Scorer Failed ({})", status))));
+                    }
+
+                    let text = format!("{}\n{}", utf8(stdout), utf8(stderr));
+                    Ok((
+                        Some(elapsed),
+                        parse_score(&parse, &text).ok_or_else(|| "Score Not Found".to_owned()),
+                    ))
+                }
+                .await;
+
+                drop(permit);
+
+                tokio::task::block_in_place(|| match &result {
+                    Ok((_, Ok(score))) => {
+                        pb.set_style(progress_style("{prefix}{msg:green}"));
+                        pb.finish_with_message(&score.to_string());
+                    }
+                    Ok((_, Err(msg))) => {
+                        pb.set_style(progress_style("{prefix}{msg:red}"));
+                        pb.finish_with_message(msg);
+                    }
+                    Err(err) => {
+                        pb.set_style(progress_style("{prefix}{msg}"));
+                        pb.finish_with_message(&format!("{:?}", err));
+                    }
+                });
+
+                let (elapsed, score) = result?;
+                Ok::<_, anyhow::Error>(ScoringCase {
+                    name,
+                    elapsed,
+                    score: score.as_ref().ok().copied(),
+                    error: score.err(),
+                })
+            }));
+        }
+
+        let mut cases = vec![];
+        for result in results {
+            cases.push(result.await??);
+        }
+        Ok::<_, anyhow::Error>(ScoringOutcome { cases })
+    });
+
+    mp.join()?;
+
+    let outcome = rt.block_on(outcome)??;
+    tempdir.close()?;
+    Ok(outcome)
+}
+
+/// Takes the first capture group (or the whole match) of `parse` as a number.
+fn parse_score(parse: &Regex, text: &str) -> Option<f64> {
+    let caps = parse.captures(text)?;
+    let m = caps.get(1).or_else(|| caps.get(0))?;
+    m.as_str().trim().parse().ok()
+}
+
+fn progress_style(template: impl AsRef<str>) -> ProgressStyle {
+    ProgressStyle::default_spinner().template(template.as_ref())
+}
+
+fn align_left(s: &str, n: usize) -> String {
+    let spaces = n.saturating_sub(s.width());
+    s.chars().chain(itertools::repeat_n(' ', spaces)).collect()
+}
+
+fn align_right(s: &str, n: usize) -> String {
+    let spaces = n.saturating_sub(s.width());
+    itertools::repeat_n(' ', spaces).chain(s.chars()).collect()
+}
+
+/// Time limit for a checker. The time limit of a test case is for the solution, not for the
+/// checker.
+const CHECKER_TIMEOUT: Duration = Duration::from_secs(10);
+
 async fn check(
     expected: &ExpectedOutput,
     actual: &str,
@@ -687,55 +1154,404 @@ async fn check(
     expected_stdout_path: &Path,
     bash_exe: &Path,
 ) -> anyhow::Result<Result<(), (Arc<str>, Arc<str>, Option<WrongAnswerNote>)>> {
-    match expected {
-        ExpectedOutput::Deterministic(expected) => Ok(if expected.accepts(actual) {
-            Ok(())
-        } else {
-            let note = expected
-                .expected_stdout()
-                .filter(|expected| expected.split_whitespace().eq(actual.split_whitespace()))
-                .map(|_| WrongAnswerNote::WordsMatched);
-            Err((Arc::from(""), Arc::from(""), note))
-        }),
+    let (program, args, env_vars, cwd) = match expected {
+        ExpectedOutput::Deterministic(expected) => {
+            return Ok(if expected.accepts(actual) {
+                Ok(())
+            } else {
+                let note =
+                    if let Some(FuzzyMatchResult { details, .. }) = expected.fuzzy_match(actual) {
+                        Some(WrongAnswerNote::FloatMismatch(details))
+                    } else {
+                        expected
+                            .expected_stdout()
+                            .filter(|expected| {
+                                expected.split_whitespace().eq(actual.split_whitespace())
+                            })
+                            .map(|_| WrongAnswerNote::WordsMatched)
+                    };
+                Err((Arc::from(""), Arc::from(""), note))
+            });
+        }
         ExpectedOutput::Checker { text, cmd, shell } => {
-            let (program, args) = match shell {
-                CheckerShell::Bash => (bash_exe, [OsStr::new("-c"), OsStr::new(cmd)]),
-            };
-
             let mut env_vars = vec![("INPUT", stdin_path), ("ACTUAL_OUTPUT", actual_stdout_path)];
             if let Some(text) = text {
                 tokio::fs::write(expected_stdout_path, text.as_ref()).await?;
                 env_vars.push(("EXPECTED_OUTPUT", expected_stdout_path));
             }
 
-            let Output {
-                status,
-                stdout,
-                stderr,
-            } = tokio::process::Command::new(program)
-                .args(&args)
-                .envs(env_vars)
-                .current_dir(cwd)
-                .stdin(Stdio::null())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .kill_on_drop(true)
-                .output()
-                .await?;
-
-            let (stdout, stderr) = (utf8(stdout)?, utf8(stderr)?);
-
-            Ok(if status.success() {
-                Ok(())
-            } else {
-                Err((stdout, stderr, None))
-            })
+            let (program, args) = match shell {
+                CheckerShell::Bash => (bash_exe.as_os_str(), vec![OsStr::new("-c"), cmd.as_ref()]),
+            };
+
+            (program, args, env_vars, cwd.to_owned())
+        }
+        ExpectedOutput::CheckerCommand {
+            text,
+            command,
+            working_directory,
+        } => {
+            let text = text.as_deref().unwrap_or("");
+            tokio::fs::write(expected_stdout_path, text).await?;
+
+            let env_vars = vec![
+                ("INPUT", stdin_path),
+                ("ACTUAL_OUTPUT", actual_stdout_path),
+                ("EXPECTED_OUTPUT", expected_stdout_path),
+            ];
+
+            let (program, args) = command
+                .split_first()
+                .with_context(|| "`CheckerCommand` requires at least one argument")?;
+            let args = checker_args(args, stdin_path, expected_stdout_path, actual_stdout_path);
+
+            let cwd = match working_directory {
+                Some(working_directory) => cwd.join(working_directory),
+                None => cwd.to_owned(),
+            };
+
+            (program.as_ref(), args, env_vars, cwd)
         }
+    };
+
+    run_checker(program, &args, env_vars, &cwd, CHECKER_TIMEOUT).await
+}
+
+/// Replaces `$in`, `$expected`, and `$actual` in `args` with the paths, or appends the paths in
+/// this order if none of them are found.
+fn checker_args<'a>(
+    args: &'a [String],
+    input: &'a Path,
+    expected: &'a Path,
+    actual: &'a Path,
+) -> Vec<&'a OsStr> {
+    let placeholder = |arg: &str| match arg {
+        "$in" => Some(input.as_os_str()),
+        "$expected" => Some(expected.as_os_str()),
+        "$actual" => Some(actual.as_os_str()),
+        _ => None,
+    };
+
+    if args.iter().any(|arg| placeholder(arg).is_some()) {
+        args.iter()
+            .map(|arg| placeholder(arg).unwrap_or_else(|| arg.as_ref()))
+            .collect()
+    } else {
+        args.iter()
+            .map(AsRef::as_ref)
+            .chain(vec![
+                input.as_os_str(),
+                expected.as_os_str(),
+                actual.as_os_str(),
+            ])
+            .collect()
+    }
+}
+
+/// Runs a checker, killing it if it does not finish in `timeout`.
+async fn run_checker(
+    program: &OsStr,
+    args: &[&OsStr],
+    env_vars: Vec<(&str, &Path)>,
+    cwd: &Path,
+    timeout: Duration,
+) -> anyhow::Result<Result<(), (Arc<str>, Arc<str>, Option<WrongAnswerNote>)>> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .envs(env_vars)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output();
+
+    let Output {
+        status,
+        stdout,
+        stderr,
+    } = tokio::time::timeout(timeout, output)
+        .await
+        .map_err(|_| anyhow!("The checker {:?} did not finish in {:?}", program, timeout))?
+        .with_context(|| format!("Could not execute {:?}", program))?;
+
+    let (stdout, stderr) = (utf8(stdout), utf8(stderr));
+
+    Ok(if status.success() {
+        Ok(())
+    } else {
+        Err((stdout, stderr, None))
+    })
+}
+
+/// Copies `src` to `path`, calling `on_line` for each line as soon as it is read.
+async fn tee_lines(
+    src: Box<dyn AsyncRead + Send + Unpin>,
+    path: PathBuf,
+    mut on_line: impl FnMut(&str),
+) -> io::Result<()> {
+    let mut src = tokio::io::BufReader::new(src);
+    let mut file = tokio::fs::File::create(path).await?;
+    let mut buf = vec![];
+    while src.read_until(b'\n', &mut buf).await? > 0 {
+        file.write_all(&buf).await?;
+        on_line(String::from_utf8_lossy(&buf).trim_end_matches(&['\r', '\n'][..]));
+        buf.clear();
     }
+    file.flush().await
 }
 
-fn utf8(bytes: Vec<u8>) -> anyhow::Result<Arc<str>> {
-    String::from_utf8(bytes)
-        .map(Into::into)
-        .map_err(|_| anyhow!("the output was not a valid UTF-8 string"))
+/// Decodes the output leniently.
+///
+/// A leading BOM is removed and invalid sequences (e.g. Shift_JIS from a Windows toolchain) are
+/// replaced with U+FFFD so that they are shown instead of aborting the whole judge.
+fn utf8(bytes: Vec<u8>) -> Arc<str> {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&bytes);
+    String::from_utf8_lossy(bytes).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        judge::CommandExpression,
+        testsuite::{BatchTestCase, DeterministicExpectedOutput, ExpectedOutput, TestCaseOrigin},
+    };
+    use indicatif::ProgressDrawTarget;
+    use std::{
+        collections::BTreeMap,
+        path::Path,
+        process::Stdio,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn checker_args() {
+        let (input, expected, actual) = (Path::new("in"), Path::new("exp"), Path::new("act"));
+        let args = |args: &[&str]| {
+            let args = args.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+            super::checker_args(&args, input, expected, actual)
+                .into_iter()
+                .map(|s| s.to_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(["-v", "in", "exp", "act"], *args(&["-v"]));
+        assert_eq!(["act", "-v", "in"], *args(&["$actual", "-v", "$in"]));
+    }
+
+    #[test]
+    fn parse_score() {
+        let parse = |re, text| super::parse_score(&regex::Regex::new(re).unwrap(), text);
+        assert_eq!(Some(1234.0), parse(r"Score = (\d+)", "Score = 1234\n"));
+        assert_eq!(Some(-0.5), parse(r"-?[0-9.]+", "-0.5\n"));
+        assert_eq!(None, parse(r"Score = (\d+)", "WA\n"));
+    }
+
+    #[test]
+    fn utf8() {
+        assert_eq!("1\n", &*super::utf8(b"\xef\xbb\xbf1\n".to_vec()));
+        // "テスト" in Shift_JIS
+        assert_eq!(
+            "\u{fffd}e\u{fffd}X\u{fffd}g\n",
+            &*super::utf8(b"\x83e\x83X\x83g\n".to_vec()),
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tee_lines() -> anyhow::Result<()> {
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-core-judge-tests-")
+            .tempdir()?;
+        let path = tempdir.path().join("stdout");
+
+        let emitted = Arc::new(Mutex::new(vec![]));
+
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_io()
+            .enable_time()
+            .build()?
+            .block_on({
+                let (path, emitted) = (path.clone(), emitted.clone());
+                async move {
+                    let mut child = tokio::process::Command::new("sh")
+                        .arg("-c")
+                        .arg("echo a; sleep 0.5; echo b")
+                        .stdout(Stdio::piped())
+                        .spawn()?;
+                    let stdout = child.stdout.take().unwrap();
+
+                    super::tee_lines(Box::new(stdout), path, |line| {
+                        emitted
+                            .lock()
+                            .unwrap()
+                            .push((line.to_owned(), Instant::now()));
+                    })
+                    .await?;
+
+                    child.wait().await?;
+                    Ok::<_, anyhow::Error>(())
+                }
+            })?;
+
+        let emitted = emitted.lock().unwrap();
+        assert_eq!(
+            ["a", "b"],
+            *emitted.iter().map(|(s, _)| &**s).collect::<Vec<_>>(),
+        );
+        assert!(emitted[1].1 - emitted[0].1 >= Duration::from_millis(300));
+        assert_eq!("a\nb\n", std::fs::read_to_string(path)?);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn export_env() -> anyhow::Result<()> {
+        let test_case = |name: &str, output: &str| BatchTestCase {
+            name: Some(name.to_owned()),
+            timelimit: Some(Duration::from_millis(2000)),
+            input: "".into(),
+            output: ExpectedOutput::Deterministic(DeterministicExpectedOutput::Exact {
+                text: output.into(),
+            }),
+            origin: TestCaseOrigin::Sample,
+            note: None,
+        };
+
+        let cmd = CommandExpression {
+            program: "sh".into(),
+            args: vec![
+                "-c".into(),
+                "echo $SNOWCHAINS_JUDGE $SNOWCHAINS_CASE_INDEX $SNOWCHAINS_CASE_NAME \
+                 $SNOWCHAINS_TIMELIMIT_MS"
+                    .into(),
+            ],
+            cwd: std::env::current_dir()?,
+            env: BTreeMap::new(),
+        };
+
+        let judge = |test_cases: &[_], export_env| {
+            super::judge(
+                ProgressDrawTarget::hidden(),
+                tokio::signal::ctrl_c,
+                &cmd,
+                test_cases,
+                false,
+                export_env,
+            )
+        };
+
+        let outcome = judge(
+            &[
+                test_case("a", "1 1 a 2000\n"),
+                test_case("b", "1 2 b 2000\n"),
+            ],
+            true,
+        )?;
+        outcome.error_on_fail()?;
+
+        let outcome = judge(&[test_case("a", "\n")], false)?;
+        outcome.error_on_fail()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn checker() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let tempdir = tempfile::Builder::new()
+            .prefix("snowchains-core-judge-tests-")
+            .tempdir()?;
+
+        let write_checker = |name: &str, content: &str| -> anyhow::Result<_> {
+            let path = tempdir.path().join(name);
+            std::fs::write(&path, content)?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+            Ok(path.to_str().unwrap().to_owned())
+        };
+
+        // Accepts any output that has as many lines as the expected one.
+        let lines = write_checker(
+            "lines.sh",
+            "#!/bin/sh\n[ \"$(wc -l < \"$2\")\" = \"$(wc -l < \"$3\")\" ]\n",
+        )?;
+        let reject = write_checker("reject.sh", "#!/bin/sh\nexit 1\n")?;
+
+        let test_case = |checker: &str| BatchTestCase {
+            name: Some("a".to_owned()),
+            timelimit: Some(Duration::from_millis(2000)),
+            input: "".into(),
+            output: ExpectedOutput::CheckerCommand {
+                text: Some("1 2\n".into()),
+                command: vec![checker.to_owned()],
+                working_directory: None,
+            },
+            origin: TestCaseOrigin::Sample,
+            note: None,
+        };
+
+        let cmd = CommandExpression {
+            program: "sh".into(),
+            args: vec!["-c".into(), "echo 2 1".into()],
+            cwd: std::env::current_dir()?,
+            env: BTreeMap::new(),
+        };
+
+        let judge = |test_cases: &[_]| {
+            super::judge(
+                ProgressDrawTarget::hidden(),
+                tokio::signal::ctrl_c,
+                &cmd,
+                test_cases,
+                false,
+                false,
+            )
+        };
+
+        judge(&[test_case(&lines)])?.error_on_fail()?;
+        assert!(judge(&[test_case(&reject)])?.error_on_fail().is_err());
+
+        let in_working_directory = BatchTestCase {
+            output: ExpectedOutput::CheckerCommand {
+                text: Some("1 2\n".into()),
+                command: ["./lines.sh", "$in", "$expected", "$actual"]
+                    .iter()
+                    .map(|&s| s.to_owned())
+                    .collect(),
+                working_directory: Some(tempdir.path().to_str().unwrap().into()),
+            },
+            ..test_case(&lines)
+        };
+        judge(&[in_working_directory])?.error_on_fail()?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_checker_timeout() -> anyhow::Result<()> {
+        let cwd = std::env::current_dir()?;
+
+        let run = |secs: &str| {
+            let (cwd, secs) = (cwd.clone(), secs.to_owned());
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_io()
+                .enable_time()
+                .build()?
+                .block_on(async move {
+                    super::run_checker(
+                        "sleep".as_ref(),
+                        &[secs.as_ref()],
+                        vec![],
+                        &cwd,
+                        Duration::from_millis(500),
+                    )
+                    .await
+                })
+        };
+
+        assert!(run("0")?.is_ok());
+        assert!(run("10").is_err());
+        Ok(())
+    }
 }
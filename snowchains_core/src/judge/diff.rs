@@ -0,0 +1,188 @@
+//! Line-based unified diffs for `Wrong Answer`.
+
+use std::cmp;
+
+/// Larger outputs are not diffed since the LCS table takes `O(n * m)` space.
+const MAX_CELLS: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Line<'a> {
+    Equal(&'a str),
+    /// Only in the expected output.
+    Delete(&'a str),
+    /// Only in the actual output.
+    Insert(&'a str),
+}
+
+impl Line<'_> {
+    fn in_expected(&self) -> bool {
+        matches!(self, Self::Equal(_) | Self::Delete(_))
+    }
+
+    fn in_actual(&self) -> bool {
+        matches!(self, Self::Equal(_) | Self::Insert(_))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Hunk<'a> {
+    pub(super) header: String,
+    pub(super) lines: Vec<Line<'a>>,
+}
+
+/// Splits the diff of `expected` and `actual` into hunks with `context` lines around changes.
+///
+/// `None` if the texts are too large. Empty if they have the same lines.
+pub(super) fn unified<'a>(
+    expected: &'a str,
+    actual: &'a str,
+    context: usize,
+) -> Option<Vec<Hunk<'a>>> {
+    let lines = diff_lines(expected, actual)?;
+
+    let mut ranges = Vec::<(usize, usize)>::new();
+
+    for (i, _) in lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, Line::Equal(_)))
+    {
+        let (start, end) = (
+            i.saturating_sub(context),
+            cmp::min(i + context + 1, lines.len()),
+        );
+
+        match ranges.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let hunks = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let count = |lines: &[Line<'a>]| {
+                (
+                    lines.iter().filter(|l| l.in_expected()).count(),
+                    lines.iter().filter(|l| l.in_actual()).count(),
+                )
+            };
+
+            let range = |start: usize, len: usize| {
+                // Like GNU diff, an empty range starts at the line before it.
+                let start = if len == 0 { start - 1 } else { start };
+                format!("{},{}", start, len)
+            };
+
+            let (expected_before, actual_before) = count(&lines[..start]);
+            let (expected_len, actual_len) = count(&lines[start..end]);
+
+            let header = format!(
+                "@@ -{} +{} @@",
+                range(expected_before + 1, expected_len),
+                range(actual_before + 1, actual_len),
+            );
+
+            Hunk {
+                header,
+                lines: lines[start..end].to_vec(),
+            }
+        })
+        .collect();
+
+    Some(hunks)
+}
+
+fn diff_lines<'a>(expected: &'a str, actual: &'a str) -> Option<Vec<Line<'a>>> {
+    let xs = expected.lines().collect::<Vec<_>>();
+    let ys = actual.lines().collect::<Vec<_>>();
+    let (n, m) = (xs.len(), ys.len());
+
+    if (n + 1).checked_mul(m + 1)? > MAX_CELLS {
+        return None;
+    }
+
+    // `lcs[i * (m + 1) + j]`: length of the LCS of `xs[i..]` and `ys[j..]`
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[at(i, j)] = if xs[i] == ys[j] {
+                lcs[at(i + 1, j + 1)] + 1
+            } else {
+                cmp::max(lcs[at(i + 1, j)], lcs[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(cmp::max(n, m));
+    let (mut i, mut j) = (0, 0);
+
+    while i < n || j < m {
+        if i < n && j < m && xs[i] == ys[j] {
+            lines.push(Line::Equal(xs[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[at(i + 1, j)] >= lcs[at(i, j + 1)]) {
+            lines.push(Line::Delete(xs[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Insert(ys[j]));
+            j += 1;
+        }
+    }
+
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hunk, Line};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unified() {
+        assert_eq!(Some(vec![]), super::unified("1\n2\n", "1\n2\n", 3));
+
+        assert_eq!(
+            Some(vec![Hunk {
+                header: "@@ -1,3 +1,3 @@".to_owned(),
+                lines: vec![
+                    Line::Equal("1"),
+                    Line::Delete("2"),
+                    Line::Insert("20"),
+                    Line::Equal("3"),
+                ],
+            }]),
+            super::unified("1\n2\n3\n", "1\n20\n3\n", 3),
+        );
+
+        let expected = (1..=20).map(|i| format!("{}\n", i)).collect::<String>();
+        let actual = expected
+            .replace("\n2\n", "\n")
+            .replace("\n19\n", "\n19\n190\n");
+
+        assert_eq!(
+            Some(vec![
+                Hunk {
+                    header: "@@ -1,3 +1,2 @@".to_owned(),
+                    lines: vec![Line::Equal("1"), Line::Delete("2"), Line::Equal("3")],
+                },
+                Hunk {
+                    header: "@@ -19,2 +18,3 @@".to_owned(),
+                    lines: vec![Line::Equal("19"), Line::Insert("190"), Line::Equal("20"),],
+                },
+            ]),
+            super::unified(&expected, &actual, 1),
+        );
+
+        assert_eq!(
+            Some(vec![Hunk {
+                header: "@@ -0,0 +1,1 @@".to_owned(),
+                lines: vec![Line::Insert("1")],
+            }]),
+            super::unified("", "1\n", 3),
+        );
+    }
+}
@@ -39,5 +39,6 @@ macro_rules! _color_spec_inner {
 }
 
 pub mod judge;
+pub mod terminal;
 pub mod testsuite;
 pub mod web;
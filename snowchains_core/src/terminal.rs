@@ -0,0 +1,45 @@
+//! Text for terminals.
+
+use std::{borrow::Cow, fmt::Write as _};
+
+/// Escapes control characters other than `\n` and `\t` so that scraped text cannot move the
+/// cursor or change the terminal state.
+///
+/// `\r` is shown as `\r`, and the others as `\x1b`-style escapes. Text written to files should
+/// not go through this.
+pub fn escape_control_chars(s: &str) -> Cow<'_, str> {
+    let is_target = |c: char| c.is_control() && c != '\n' && c != '\t';
+
+    if !s.contains(is_target) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut escaped = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        match c {
+            '\r' => escaped.push_str("\\r"),
+            c if is_target(c) => write!(escaped, "\\x{:02x}", u32::from(c)).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn escape_control_chars() {
+        assert_eq!("1 2\n\t3\n", super::escape_control_chars("1 2\n\t3\n"));
+        assert_eq!("1\\r2\\r3\\r", super::escape_control_chars("1\r2\r3\r"));
+        assert_eq!(
+            "\\x1b[2J\\x1b[31m1 2\\x1b[0m\n",
+            super::escape_control_chars("\x1b[2J\x1b[31m1 2\x1b[0m\n"),
+        );
+        assert_eq!(
+            "\\x00\\x7f\\x9b",
+            super::escape_control_chars("\0\x7f\u{9b}")
+        );
+    }
+}
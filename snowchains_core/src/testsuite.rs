@@ -1,4 +1,4 @@
-use anyhow::{bail, ensure, Context as _};
+use anyhow::{anyhow, bail, ensure, Context as _};
 use camino::Utf8PathBuf;
 use humantime_serde::Serde;
 use ignore::{overrides::OverrideBuilder, WalkBuilder};
@@ -10,7 +10,7 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs,
     hash::Hash,
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
     time::Duration,
@@ -23,6 +23,7 @@ pub enum TestSuite {
     Batch(BatchTestSuite),
     Interactive(InteractiveTestSuite),
     Unsubmittable,
+    Scoring(ScoringTestSuite),
 }
 
 impl TestSuite {
@@ -35,6 +36,10 @@ impl TestSuite {
                 yaml += &key_value("timelimit", Serde::from(suite.timelimit)).ok()?;
                 yaml += &key_value("match", &suite.r#match).ok()?;
 
+                if let Some(generator) = &suite.generator {
+                    yaml += &key_value("generator", generator).ok()?;
+                }
+
                 yaml += if suite.cases.is_empty() {
                     "\ncases: []\n"
                 } else {
@@ -62,6 +67,14 @@ impl TestSuite {
                         part += &key_value("match", r#match).ok()?;
                     }
 
+                    if let Some(origin) = case.origin {
+                        part += &key_value("origin", origin).ok()?;
+                    }
+
+                    if let Some(note) = &case.note {
+                        part += &key_value("note", note).ok()?;
+                    }
+
                     for (i, line) in part.lines().enumerate() {
                         yaml += match i {
                             0 => "  - ",
@@ -154,6 +167,14 @@ pub struct BatchTestSuite {
     #[serde(default, with = "humantime_serde")]
     pub timelimit: Option<Duration>,
     pub r#match: Match,
+    /// Compares the numbers in the output with this tolerance, unless `match` is `Float`,
+    /// `Checker`, or `CheckerCommand`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub float_tolerance: Option<PositiveFinite<f64>>,
+    /// Command that prints a random input, run in the workspace with the iteration index
+    /// appended. Used by `generate` and `stress`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<Vec<String>>,
     #[serde(default)]
     pub cases: Vec<PartialBatchTestCase>,
     #[serde(default)]
@@ -183,7 +204,9 @@ impl BatchTestSuite {
                     _ => true,
                 },
             )
-            .map(|case| BatchTestCase::new(case, self.timelimit, &self.r#match))
+            .map(|case| {
+                BatchTestCase::new(case, self.timelimit, &self.r#match, self.float_tolerance)
+            })
             .collect();
 
         if let Some(names) = names {
@@ -197,6 +220,89 @@ impl BatchTestSuite {
 
         Ok(cases)
     }
+
+    /// Makes `working_directory` of each `CheckerCommand` absolute, regarding it as relative to
+    /// `workspace`.
+    pub fn resolve_checker(&mut self, workspace: &Path) -> anyhow::Result<()> {
+        let working_directories = self
+            .cases
+            .iter_mut()
+            .flat_map(|case| &mut case.r#match)
+            .chain(
+                self.extend
+                    .iter_mut()
+                    .flat_map(|additional| match additional {
+                        Additional::Text { r#match, .. } => r#match.as_mut(),
+                        Additional::SystemTestCases { .. } => None,
+                    }),
+            )
+            .chain(Some(&mut self.r#match))
+            .flat_map(|r#match| match r#match {
+                Match::CheckerCommand {
+                    working_directory, ..
+                } => working_directory.as_mut(),
+                _ => None,
+            });
+
+        for path in working_directories {
+            *path = Utf8PathBuf::from_path_buf(workspace.join(&*path))
+                .map_err(|path| anyhow!("Non UTF-8 path: {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// Locates the input and the output of each test case without reading the text files.
+    ///
+    /// The order is the same as [`load_test_cases`]: `cases` first, then each of `extend` in
+    /// order. The files of a `Text` are ordered by their names. `SystemTestCases` are not
+    /// supported.
+    ///
+    /// [`load_test_cases`]: BatchTestSuite::load_test_cases
+    pub fn test_case_sources(&self, parent_dir: &Path) -> anyhow::Result<Vec<TestCaseSource>> {
+        let mut sources = self
+            .cases
+            .iter()
+            .map(|case| TestCaseSource {
+                name: case.name.clone(),
+                r#in: TextSource::Inline(case.r#in.clone()),
+                out: case.out.clone().map(TextSource::Inline),
+            })
+            .collect::<Vec<_>>();
+
+        for extend in &self.extend {
+            match extend {
+                Additional::Text {
+                    path, r#in, out, ..
+                } => {
+                    for (name, (in_path, out_path)) in text_file_paths(parent_dir, path, r#in, out)?
+                    {
+                        sources.push(TestCaseSource {
+                            name: Some(name),
+                            r#in: TextSource::File(in_path),
+                            out: out_path.map(TextSource::File),
+                        });
+                    }
+                }
+                Additional::SystemTestCases { .. } => bail!("`SystemTestCases` is not supported"),
+            }
+        }
+
+        Ok(sources)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCaseSource {
+    pub name: Option<String>,
+    pub r#in: TextSource,
+    pub out: Option<TextSource>,
+}
+
+/// Text written in the YAML, or a path to a text file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextSource {
+    Inline(Arc<str>),
+    File(PathBuf),
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -209,6 +315,34 @@ pub struct PartialBatchTestCase {
     #[serde(default, with = "humantime_serde")]
     pub timelimit: Option<Duration>,
     pub r#match: Option<Match>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<TestCaseOrigin>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Where a test case came from.
+///
+/// Untagged cases in `cases` are considered to be samples and untagged ones from `extend` to be
+/// system test cases, since those are what `retrieve testcases` wrote before the tags existed.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TestCaseOrigin {
+    Sample,
+    System,
+    Manual,
+    Generated,
+}
+
+impl TestCaseOrigin {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sample => "sample",
+            Self::System => "system",
+            Self::Manual => "manual",
+            Self::Generated => "generated",
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
@@ -226,6 +360,8 @@ pub enum Additional {
         timelimit: Option<Duration>,
         #[serde(skip_serializing_if = "Option::is_none")]
         r#match: Option<Match>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        origin: Option<TestCaseOrigin>,
     },
     SystemTestCases {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -248,77 +384,103 @@ impl Additional {
                 out,
                 r#match,
                 timelimit,
-            } => {
-                let base = Path::new(base);
-                let base = parent_dir.join(base.strip_prefix(".").unwrap_or(base));
-                let base = base.strip_prefix(".").unwrap_or(&base);
-
-                let mut cases = BTreeMap::<_, (Option<_>, Option<_>)>::new();
-
-                let walk = |overrides| -> _ {
-                    WalkBuilder::new(base)
-                        .max_depth(Some(128))
-                        .overrides(overrides)
-                        .standard_filters(false)
-                        .build()
-                        .map::<anyhow::Result<_>, _>(|entry| {
-                            let path = entry?.into_path();
-
-                            if path.is_dir() {
-                                return Ok(None);
-                            }
-
-                            let name = path
-                                .file_stem()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .into_owned();
+                origin,
+            } => text_file_paths(parent_dir, base, r#in, out)?
+                .into_iter()
+                .map(|(name, (in_path, out_path))| {
+                    let read = |path: &Path| -> anyhow::Result<Arc<str>> {
+                        fs::read_to_string(path)
+                            .map(Into::into)
+                            .with_context(|| format!("Could not read {}", path.display()))
+                    };
+
+                    Ok(PartialBatchTestCase {
+                        name: Some(name),
+                        r#in: read(&in_path)?,
+                        out: out_path.as_deref().map(read).transpose()?,
+                        timelimit: *timelimit,
+                        r#match: r#match.clone(),
+                        origin: Some(origin.unwrap_or(TestCaseOrigin::System)),
+                        note: None,
+                    })
+                })
+                .collect(),
+            Self::SystemTestCases { problem } => {
+                let mut cases = prepare_system_test_cases(problem.as_ref())?;
+                for case in &mut cases {
+                    case.origin.get_or_insert(TestCaseOrigin::System);
+                }
+                Ok(cases)
+            }
+        }
+    }
+}
 
-                            let content = fs::read_to_string(&path)
-                                .with_context(|| format!("Could not read {}", path.display()))?
-                                .into();
+/// Lists the input and output files of `Additional::Text` by name, in the name order.
+fn text_file_paths(
+    parent_dir: &Path,
+    base: &Utf8PathBuf,
+    r#in: &str,
+    out: &str,
+) -> anyhow::Result<BTreeMap<String, (PathBuf, Option<PathBuf>)>> {
+    let base = Path::new(base);
+    let base = parent_dir.join(base.strip_prefix(".").unwrap_or(base));
+    let base = base.strip_prefix(".").unwrap_or(&base);
+
+    let mut paths = BTreeMap::<_, (Option<_>, Option<_>)>::new();
+
+    for result in walk_files(base, r#in)? {
+        let (name, path) = result?;
+        let (entry, _) = paths.entry(name.clone()).or_default();
+        ensure!(entry.is_none(), "Duplicated name: {:?}", name);
+        *entry = Some(path);
+    }
 
-                            Ok(Some((name, content)))
-                        })
-                        .flat_map(Result::transpose)
-                };
+    for result in walk_files(base, out)? {
+        let (name, path) = result?;
+        let (_, entry) = paths.entry(name.clone()).or_default();
+        ensure!(entry.is_none(), "Duplicated name: {:?}", name);
+        *entry = Some(path);
+    }
 
-                for result in walk(OverrideBuilder::new(base).add(r#in)?.build()?) {
-                    let (name, content) = result?;
-                    let (entry, _) = cases.entry(name.clone()).or_default();
-                    ensure!(entry.is_none(), "Duplicated name: {:?}", name);
-                    *entry = Some(content);
-                }
+    paths
+        .into_iter()
+        .map(|kv| match kv {
+            (_, (None, None)) => unreachable!(),
+            (name, (None, Some(_))) => bail!("No input file for {:?}", name),
+            (name, (Some(r#in), out)) => Ok((name, (r#in, out))),
+        })
+        .collect()
+}
 
-                for result in walk(OverrideBuilder::new(base).add(out)?.build()?) {
-                    let (name, content) = result?;
-                    let (_, entry) = cases.entry(name.clone()).or_default();
-                    ensure!(entry.is_none(), "Duplicated name: {:?}", name);
-                    *entry = Some(content);
-                }
+/// Walks the files under `base` matching `glob`, paired with their file stems.
+fn walk_files(
+    base: &Path,
+    glob: &str,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<(String, PathBuf)>>> {
+    let overrides = OverrideBuilder::new(base).add(glob)?.build()?;
+
+    Ok(WalkBuilder::new(base)
+        .max_depth(Some(128))
+        .overrides(overrides)
+        .standard_filters(false)
+        .build()
+        .map::<anyhow::Result<_>, _>(|entry| {
+            let path = entry?.into_path();
+
+            if path.is_dir() {
+                return Ok(None);
+            }
 
-                cases
-                    .into_iter()
-                    .map(|kv| {
-                        let (name, r#in, out) = match kv {
-                            (_, (None, None)) => unreachable!(),
-                            (name, (None, Some(_))) => bail!("No input file for {:?}", name),
-                            (name, (Some(r#in), out)) => (name, r#in, out),
-                        };
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
 
-                        Ok(PartialBatchTestCase {
-                            name: Some(name),
-                            r#in,
-                            out,
-                            timelimit: *timelimit,
-                            r#match: r#match.clone(),
-                        })
-                    })
-                    .collect()
-            }
-            Self::SystemTestCases { problem } => prepare_system_test_cases(problem.as_ref()),
-        }
-    }
+            Ok(Some((name, path)))
+        })
+        .flat_map(Result::transpose))
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -334,6 +496,17 @@ pub enum Match {
         cmd: String,
         shell: CheckerShell,
     },
+    /// Runs `command` in `working_directory`, which is relative to the workspace, and accepts if
+    /// it exits with 0.
+    ///
+    /// `$in`, `$expected`, and `$actual` in `command` are replaced with the paths of the input,
+    /// the expected output, and the actual output. Without them, the paths are appended in this
+    /// order. The expected output file is empty when the test case has no `out`.
+    CheckerCommand {
+        command: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        working_directory: Option<Utf8PathBuf>,
+    },
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
@@ -347,21 +520,91 @@ pub struct InteractiveTestSuite {
     pub timelimit: Option<Duration>,
 }
 
+/// A test suite for score-maximization problems (e.g. AtCoder Heuristic Contest).
+///
+/// Each output is given to the `scorer` instead of being compared with an expected one.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct ScoringTestSuite {
+    #[serde(default, with = "humantime_serde")]
+    pub timelimit: Option<Duration>,
+    /// Directory of the input files, relative to the test suite file.
+    pub path: Utf8PathBuf,
+    /// Glob for the input files.
+    pub r#in: String,
+    pub scorer: Scorer,
+}
+
+impl ScoringTestSuite {
+    /// Lists the input files by name, in the name order.
+    pub fn input_paths(&self, parent_dir: &Path) -> anyhow::Result<Vec<(String, PathBuf)>> {
+        let base = Path::new(&self.path);
+        let base = parent_dir.join(base.strip_prefix(".").unwrap_or(base));
+        let base = base.strip_prefix(".").unwrap_or(&base);
+
+        let mut paths = BTreeMap::new();
+        for result in walk_files(base, &self.r#in)? {
+            let (name, path) = result?;
+            ensure!(
+                paths.insert(name.clone(), path).is_none(),
+                "Duplicated name: {:?}",
+                name,
+            );
+        }
+        Ok(paths.into_iter().collect())
+    }
+}
+
+/// Runs `{command} {input} {output}` and takes the score from its stdout and stderr.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Scorer {
+    pub command: Vec<String>,
+    /// Regex whose first capture group (or the whole match) is the score.
+    pub parse: String,
+    /// Whether a smaller total is better.
+    #[serde(default)]
+    pub minimize: bool,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct BatchTestCase {
     pub name: Option<String>,
     pub timelimit: Option<Duration>,
     pub input: Arc<str>,
     pub output: ExpectedOutput,
+    pub origin: TestCaseOrigin,
+    /// e.g. the explanation of a sample
+    pub note: Option<String>,
 }
 
 impl BatchTestCase {
-    fn new(case: PartialBatchTestCase, timelimit: Option<Duration>, matching: &Match) -> Self {
+    /// Whether the input or the expected output starts with a BOM.
+    pub fn has_bom(&self) -> bool {
+        self.input.starts_with('\u{feff}')
+            || matches!(
+                self.output.expected_stdout().or_else(|| self.output.example()),
+                Some(s) if s.starts_with('\u{feff}')
+            )
+    }
+
+    fn new(
+        case: PartialBatchTestCase,
+        timelimit: Option<Duration>,
+        matching: &Match,
+        float_tolerance: Option<PositiveFinite<f64>>,
+    ) -> Self {
+        let mut output =
+            ExpectedOutput::new(case.out, case.r#match.unwrap_or_else(|| matching.clone()));
+        if let Some(tolerance) = float_tolerance {
+            output.tolerate_float_errors(tolerance);
+        }
+
         BatchTestCase {
             name: case.name,
             timelimit: case.timelimit.or(timelimit),
             input: case.r#in,
-            output: ExpectedOutput::new(case.out, case.r#match.unwrap_or_else(|| matching.clone())),
+            output,
+            origin: case.origin.unwrap_or(TestCaseOrigin::Sample),
+            note: case.note,
         }
     }
 }
@@ -374,12 +617,28 @@ pub enum ExpectedOutput {
         cmd: String,
         shell: CheckerShell,
     },
+    CheckerCommand {
+        text: Option<Arc<str>>,
+        command: Vec<String>,
+        working_directory: Option<Utf8PathBuf>,
+    },
 }
 
 impl ExpectedOutput {
     fn new(text: Option<Arc<str>>, matching: Match) -> Self {
         match (text, matching) {
             (text, Match::Checker { cmd, shell }) => Self::Checker { text, cmd, shell },
+            (
+                text,
+                Match::CheckerCommand {
+                    command,
+                    working_directory,
+                },
+            ) => Self::CheckerCommand {
+                text,
+                command,
+                working_directory,
+            },
             (Some(text), Match::Exact) => {
                 Self::Deterministic(DeterministicExpectedOutput::Exact { text })
             }
@@ -407,20 +666,52 @@ impl ExpectedOutput {
     pub(crate) fn is_float(&self) -> bool {
         matches!(
             self,
-            Self::Deterministic(DeterministicExpectedOutput::Float { .. })
+            Self::Deterministic(
+                DeterministicExpectedOutput::Float { .. }
+                    | DeterministicExpectedOutput::FloatTolerance { .. }
+            )
         )
     }
 
-    pub(crate) fn expected_stdout(&self) -> Option<&str> {
+    /// Makes `Exact`, `SplitWhitespace`, `Lines`, and `LinesTrimEnd` compare the numbers with
+    /// `tolerance`.
+    fn tolerate_float_errors(&mut self, tolerance: PositiveFinite<f64>) {
+        if let Self::Deterministic(expected) = self {
+            if let DeterministicExpectedOutput::Exact { text }
+            | DeterministicExpectedOutput::SplitWhitespace { text }
+            | DeterministicExpectedOutput::Lines { text }
+            | DeterministicExpectedOutput::LinesTrimEnd { text } = expected
+            {
+                *expected = DeterministicExpectedOutput::FloatTolerance {
+                    text: text.clone(),
+                    tolerance,
+                };
+            }
+        }
+    }
+
+    /// Makes `Exact` and `Lines` ignore trailing whitespace. Other kinds already do or are left
+    /// to the checker.
+    pub fn ignore_trailing_whitespace(&mut self) {
+        if let Self::Deterministic(expected) = self {
+            if let DeterministicExpectedOutput::Exact { text }
+            | DeterministicExpectedOutput::Lines { text } = expected
+            {
+                *expected = DeterministicExpectedOutput::LinesTrimEnd { text: text.clone() };
+            }
+        }
+    }
+
+    pub fn expected_stdout(&self) -> Option<&str> {
         match self {
             Self::Deterministic(expected) => expected.expected_stdout(),
-            Self::Checker { .. } => None,
+            Self::Checker { .. } | Self::CheckerCommand { .. } => None,
         }
     }
 
-    pub(crate) fn example(&self) -> Option<&str> {
+    pub fn example(&self) -> Option<&str> {
         match self {
-            Self::Checker { text, .. } => text.as_deref(),
+            Self::Checker { text, .. } | Self::CheckerCommand { text, .. } => text.as_deref(),
             _ => None,
         }
     }
@@ -438,49 +729,86 @@ pub enum DeterministicExpectedOutput {
     Lines {
         text: Arc<str>,
     },
+    /// Compares line by line ignoring trailing whitespace of each line and of the whole text.
+    LinesTrimEnd {
+        text: Arc<str>,
+    },
     Float {
         text: Arc<str>,
         relative_error: Option<PositiveFinite<f64>>,
         absolute_error: Option<PositiveFinite<f64>>,
     },
+    /// Compares whitespace-separated tokens. Two numbers match if their difference is within
+    /// `tolerance * max(|expected|, 1)`. Other tokens are compared as strings.
+    FloatTolerance {
+        text: Arc<str>,
+        tolerance: PositiveFinite<f64>,
+    },
 }
 
 impl DeterministicExpectedOutput {
     pub(crate) fn accepts(&self, actual: &str) -> bool {
+        // A BOM pasted together with a sample should not cause a spurious WA.
+        let actual = actual.trim_start_matches('\u{feff}');
+
         match self {
             Self::Pass => true,
-            Self::Exact { text } => &**text == actual,
-            Self::SplitWhitespace { text } => text.split_whitespace().eq(actual.split_whitespace()),
-            Self::Lines { text } => text.lines().eq(actual.lines()),
+            Self::Exact { text } => strip_bom(text) == actual,
+            Self::SplitWhitespace { text } => strip_bom(text)
+                .split_whitespace()
+                .eq(actual.split_whitespace()),
+            Self::Lines { text } => strip_bom(text).lines().eq(actual.lines()),
+            Self::LinesTrimEnd { text } => {
+                fn lines(s: &str) -> impl Iterator<Item = &str> {
+                    s.trim_end().lines().map(str::trim_end)
+                }
+                lines(strip_bom(text)).eq(lines(actual))
+            }
+            Self::Float { .. } => self.fuzzy_match(actual).is_some_and(|r| r.matched),
+            Self::FloatTolerance { text, tolerance } => {
+                let tolerance = tolerance.get();
+
+                strip_bom(text)
+                    .split_whitespace()
+                    .zip_longest(actual.split_whitespace())
+                    .all(|zip| match zip {
+                        EitherOrBoth::Both(expected, actual) => {
+                            match (expected.parse::<f64>(), actual.parse::<f64>()) {
+                                (Ok(expected), Ok(actual))
+                                    if expected.is_finite() && actual.is_finite() =>
+                                {
+                                    (actual - expected).abs() <= tolerance * expected.abs().max(1.0)
+                                }
+                                (Ok(expected), Ok(actual)) => {
+                                    expected.to_bits() == actual.to_bits()
+                                        || (expected.is_nan() && actual.is_nan())
+                                }
+                                _ => expected == actual,
+                            }
+                        }
+                        EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+                    })
+            }
+        }
+    }
+
+    /// Compares with [`fuzzy_match`] if this is `Float`.
+    pub(crate) fn fuzzy_match(&self, actual: &str) -> Option<FuzzyMatchResult> {
+        match self {
             Self::Float {
                 text,
                 relative_error,
                 absolute_error,
             } => {
-                let (text, actual) = (text.lines(), actual.lines());
-                let relative_error = relative_error.map(PositiveFinite::get).unwrap_or(0.0);
-                let absolute_error = absolute_error.map(PositiveFinite::get).unwrap_or(0.0);
-
-                text.zip_longest(actual).all(|zip| {
-                    if let EitherOrBoth::Both(line1, line2) = zip {
-                        let (words1, words2) = (line1.split_whitespace(), line2.split_whitespace());
-                        words1.zip_longest(words2).all(|zip| match zip {
-                            EitherOrBoth::Both(s1, s2) => {
-                                match (s1.parse::<f64>(), s2.parse::<f64>()) {
-                                    (Ok(v1), Ok(v2)) => {
-                                        (v1 - v2).abs() <= absolute_error
-                                            || ((v1 - v2) / v2).abs() <= relative_error
-                                    }
-                                    _ => s1 == s2,
-                                }
-                            }
-                            EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
-                        })
-                    } else {
-                        false
-                    }
-                })
+                let opts = FuzzyMatchOpts {
+                    float_abs_eps: absolute_error.map(PositiveFinite::get).unwrap_or(0.0),
+                    float_rel_eps: relative_error.map(PositiveFinite::get).unwrap_or(0.0),
+                    ignore_trailing_whitespace: false,
+                    case_insensitive: false,
+                };
+                Some(fuzzy_match(strip_bom(text), strip_bom(actual), &opts))
             }
+            _ => None,
         }
     }
 
@@ -490,11 +818,106 @@ impl DeterministicExpectedOutput {
             Self::Exact { text }
             | Self::SplitWhitespace { text }
             | Self::Lines { text }
-            | Self::Float { text, .. } => Some(text),
+            | Self::LinesTrimEnd { text }
+            | Self::Float { text, .. }
+            | Self::FloatTolerance { text, .. } => Some(text),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FuzzyMatchOpts {
+    /// Two numbers match if `|expected - actual| <= float_abs_eps`.
+    pub float_abs_eps: f64,
+    /// Two numbers match if `|(expected - actual) / actual| <= float_rel_eps`.
+    pub float_rel_eps: f64,
+    /// Ignores trailing empty lines and whitespace.
+    pub ignore_trailing_whitespace: bool,
+    /// Compares non-numeric tokens ignoring ASCII case.
+    pub case_insensitive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatchResult {
+    pub matched: bool,
+    /// 1-based.
+    pub first_mismatch_line: Option<usize>,
+    /// Describes the first mismatch. Empty if matched.
+    pub details: String,
+}
+
+/// Compares `expected` and `actual` line by line, and each line token by token.
+///
+/// Tokens that can be parsed as finite floating-point numbers are compared with the errors in
+/// `opts`. `nan` and `inf` are compared as strings.
+pub fn fuzzy_match(expected: &str, actual: &str, opts: &FuzzyMatchOpts) -> FuzzyMatchResult {
+    fn lines(text: &str, trim_end: bool) -> std::str::Lines<'_> {
+        if trim_end { text.trim_end() } else { text }.lines()
+    }
+
+    let compare = |s1: &str, s2: &str| match (s1.parse::<f64>(), s2.parse::<f64>()) {
+        (Ok(v1), Ok(v2)) if v1.is_finite() && v2.is_finite() => {
+            (v1 - v2).abs() <= opts.float_abs_eps || ((v1 - v2) / v2).abs() <= opts.float_rel_eps
         }
+        _ if opts.case_insensitive => s1.eq_ignore_ascii_case(s2),
+        _ => s1 == s2,
+    };
+
+    let mismatch = lines(expected, opts.ignore_trailing_whitespace)
+        .zip_longest(lines(actual, opts.ignore_trailing_whitespace))
+        .enumerate()
+        .find_map(|(i, zip)| {
+            let details = match zip {
+                EitherOrBoth::Both(line1, line2) => line1
+                    .split_whitespace()
+                    .zip_longest(line2.split_whitespace())
+                    .enumerate()
+                    .find_map(|(j, zip)| match zip {
+                        EitherOrBoth::Both(s1, s2) if compare(s1, s2) => None,
+                        EitherOrBoth::Both(s1, s2) => Some(format!(
+                            "token {}: expected {:?}, found {:?}",
+                            j + 1,
+                            s1,
+                            s2
+                        )),
+                        EitherOrBoth::Left(s1) => Some(format!(
+                            "token {}: expected {:?}, found the end of the line",
+                            j + 1,
+                            s1,
+                        )),
+                        EitherOrBoth::Right(s2) => {
+                            Some(format!("token {}: unexpected {:?}", j + 1, s2))
+                        }
+                    })
+                    .map(|details| format!("line {}, {}", i + 1, details))?,
+                EitherOrBoth::Left(line1) => format!(
+                    "line {}: expected {:?}, found the end of the output",
+                    i + 1,
+                    line1,
+                ),
+                EitherOrBoth::Right(line2) => format!("line {}: unexpected {:?}", i + 1, line2),
+            };
+            Some((i + 1, details))
+        });
+
+    match mismatch {
+        Some((line, details)) => FuzzyMatchResult {
+            matched: false,
+            first_mismatch_line: Some(line),
+            details,
+        },
+        None => FuzzyMatchResult {
+            matched: true,
+            first_mismatch_line: None,
+            details: "".to_owned(),
+        },
     }
 }
 
+fn strip_bom(s: &str) -> &str {
+    s.trim_start_matches('\u{feff}')
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 #[serde(transparent)]
 pub struct PositiveFinite<F>(F);
@@ -581,12 +1004,13 @@ mod serde_fn {
 #[cfg(test)]
 mod tests {
     use crate::testsuite::{
-        Additional, BatchTestSuite, DeterministicExpectedOutput, Match, PartialBatchTestCase,
-        PositiveFinite, TestSuite,
+        Additional, BatchTestSuite, DeterministicExpectedOutput, ExpectedOutput, FuzzyMatchOpts,
+        FuzzyMatchResult, Match, PartialBatchTestCase, PositiveFinite, TestCaseOrigin, TestSuite,
+        TextSource,
     };
     use difference::assert_diff;
     use pretty_assertions::assert_eq;
-    use std::time::Duration;
+    use std::{collections::HashSet, path::Path, time::Duration};
 
     #[test]
     fn atcoder_abc162_a() {
@@ -618,6 +1042,8 @@ extend: []
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
                 r#match: Match::Lines,
+                float_tolerance: None,
+                generator: None,
                 cases: vec![
                     PartialBatchTestCase {
                         name: Some("Sample 1".to_owned()),
@@ -625,6 +1051,8 @@ extend: []
                         out: Some("Yes\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                     PartialBatchTestCase {
                         name: Some("Sample 2".to_owned()),
@@ -632,6 +1060,8 @@ extend: []
                         out: Some("No\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                     PartialBatchTestCase {
                         name: Some("Sample 3".to_owned()),
@@ -639,6 +1069,8 @@ extend: []
                         out: Some("Yes\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                 ],
                 extend: vec![],
@@ -662,6 +1094,8 @@ extend:
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
                 r#match: Match::Lines,
+                float_tolerance: None,
+                generator: None,
                 cases: vec![],
                 extend: vec![Additional::Text {
                     path: "./a".into(),
@@ -669,6 +1103,7 @@ extend:
                     out: "/out/*.txt".into(),
                     timelimit: None,
                     r#match: None,
+                    origin: None,
                 }],
             }),
         );
@@ -705,6 +1140,8 @@ extend: []
                     relative_error: Some(PositiveFinite(0.01)),
                     absolute_error: Some(PositiveFinite(0.01)),
                 },
+                float_tolerance: None,
+                generator: None,
                 cases: vec![
                     PartialBatchTestCase {
                         name: Some("Sample 1".to_owned()),
@@ -712,6 +1149,8 @@ extend: []
                         out: Some("6.28318530717958623200\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                     PartialBatchTestCase {
                         name: Some("Sample 2".to_owned()),
@@ -719,6 +1158,8 @@ extend: []
                         out: Some("458.67252742410977361942\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                 ],
                 extend: vec![],
@@ -756,6 +1197,8 @@ extend: []
             &TestSuite::Batch(BatchTestSuite {
                 timelimit: Some(Duration::from_secs(2)),
                 r#match: Match::Lines,
+                float_tolerance: None,
+                generator: None,
                 cases: vec![
                     PartialBatchTestCase {
                         name: Some("Sample 1".to_owned()),
@@ -763,6 +1206,8 @@ extend: []
                         out: Some("aac\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                     PartialBatchTestCase {
                         name: Some("Sample 2".to_owned()),
@@ -770,6 +1215,8 @@ extend: []
                         out: Some("\n".into()),
                         timelimit: None,
                         r#match: None,
+                        origin: None,
+                        note: None,
                     },
                 ],
                 extend: vec![],
@@ -777,6 +1224,233 @@ extend: []
         );
     }
 
+    #[test]
+    fn origin() -> anyhow::Result<()> {
+        let suite = serde_yaml::from_str::<BatchTestSuite>(
+            r#"---
+match: Lines
+
+cases:
+  - name: Sample 1
+    in: ""
+  - name: mine
+    in: ""
+    origin: manual
+"#,
+        )?;
+
+        let origins = suite
+            .load_test_cases(Path::new(""), None::<HashSet<String>>, |_| Ok(vec![]))?
+            .into_iter()
+            .map(|case| case.origin)
+            .collect::<Vec<_>>();
+
+        assert_eq!([TestCaseOrigin::Sample, TestCaseOrigin::Manual], *origins);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn checker_command() -> anyhow::Result<()> {
+        let mut suite = serde_yaml::from_str::<BatchTestSuite>(
+            r#"---
+match:
+  CheckerCommand:
+    command: [./checker, $in, $expected, $actual]
+    working_directory: checkers
+
+cases:
+  - name: Sample 1
+    in: "1\n"
+    out: "2\n"
+  - name: Sample 2
+    in: "3\n"
+    out: "4\n"
+    match:
+      CheckerCommand:
+        command: [./other]
+"#,
+        )?;
+
+        suite.resolve_checker(Path::new("/workspace"))?;
+
+        let outputs = suite
+            .load_test_cases(Path::new(""), None::<HashSet<String>>, |_| Ok(vec![]))?
+            .into_iter()
+            .map(|case| case.output)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            [
+                ExpectedOutput::CheckerCommand {
+                    text: Some("2\n".into()),
+                    command: vec![
+                        "./checker".to_owned(),
+                        "$in".to_owned(),
+                        "$expected".to_owned(),
+                        "$actual".to_owned(),
+                    ],
+                    working_directory: Some("/workspace/checkers".into()),
+                },
+                ExpectedOutput::CheckerCommand {
+                    text: Some("4\n".into()),
+                    command: vec!["./other".to_owned()],
+                    working_directory: None,
+                },
+            ],
+            *outputs,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_case_sources() -> anyhow::Result<()> {
+        let dir = tempfile::Builder::new()
+            .prefix("snowchains-core-test-case-sources-")
+            .tempdir()?;
+
+        for (path, content) in &[
+            ("a/in/02.txt", "2\n"),
+            ("a/in/10.txt", "10"),
+            ("a/in/01.txt", "1\n"),
+            ("a/out/01.txt", "one\n"),
+            ("a/out/10.txt", "ten"),
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+        }
+
+        let suite = serde_yaml::from_str::<BatchTestSuite>(
+            r#"---
+match: Lines
+
+cases:
+  - name: Sample 1
+    in: "0\n"
+    out: "zero\n"
+
+extend:
+  - type: Text
+    path: "./a"
+    in: /in/*.txt
+    out: /out/*.txt
+"#,
+        )?;
+
+        let sources = suite.test_case_sources(dir.path())?;
+
+        assert_eq!(
+            sources
+                .iter()
+                .map(|s| s.name.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["Sample 1", "01", "02", "10"],
+        );
+        assert_eq!(
+            sources[3].r#in,
+            TextSource::File(dir.path().join("a").join("in").join("10.txt")),
+        );
+        assert_eq!(sources[2].out, None);
+
+        let cases = suite.load_test_cases(dir.path(), None::<HashSet<String>>, |_| Ok(vec![]))?;
+
+        assert_eq!(
+            cases
+                .iter()
+                .map(|c| c.name.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["Sample 1", "01", "02", "10"],
+        );
+        assert_eq!(&*cases[3].input, "10");
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn match_checker_command() {
+        test_serialize_deserialize(
+            r#"---
+type: Batch
+timelimit: 2s
+match:
+  CheckerCommand:
+    command:
+      - python3
+      - compare.py
+
+cases:
+  - name: Sample 1
+    in: |
+      3 2
+      1 2
+      1 3
+    out: |
+      1 2 3
+
+extend: []
+"#,
+            &TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(2)),
+                r#match: Match::CheckerCommand {
+                    command: vec!["python3".to_owned(), "compare.py".to_owned()],
+                    working_directory: None,
+                },
+                float_tolerance: None,
+                generator: None,
+                cases: vec![PartialBatchTestCase {
+                    name: Some("Sample 1".to_owned()),
+                    r#in: "3 2\n1 2\n1 3\n".into(),
+                    out: Some("1 2 3\n".into()),
+                    timelimit: None,
+                    r#match: None,
+                    origin: None,
+                    note: None,
+                }],
+                extend: vec![],
+            }),
+        );
+    }
+
+    #[test]
+    fn generator() {
+        test_serialize_deserialize(
+            r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+generator:
+  - python3
+  - gen.py
+
+cases:
+  - name: Generated 1
+    in: |
+      3
+    origin: generated
+
+extend: []
+"#,
+            &TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(2)),
+                r#match: Match::Lines,
+                float_tolerance: None,
+                generator: Some(vec!["python3".to_owned(), "gen.py".to_owned()]),
+                cases: vec![PartialBatchTestCase {
+                    name: Some("Generated 1".to_owned()),
+                    r#in: "3\n".into(),
+                    out: None,
+                    timelimit: None,
+                    r#match: None,
+                    origin: Some(TestCaseOrigin::Generated),
+                    note: None,
+                }],
+                extend: vec![],
+            }),
+        );
+    }
+
     fn test_serialize_deserialize(yaml: &str, expected: &TestSuite) {
         let actual = serde_yaml::from_str::<TestSuite>(yaml).unwrap();
         assert_eq!(*expected, actual);
@@ -850,5 +1524,120 @@ extend: []
             absolute_error: None,
         }
         .accepts("0\n"));
+
+        let float_tolerance = |text: &str| DeterministicExpectedOutput::FloatTolerance {
+            text: text.into(),
+            tolerance: PositiveFinite(1e-6),
+        };
+
+        assert!(float_tolerance("0.5\n").accepts("0.5000001\n"));
+        assert!(!float_tolerance("0.5\n").accepts("0.50001\n"));
+        assert!(float_tolerance("1000000\n").accepts("1000000.5\n"));
+        assert!(!float_tolerance("1000000\n").accepts("1000002\n"));
+        assert!(float_tolerance("Yes 0.5\n").accepts("Yes\n0.5000001\n"));
+        assert!(!float_tolerance("Yes 0.5\n").accepts("No 0.5\n"));
+        assert!(!float_tolerance("Yes 0.5\n").accepts("Yes\n"));
+        assert!(!float_tolerance("0.5\n").accepts("abc\n"));
+        assert!(float_tolerance("NaN\n").accepts("NaN\n"));
+        assert!(!float_tolerance("NaN\n").accepts("0\n"));
+        assert!(!float_tolerance("0\n").accepts("NaN\n"));
+        assert!(float_tolerance("inf\n").accepts("inf\n"));
+        assert!(!float_tolerance("inf\n").accepts("-inf\n"));
+        assert!(!float_tolerance("inf\n").accepts("1e308\n"));
+        assert!(!float_tolerance("1e308\n").accepts("inf\n"));
+
+        assert!(DeterministicExpectedOutput::LinesTrimEnd {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1 2 \n3\n\n"));
+
+        assert!(!DeterministicExpectedOutput::LinesTrimEnd {
+            text: "1 2\n3\n".into()
+        }
+        .accepts("1  2\n3\n"));
+
+        assert!(DeterministicExpectedOutput::Exact {
+            text: "\u{feff}Yes\n".into()
+        }
+        .accepts("Yes\n"));
+
+        assert!(DeterministicExpectedOutput::Lines {
+            text: "Yes\n".into()
+        }
+        .accepts("\u{feff}Yes\n"));
+    }
+
+    #[test]
+    fn fuzzy_match() {
+        let opts = FuzzyMatchOpts {
+            float_abs_eps: 1e-6,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            FuzzyMatchResult {
+                matched: true,
+                first_mismatch_line: None,
+                details: "".to_owned(),
+            },
+            super::fuzzy_match("Yes\n0.5 1\n", "Yes\n0.5000001  1.0\n", &opts),
+        );
+
+        assert_eq!(
+            FuzzyMatchResult {
+                matched: false,
+                first_mismatch_line: Some(2),
+                details: r#"line 2, token 2: expected "1", found "1.1""#.to_owned(),
+            },
+            super::fuzzy_match("Yes\n0.5 1\n", "Yes\n0.5 1.1\n", &opts),
+        );
+
+        assert_eq!(
+            FuzzyMatchResult {
+                matched: false,
+                first_mismatch_line: Some(3),
+                details: r#"line 3: unexpected """#.to_owned(),
+            },
+            super::fuzzy_match("1\n2\n", "1\n2\n\n", &opts),
+        );
+
+        assert_eq!(
+            Some(2),
+            super::fuzzy_match("1\n2\n", "1\n", &opts).first_mismatch_line,
+        );
+
+        assert!(!super::fuzzy_match("YES\n", "Yes\n", &opts).matched);
+
+        let opts = FuzzyMatchOpts {
+            ignore_trailing_whitespace: true,
+            case_insensitive: true,
+            ..opts
+        };
+
+        assert!(super::fuzzy_match("1\n2\n", "1\n2\n\n", &opts).matched);
+        assert!(super::fuzzy_match("YES\n", "Yes\n", &opts).matched);
+
+        let opts = FuzzyMatchOpts {
+            float_rel_eps: 0.01,
+            ..Default::default()
+        };
+
+        assert!(super::fuzzy_match("10000.0\n", "10001.0\n", &opts).matched);
+        assert!(!super::fuzzy_match("10000.0\n", "0\n", &opts).matched);
+
+        assert!(super::fuzzy_match("nan\n", "nan\n", &opts).matched);
+        assert!(super::fuzzy_match("inf -inf\n", "inf -inf\n", &opts).matched);
+        assert!(!super::fuzzy_match("1\n", "nan\n", &opts).matched);
+        assert!(!super::fuzzy_match("1\n", "inf\n", &opts).matched);
+        assert!(!super::fuzzy_match("inf\n", "-inf\n", &opts).matched);
+
+        assert_eq!(
+            r#"line 1, token 2: expected "2", found the end of the line"#,
+            super::fuzzy_match("1 2\n", "1\n", &opts).details,
+        );
+        assert_eq!(
+            r#"line 1, token 2: unexpected "2""#,
+            super::fuzzy_match("1\n", "1 2\n", &opts).details,
+        );
     }
 }
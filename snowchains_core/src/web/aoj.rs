@@ -0,0 +1,349 @@
+use crate::{
+    testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestCaseOrigin, TestSuite},
+    web::{
+        aoj::api::SessionMutExt as _, Exec, Platform, RetrieveFullTestCases, RetrieveTestCases,
+        RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeProblem,
+        RetrieveTestCasesOutcomeProblemTextFiles, Session, Shell,
+    },
+};
+use anyhow::{bail, Context as _};
+use indexmap::indexmap;
+use itertools::Itertools as _;
+use once_cell::sync::Lazy;
+use std::{collections::BTreeSet, convert::Infallible, time::Duration};
+use url::Url;
+
+static BASE_URL: Lazy<Url> = lazy_url!("https://onlinejudge.u-aizu.ac.jp");
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Aoj {}
+
+impl Aoj {
+    pub fn exec<A>(args: A) -> anyhow::Result<<Self as Exec<A>>::Output>
+    where
+        Self: Exec<A>,
+    {
+        <Self as Exec<_>>::exec(args)
+    }
+}
+
+impl Platform for Aoj {
+    type CookieStorage = ();
+    type LoginCredentials = Infallible;
+    type ParticipateTarget = Infallible;
+    type ParticipateCredentials = Infallible;
+    type RetrieveLanguagesTarget = Infallible;
+    type RetrieveLanguagesCredentials = Infallible;
+    type RetrieveTestCasesTargets = AojRetrieveTestCasesTargets;
+    type RetrieveTestCasesCredentials = ();
+    type RetrieveFullTestCasesCredentials = ();
+    type RetrieveSubmissionSummariesTarget = Infallible;
+    type RetrieveSubmissionSummariesCredentials = Infallible;
+    type WatchSubmissionsTarget = Infallible;
+    type WatchSubmissionsCredentials = Infallible;
+    type SubmitTarget = Infallible;
+    type SubmitCredentials = Infallible;
+}
+
+impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Aoj {
+    type Output = RetrieveTestCasesOutcome;
+
+    fn exec(args: RetrieveTestCases<Self, S>) -> anyhow::Result<RetrieveTestCasesOutcome> {
+        let RetrieveTestCases {
+            targets,
+            credentials: (),
+            full,
+            cookie_storage: (),
+            timeout,
+            shell,
+        } = args;
+
+        let mut sess = Session::new(timeout, None, shell)?;
+
+        let mut outcome = RetrieveTestCasesOutcome {
+            problems: vec![],
+            num_reused_files: 0,
+        };
+
+        for (index, problem_id) in targets.problem_ids()? {
+            let problem = sess.get_problem(&problem_id)?;
+            let samples = sess.get_samples(&problem_id)?;
+
+            outcome.problems.push(RetrieveTestCasesOutcomeProblem {
+                contest: None,
+                index,
+                url: url!("/problems/{}", problem_id),
+                screen_name: Some(problem_id),
+                display_name: problem.name.clone(),
+                test_suite: test_suite(&problem, samples),
+                text_files: indexmap!(),
+                statement: None,
+                checker: None,
+                points: None,
+                difficulty_stars: None,
+            });
+        }
+
+        if let Some(RetrieveFullTestCases {
+            credentials: (),
+            available_space: _,
+            saved_path: _,
+            parallelism,
+        }) = full
+        {
+            for outcome_problem in &mut outcome.problems {
+                let problem_id = outcome_problem
+                    .screen_name
+                    .clone()
+                    .expect("should be present");
+
+                let api::TestCaseHeaders { headers } = sess.get_test_case_headers(&problem_id)?;
+
+                let contents = super::download_with_progress(
+                    sess.shell.progress_draw_target(),
+                    parallelism,
+                    headers
+                        .iter()
+                        .map(|&api::TestCaseHeader { serial, .. }| {
+                            let req = sess.get_test_case(&problem_id, serial)?;
+                            Ok((serial.to_string(), req))
+                        })
+                        .collect::<Result<_, url::ParseError>>()?,
+                )?;
+
+                for (api::TestCaseHeader { serial, name, .. }, content) in
+                    headers.into_iter().zip_eq(contents)
+                {
+                    let api::TestCase { r#in, out, .. } = serde_json::from_str(&content)
+                        .with_context(|| format!("{}: Could not parse the response", serial))?;
+
+                    outcome_problem.text_files.insert(
+                        text_file_name(serial, &name),
+                        RetrieveTestCasesOutcomeProblemTextFiles {
+                            r#in,
+                            out: Some(out),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+#[derive(Debug)]
+pub enum AojRetrieveTestCasesTargets {
+    /// Problem IDs such as `ITP1_1_A` or `2200`.
+    ProblemIds(BTreeSet<String>),
+    /// A course ID (e.g. `ITP1`) and problem indexes in it (e.g. `1_A`).
+    Course(String, BTreeSet<String>),
+}
+
+impl AojRetrieveTestCasesTargets {
+    /// Returns pairs of an index and a problem ID.
+    fn problem_ids(self) -> anyhow::Result<Vec<(String, String)>> {
+        let ids: Vec<_> = match self {
+            Self::ProblemIds(ids) => ids.into_iter().map(|id| (id.clone(), id)).collect(),
+            Self::Course(course, indexes) => indexes
+                .into_iter()
+                .map(|index| {
+                    let id = format!("{}_{}", course.to_uppercase(), index.to_uppercase());
+                    (index, id)
+                })
+                .collect(),
+        };
+
+        for (_, id) in &ids {
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("Invalid problem ID for AOJ: {:?}", id);
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+fn test_suite(problem: &api::Problem, samples: Vec<api::TestCase>) -> TestSuite {
+    TestSuite::Batch(BatchTestSuite {
+        timelimit: Some(Duration::from_secs(problem.problem_time_limit)),
+        r#match: Match::Lines,
+        float_tolerance: None,
+        generator: None,
+        cases: samples
+            .into_iter()
+            .map(|api::TestCase { serial, r#in, out }| PartialBatchTestCase {
+                name: Some(format!("sample{}", serial)),
+                r#in: r#in.into(),
+                out: Some(out.into()),
+                timelimit: None,
+                r#match: None,
+                origin: Some(TestCaseOrigin::Sample),
+                note: None,
+            })
+            .collect(),
+        extend: vec![],
+    })
+}
+
+/// Prefixes the serial so that the files are sorted in the order of the API response.
+fn text_file_name(serial: u64, name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    format!("{:03}_{}", serial, name)
+}
+
+mod api {
+    //! <http://developers.u-aizu.ac.jp/index>
+
+    use crate::web::{ResponseExt as _, SessionMut};
+    use once_cell::sync::Lazy;
+    use serde::Deserialize;
+    use url::Url;
+
+    static JUDGEAPI_BASE_URL: Lazy<Url> = lazy_url!("https://judgeapi.u-aizu.ac.jp/");
+    static JUDGEDAT_BASE_URL: Lazy<Url> = lazy_url!("https://judgedat.u-aizu.ac.jp/");
+
+    pub(super) trait SessionMutExt: SessionMut {
+        /// Problem metadata including the timelimit.
+        fn get_problem(&mut self, problem_id: &str) -> anyhow::Result<Problem> {
+            let url = JUDGEAPI_BASE_URL.join(&format!("problems/{}", problem_id))?;
+
+            self.get(url)
+                .colorize_status_code(&[200], (), ..)
+                .send()?
+                .ensure_status(&[200])?
+                .json()
+                .map_err(Into::into)
+        }
+
+        fn get_samples(&mut self, problem_id: &str) -> anyhow::Result<Vec<TestCase>> {
+            let url = JUDGEDAT_BASE_URL.join(&format!("testcases/samples/{}", problem_id))?;
+
+            self.get(url)
+                .colorize_status_code(&[200], (), ..)
+                .send()?
+                .ensure_status(&[200])?
+                .json()
+                .map_err(Into::into)
+        }
+
+        fn get_test_case_headers(&mut self, problem_id: &str) -> anyhow::Result<TestCaseHeaders> {
+            let url = JUDGEDAT_BASE_URL.join(&format!("testcases/{}/header", problem_id))?;
+
+            self.get(url)
+                .colorize_status_code(&[200], (), ..)
+                .send()?
+                .ensure_status(&[200])?
+                .json()
+                .map_err(Into::into)
+        }
+
+        /// Responds a JSON of [`TestCase`]. Large inputs and outputs are truncated by the server.
+        fn get_test_case(
+            &self,
+            problem_id: &str,
+            serial: u64,
+        ) -> Result<reqwest::RequestBuilder, url::ParseError> {
+            let url = JUDGEDAT_BASE_URL.join(&format!("testcases/{}/{}", problem_id, serial))?;
+            Ok(self.async_client().get(url))
+        }
+    }
+
+    impl<S: SessionMut> SessionMutExt for S {}
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct Problem {
+        pub(super) name: String,
+        /// In seconds.
+        pub(super) problem_time_limit: u64,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct TestCase {
+        pub(super) serial: u64,
+        pub(super) r#in: String,
+        pub(super) out: String,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct TestCaseHeaders {
+        pub(super) headers: Vec<TestCaseHeader>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "camelCase")]
+    pub(super) struct TestCaseHeader {
+        pub(super) serial: u64,
+        pub(super) name: String,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::api;
+    use crate::testsuite::{BatchTestSuite, TestSuite};
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn test_suite() {
+        fn test(
+            problem: &str,
+            samples: &str,
+            header: &str,
+            expected_timelimit: Duration,
+            expected_num_samples: usize,
+            expected_num_full: usize,
+        ) -> anyhow::Result<()> {
+            let problem = serde_json::from_str::<api::Problem>(problem)?;
+            let samples = serde_json::from_str::<Vec<api::TestCase>>(samples)?;
+            let api::TestCaseHeaders { headers } = serde_json::from_str(header)?;
+
+            let test_suite = super::test_suite(&problem, samples);
+
+            let (timelimit, num_samples) = match test_suite {
+                TestSuite::Batch(BatchTestSuite {
+                    timelimit, cases, ..
+                }) => (timelimit, cases.len()),
+                _ => panic!("expected `Batch`"),
+            };
+
+            assert_eq!(Some(expected_timelimit), timelimit);
+            assert_eq!(expected_num_samples, num_samples);
+            assert_eq!(expected_num_full, headers.len());
+            Ok(())
+        }
+
+        test(
+            include_str!("../../tests/fixtures/aoj-ITP1_1_A-problem.json"),
+            include_str!("../../tests/fixtures/aoj-ITP1_1_A-samples.json"),
+            include_str!("../../tests/fixtures/aoj-ITP1_1_A-header.json"),
+            Duration::from_secs(1),
+            1,
+            1,
+        )
+        .unwrap();
+
+        test(
+            include_str!("../../tests/fixtures/aoj-ALDS1_1_A-problem.json"),
+            include_str!("../../tests/fixtures/aoj-ALDS1_1_A-samples.json"),
+            include_str!("../../tests/fixtures/aoj-ALDS1_1_A-header.json"),
+            Duration::from_secs(1),
+            2,
+            10,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn text_file_name() {
+        assert_eq!("001_judge_data", super::text_file_name(1, "judge_data"));
+        assert_eq!("012_in12_txt", super::text_file_name(12, "in12.txt"));
+    }
+}
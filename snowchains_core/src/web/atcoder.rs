@@ -1,16 +1,18 @@
 use crate::{
     testsuite::{
         BatchTestSuite, InteractiveTestSuite, Match, PartialBatchTestCase, PositiveFinite,
-        TestSuite,
+        TestCaseOrigin, TestSuite,
     },
     web::{
-        AnsiColored, CaseConverted, CookieStorage, Exec, Login, LoginOutcome, LowerCase,
-        Participate, ParticipateOutcome, Platform, ProblemInContest, ProblemsInContest,
-        ResponseExt as _, RetrieveFullTestCases, RetrieveLanguages, RetrieveLanguagesOutcome,
-        RetrieveSubmissionSummaries, RetrieveTestCases, RetrieveTestCasesOutcome,
-        RetrieveTestCasesOutcomeProblem, RetrieveTestCasesOutcomeProblemContest,
-        RetrieveTestCasesOutcomeProblemTextFiles, Session, SessionMut, Shell, Submit,
-        SubmitOutcome, UpperCase, WatchSubmissions,
+        AnsiColored, CaseConverted, ContestDuration, CookieStorage, Exec, Login, LoginOutcome,
+        LowerCase, Participate, ParticipateOutcome, Platform, ProblemInContest, ProblemsInContest,
+        RatedRange, ResponseExt as _, RetrieveContestDuration, RetrieveFullTestCases,
+        RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveRatedRange,
+        RetrieveSubmissionSummaries, RetrieveSubmittedCode, RetrieveTestCases,
+        RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeProblem,
+        RetrieveTestCasesOutcomeProblemContest, RetrieveTestCasesOutcomeProblemTextFiles,
+        RetrieveTopSubmissions, Session, SessionMut, Shell, SubmissionVerdict, Submit,
+        SubmitOutcome, UpperCase, WatchSubmission, WatchSubmissions,
     },
 };
 use anyhow::{anyhow, bail, Context as _};
@@ -22,12 +24,15 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use itertools::Itertools as _;
 use maplit::{btreemap, hashmap, hashset};
 use once_cell::sync::Lazy;
+use prettytable::{cell, row};
 use regex::Regex;
 use reqwest::header;
 use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::json;
+use sha2::{Digest as _, Sha256};
 use std::{
+    cmp,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::Infallible,
     fmt,
@@ -35,10 +40,11 @@ use std::{
     io,
     marker::PhantomData,
     mem,
+    num::NonZeroUsize,
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use termcolor::{Color, ColorSpec, WriteColor};
 use tokio::runtime::Runtime;
@@ -48,7 +54,7 @@ use url::Url;
 static BASE_URL: Lazy<Url> = lazy_url!("https://atcoder.jp");
 
 pub fn contest_id_from_url(url: &Url) -> anyhow::Result<String> {
-    if url.domain() != Some("atcoder.jp") {
+    if ![Some("atcoder.jp"), Some("beta.atcoder.jp")].contains(&url.domain()) {
         bail!("wrong domain. expected `atcoder.jp`: {}", url);
     }
 
@@ -58,6 +64,62 @@ pub fn contest_id_from_url(url: &Url) -> anyhow::Result<String> {
         .with_context(|| "Could not extract contest ID of the problem")
 }
 
+/// Resolves `languageId` against the options of `#select-lang`.
+///
+/// `language_id` is either an ID in `names_by_id` or a pattern for the names: a regex enclosed in
+/// `/`s (e.g. `/^C\+\+ \(GCC/`) or a plain substring (e.g. `C++ (GCC`). A pattern has to match
+/// exactly one of the names.
+pub fn resolve_language_id(
+    names_by_id: &IndexMap<String, String>,
+    language_id: &str,
+) -> anyhow::Result<String> {
+    if names_by_id.contains_key(language_id) {
+        return Ok(language_id.to_owned());
+    }
+
+    let candidates =
+        if language_id.len() >= 2 && language_id.starts_with('/') && language_id.ends_with('/') {
+            let regex = Regex::new(&language_id[1..language_id.len() - 1])
+                .with_context(|| format!("Invalid regex for `languageId`: {:?}", language_id))?;
+            names_by_id
+                .iter()
+                .filter(|(_, name)| regex.is_match(name))
+                .collect::<Vec<_>>()
+        } else {
+            names_by_id
+                .iter()
+                .filter(|(_, name)| name.contains(language_id))
+                .collect()
+        };
+
+    match *candidates {
+        [(id, _)] => Ok(id.clone()),
+        [] => bail!(
+            "No language matches `languageId` {:?}. Available: {}",
+            language_id,
+            names_by_id
+                .values()
+                .format_with(", ", |n, f| f(&format_args!("{:?}", n))),
+        ),
+        _ => bail!(
+            "Multiple languages match `languageId` {:?}: {}",
+            language_id,
+            candidates
+                .iter()
+                .format_with(", ", |(id, n), f| f(&format_args!("{:?} ({})", n, id))),
+        ),
+    }
+}
+
+/// Rewrites a URL on `beta.atcoder.jp`, where AtCoder was before moving to `atcoder.jp`.
+fn with_current_domain(mut url: Url) -> Url {
+    if url.domain() == Some("beta.atcoder.jp") {
+        url.set_host(Some("atcoder.jp"))
+            .expect("`atcoder.jp` should be a valid host");
+    }
+    url
+}
+
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Atcoder<'closures> {
     Infallible(Infallible, PhantomData<fn() -> &'closures ()>),
@@ -208,6 +270,7 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
         } = args;
 
         let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        sess.warm_connection(BASE_URL.clone())?;
 
         let mut outcome = retrieve_sample_test_cases(&mut sess, username_and_password, &targets)?;
 
@@ -216,6 +279,9 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                 AtcoderRetrieveFullTestCasesCredentials {
                     dropbox_access_token,
                 },
+            available_space,
+            saved_path,
+            parallelism,
         }) = full
         {
             static DROPBOX_PATH_PREFIXES: Lazy<HashMap<String, String>> = Lazy::new(|| {
@@ -223,7 +289,9 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                     .unwrap()
             });
 
-            for problem in &mut outcome.problems {
+            let mut file_paths = vec![];
+
+            for problem in &outcome.problems {
                 let path_prefix = {
                     let contest = &problem.contest.as_ref().expect("should be `Some`").id;
                     DROPBOX_PATH_PREFIXES
@@ -263,11 +331,88 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                     ),
                 };
 
-                let mut retrieve_files = |file_paths| -> anyhow::Result<_> {
-                    retrieve_files(&mut sess, &dropbox_access_token, file_paths)
+                file_paths.push((in_file_paths, out_file_paths));
+            }
+
+            // Files saved by an interrupted run are reused if they have the same content.
+            let file_paths = file_paths
+                .into_iter()
+                .zip_eq(&outcome.problems)
+                .map(|((in_file_paths, out_file_paths), problem)| {
+                    let find_saved = |files: Vec<DropboxFile>, dir_file_name: &str| {
+                        files
+                            .into_iter()
+                            .map(|file| {
+                                let saved_path = saved_path.as_ref().map(|saved_path| {
+                                    saved_path(
+                                        &problem.index,
+                                        dir_file_name,
+                                        &file_stem(&file.path),
+                                    )
+                                });
+                                let saved = match &saved_path {
+                                    Some(saved_path) => file.read_if_same(saved_path)?,
+                                    None => None,
+                                };
+                                Ok((file, saved_path, saved))
+                            })
+                            .collect::<anyhow::Result<Vec<_>>>()
+                    };
+                    Ok((
+                        find_saved(in_file_paths, "in")?,
+                        find_saved(out_file_paths, "out")?,
+                    ))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            // List everything first so that nothing is downloaded if it would not fit.
+            let total_size = file_paths
+                .iter()
+                .flat_map(|(in_file_paths, out_file_paths)| {
+                    in_file_paths.iter().chain(out_file_paths)
+                })
+                .filter(|(_, _, saved)| saved.is_none())
+                .map(|(file, _, _)| file.size)
+                .sum();
+
+            super::ensure_available_space(total_size, available_space)?;
+
+            // The files of all of the problems are downloaded together so that the requests are
+            // not serialized per problem.
+            let unsaved = file_paths
+                .iter()
+                .flat_map(|(in_file_paths, out_file_paths)| {
+                    in_file_paths.iter().chain(out_file_paths)
+                })
+                .filter(|(_, _, saved)| saved.is_none())
+                .map(|(file, saved_path, _)| (file.path.clone(), saved_path.clone()))
+                .collect::<Vec<_>>();
+
+            let mut downloaded =
+                retrieve_files(&mut sess, &dropbox_access_token, parallelism, &unsaved)?
+                    .into_iter();
+
+            let mut num_reused_files = 0;
+
+            for (problem, (in_file_paths, out_file_paths)) in
+                outcome.problems.iter_mut().zip_eq(file_paths)
+            {
+                let mut contents = |files: Vec<ListedFile>| -> IndexMap<_, _> {
+                    files
+                        .into_iter()
+                        .map(|(file, _, saved)| {
+                            let content = if let Some(saved) = saved {
+                                num_reused_files += 1;
+                                saved
+                            } else {
+                                downloaded.next().expect("should be as many as `unsaved`")
+                            };
+                            (file_stem(&file.path), content)
+                        })
+                        .collect()
                 };
-                let in_contents = retrieve_files(&in_file_paths)?;
-                let mut out_contents = retrieve_files(&out_file_paths)?;
+                let in_contents = contents(in_file_paths);
+                let mut out_contents = contents(out_file_paths);
 
                 problem.text_files = in_contents
                     .into_iter()
@@ -277,6 +422,8 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                     })
                     .collect();
             }
+
+            outcome.num_reused_files = num_reused_files;
         }
 
         return Ok(outcome);
@@ -284,7 +431,37 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
         static URL: &str =
             "https://www.dropbox.com/sh/arnpe0ef5wds8cv/AAAk_SECQ2Nc6SVGii3rHX6Fa?dl=0";
 
-        struct Entries(Vec<Either<String, String>>);
+        #[derive(Debug, Clone)]
+        struct DropboxFile {
+            path: String,
+            size: u64,
+            /// <https://www.dropbox.com/developers/reference/content-hash>
+            content_hash: Option<String>,
+        }
+
+        /// A file on Dropbox, where it is saved, and its content if it is already saved.
+        type ListedFile = (DropboxFile, Option<PathBuf>, Option<String>);
+
+        impl DropboxFile {
+            /// Reads the file at `path` if it has the same size and content hash.
+            fn read_if_same(&self, path: &Path) -> anyhow::Result<Option<String>> {
+                match std::fs::metadata(path) {
+                    Ok(metadata) if metadata.len() == self.size => {}
+                    _ => return Ok(None),
+                }
+
+                let content = std::fs::read(path)
+                    .with_context(|| format!("could not read `{}`", path.display()))?;
+
+                if self.content_hash.as_deref() != Some(&*dropbox_content_hash(&content)) {
+                    return Ok(None);
+                }
+                Ok(String::from_utf8(content).ok())
+            }
+        }
+
+        /// Files, or folders.
+        struct Entries(Vec<Either<DropboxFile, String>>);
 
         impl Entries {
             fn has_folder(&self, name: &str) -> bool {
@@ -293,7 +470,7 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                     .any(|e| matches!(e, Either::Right(s) if s.split('/').last().unwrap() == name))
             }
 
-            fn files(&self) -> Vec<String> {
+            fn files(&self) -> Vec<DropboxFile> {
                 self.0
                     .iter()
                     .flat_map(|e| e.as_ref().left().cloned())
@@ -331,16 +508,24 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
             #[derive(Deserialize)]
             #[serde(tag = ".tag", rename_all = "snake_case")]
             enum Metadata {
-                File { name: String },
-                Folder { name: String },
-                Deleted { name: String },
+                File {
+                    name: String,
+                    size: u64,
+                    content_hash: Option<String>,
+                },
+                Folder {
+                    name: String,
+                },
+                Deleted {
+                    name: String,
+                },
             }
 
             impl Metadata {
                 fn is_valid(&self) -> bool {
                     !(matches!(self, Self::Folder { name } if name == "etc")
                         || matches!(
-                            self, Self::File { name }
+                            self, Self::File { name, .. }
                             if !(name.is_ascii()
                                 && [
                                     None,
@@ -416,7 +601,15 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                 .map(|metadata| {
                     let join = |name: &str| format!("{}/{}", path.trim_end_matches('/'), name);
                     match metadata {
-                        Metadata::File { name } => Ok(Either::Left(join(&name))),
+                        Metadata::File {
+                            name,
+                            size,
+                            content_hash,
+                        } => Ok(Either::Left(DropboxFile {
+                            path: join(&name),
+                            size,
+                            content_hash,
+                        })),
                         Metadata::Folder { name } => Ok(Either::Right(join(&name))),
                         Metadata::Deleted { name } => bail!("deleted: {:?}", name),
                     }
@@ -425,16 +618,22 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                 .map(Entries)
         }
 
+        /// Downloads the files, saving each of them to the path if any.
+        ///
+        /// The completed files are saved even if some of the others fail, so that the next run
+        /// resumes from them.
         fn retrieve_files(
             mut sess: impl SessionMut,
             access_token: &str,
-            file_paths: &[String],
-        ) -> anyhow::Result<IndexMap<String, String>> {
-            let contents = super::download_with_progress(
+            parallelism: NonZeroUsize,
+            file_paths: &[(String, Option<PathBuf>)],
+        ) -> anyhow::Result<Vec<String>> {
+            let contents = super::download_each_with_progress(
                 sess.shell().progress_draw_target(),
+                parallelism,
                 file_paths
                     .iter()
-                    .map(|path| {
+                    .map(|(path, _)| {
                         let req = sess
                             .async_client()
                             .post("https://content.dropboxapi.com/2/sharing/get_shared_link_file")
@@ -448,22 +647,45 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Atcoder<'_> {
                     .collect(),
             )?;
 
-            return Ok(file_paths.iter().map(file_stem).zip_eq(contents).collect());
-
-            fn file_stem(path: impl AsRef<str>) -> String {
-                path.as_ref()
-                    .split('/')
-                    .last()
-                    .unwrap()
-                    .split('.')
-                    .next()
-                    .unwrap()
-                    .to_owned()
+            for ((_, saved_path), content) in file_paths.iter().zip_eq(&contents) {
+                if let (Some(saved_path), Ok(content)) = (saved_path, content) {
+                    if let Some(parent) = saved_path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .with_context(|| format!("could not create `{}`", parent.display()))?;
+                    }
+                    std::fs::write(saved_path, content)
+                        .with_context(|| format!("could not write `{}`", saved_path.display()))?;
+                }
             }
+
+            contents.into_iter().collect()
+        }
+
+        fn file_stem(path: impl AsRef<str>) -> String {
+            path.as_ref()
+                .split('/')
+                .last()
+                .unwrap()
+                .split('.')
+                .next()
+                .unwrap()
+                .to_owned()
         }
     }
 }
 
+/// Hashes each 4 MiB block with SHA-256, then hashes the concatenation of them.
+fn dropbox_content_hash(content: &[u8]) -> String {
+    const BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+    let block_hashes = content
+        .chunks(BLOCK_SIZE)
+        .flat_map(Sha256::digest)
+        .collect::<Vec<_>>();
+
+    format!("{:x}", Sha256::digest(&block_hashes))
+}
+
 impl<S: Shell> Exec<RetrieveSubmissionSummaries<Self, S>> for Atcoder<'_> {
     type Output = AtcoderRetrieveSubmissionSummariesOutcome;
 
@@ -471,7 +693,12 @@ impl<S: Shell> Exec<RetrieveSubmissionSummaries<Self, S>> for Atcoder<'_> {
         args: RetrieveSubmissionSummaries<Self, S>,
     ) -> anyhow::Result<AtcoderRetrieveSubmissionSummariesOutcome> {
         let RetrieveSubmissionSummaries {
-            target: AtcoderRetrieveSubmissionSummariesTarget { contest },
+            target:
+                AtcoderRetrieveSubmissionSummariesTarget {
+                    contest,
+                    problems,
+                    max_pages,
+                },
             credentials:
                 AtcoderRetrieveSubmissionSummariesCredentials {
                     username_and_password,
@@ -488,14 +715,273 @@ impl<S: Shell> Exec<RetrieveSubmissionSummaries<Self, S>> for Atcoder<'_> {
         let (mut summaries, num_pages) =
             retrieve_submission_summaries(&mut sess, &contest, 1, username_and_password)?;
 
-        for page in 2..=num_pages {
+        let last_page = max_pages.map_or(num_pages, |max| cmp::min(max, num_pages));
+        let mut num_retrieved_pages = 1;
+
+        // The submissions are sorted from the newest, so the rest are older ones.
+        let all_found = |summaries: &[SubmissionSummary]| {
+            matches!(&problems, Some(problems) if problems.iter().all(|problem| {
+                summaries.iter().any(|summary| summary.task.is(problem))
+            }))
+        };
+
+        for page in 2..=last_page {
+            if all_found(&summaries) {
+                break;
+            }
+
             let (extend, _) = retrieve_submission_summaries(&mut sess, &contest, page, || {
                 bail!("should be logged in");
             })?;
             summaries.extend(extend);
+            num_retrieved_pages += 1;
+        }
+
+        if let Some(problems) = &problems {
+            summaries.retain(|summary| problems.iter().any(|problem| summary.task.is(problem)));
         }
 
-        Ok(AtcoderRetrieveSubmissionSummariesOutcome { summaries })
+        Ok(AtcoderRetrieveSubmissionSummariesOutcome {
+            summaries,
+            num_pages,
+            num_retrieved_pages,
+        })
+    }
+}
+
+impl<S: Shell> Exec<RetrieveSubmittedCode<Self, S>> for Atcoder<'_> {
+    type Output = AtcoderSubmittedCode;
+
+    fn exec(args: RetrieveSubmittedCode<Self, S>) -> anyhow::Result<AtcoderSubmittedCode> {
+        let RetrieveSubmittedCode {
+            target,
+            language,
+            verdicts,
+            credentials:
+                AtcoderRetrieveSubmissionSummariesCredentials {
+                    username_and_password,
+                },
+            cookie_storage,
+            timeout,
+            shell,
+        } = args;
+
+        let verdicts = verdicts.unwrap_or_else(|| vec![SubmissionVerdict::Ac]);
+
+        let (contest, problem) = match target {
+            ProblemInContest::Index { contest, problem } => {
+                (CaseConverted::<LowerCase>::new(contest), problem)
+            }
+            ProblemInContest::Url { url } => {
+                let url = with_current_domain(url);
+                let contest = CaseConverted::new(contest_id_from_url(&url)?);
+                let task_screen_name = url
+                    .path_segments()
+                    .and_then(Iterator::last)
+                    .with_context(|| "Empty URL")?
+                    .to_owned();
+                (contest, task_screen_name)
+            }
+        };
+
+        let language = language.map(|l| l.to_lowercase());
+
+        let is_target = |summary: &SubmissionSummary| {
+            summary.task.is(&problem)
+                && verdicts.iter().any(|&v| summary.status.is(v))
+                && language
+                    .as_ref()
+                    .map_or(true, |l| summary.language.to_lowercase().contains(l))
+        };
+
+        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+
+        let (summaries, num_pages) =
+            retrieve_submission_summaries(&mut sess, &contest, 1, username_and_password)?;
+
+        let mut found = summaries.into_iter().find(is_target);
+
+        for page in 2..=num_pages {
+            if found.is_some() {
+                break;
+            }
+
+            let (summaries, _) = retrieve_submission_summaries(&mut sess, &contest, page, || {
+                bail!("should be logged in");
+            })?;
+            found = summaries.into_iter().find(is_target);
+        }
+
+        let SubmissionSummary {
+            submission_time,
+            language,
+            score,
+            status,
+            exec_time,
+            memory,
+            detail,
+            ..
+        } = found.with_context(|| {
+            format!(
+                "No {} submissions for `{}`",
+                verdicts
+                    .iter()
+                    .map(|v| v.to_string().to_uppercase())
+                    .join("/"),
+                problem,
+            )
+        })?;
+
+        let code = sess
+            .get(detail.clone())
+            .colorize_status_code(&[200], (), ..)
+            .send()?
+            .ensure_status(&[200])?
+            .html()?
+            .extract_submitted_code()?;
+
+        Ok(AtcoderSubmittedCode {
+            url: detail,
+            submission_time,
+            language,
+            score,
+            status: status.to_string(),
+            exec_time,
+            memory,
+            code,
+        })
+    }
+}
+
+impl<S: Shell> Exec<RetrieveContestDuration<Self, S>> for Atcoder<'_> {
+    type Output = ContestDuration;
+
+    fn exec(args: RetrieveContestDuration<Self, S>) -> anyhow::Result<ContestDuration> {
+        let RetrieveContestDuration {
+            contest,
+            cookie_storage,
+            timeout,
+            shell,
+        } = args;
+
+        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+
+        let contest = CaseConverted::<LowerCase>::new(contest);
+
+        let res = sess
+            .get(url!("/contests/{}", contest))
+            .colorize_status_code(&[200], (), ..)
+            .send()?
+            .ensure_status(&[200, 404])?;
+
+        if res.status() == 404 {
+            bail!("The contest `{}` does not exist", contest);
+        }
+
+        let (start, end) = res.html()?.extract_contest_duration()?;
+        Ok(ContestDuration {
+            start,
+            end,
+            clock_offset: sess.clock_offset(),
+        })
+    }
+}
+
+impl<S: Shell> Exec<RetrieveRatedRange<Self, S>> for Atcoder<'_> {
+    type Output = Option<RatedRange>;
+
+    fn exec(args: RetrieveRatedRange<Self, S>) -> anyhow::Result<Option<RatedRange>> {
+        let RetrieveRatedRange {
+            contest,
+            cookie_storage,
+            timeout,
+            shell,
+        } = args;
+
+        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+
+        let contest = CaseConverted::<LowerCase>::new(contest);
+
+        let res = sess
+            .get(url!("/contests/{}", contest))
+            .colorize_status_code(&[200], (), ..)
+            .send()?
+            .ensure_status(&[200, 404])?;
+
+        if res.status() == 404 {
+            bail!("The contest `{}` does not exist", contest);
+        }
+
+        let range = res.html()?.extract_rated_range()?;
+        Ok(range.map(|(low, high)| RatedRange { low, high }))
+    }
+}
+
+impl<S: Shell> Exec<RetrieveTopSubmissions<Self, S>> for Atcoder<'_> {
+    type Output = AtcoderRetrieveSubmissionSummariesOutcome;
+
+    fn exec(
+        args: RetrieveTopSubmissions<Self, S>,
+    ) -> anyhow::Result<AtcoderRetrieveSubmissionSummariesOutcome> {
+        let RetrieveTopSubmissions {
+            target,
+            credentials:
+                AtcoderRetrieveSubmissionSummariesCredentials {
+                    username_and_password,
+                },
+            limit,
+            cookie_storage,
+            timeout,
+            shell,
+        } = args;
+
+        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+
+        let (contest, url) = match target {
+            ProblemInContest::Index { contest, problem } => {
+                let contest = CaseConverted::<LowerCase>::new(contest);
+                let problem = CaseConverted::<UpperCase>::new(problem);
+
+                let url = retrieve_tasks_page(&mut sess, username_and_password, &contest)?
+                    .extract_task_indexes_and_urls()?
+                    .remove(&problem)
+                    .with_context(|| format!("No such problem: `{}`", problem))?;
+
+                (contest, url)
+            }
+            ProblemInContest::Url { url } => {
+                let url = with_current_domain(url);
+                let contest = CaseConverted::new(contest_id_from_url(&url)?);
+                (contest, url)
+            }
+        };
+
+        let task_screen_name = url
+            .path_segments()
+            .and_then(Iterator::last)
+            .with_context(|| "Empty URL")?;
+
+        let mut url = url!("/contests/{}/submissions", contest);
+        url.query_pairs_mut()
+            .append_pair("f.Task", task_screen_name)
+            .append_pair("f.Status", "AC")
+            .append_pair("orderBy", "time_consumption");
+
+        let (mut summaries, num_pages) = sess
+            .get(url)
+            .colorize_status_code(&[200], (), ..)
+            .send()?
+            .ensure_status(&[200])?
+            .html()?
+            .extract_submissions()?;
+
+        summaries.truncate(limit);
+
+        Ok(AtcoderRetrieveSubmissionSummariesOutcome {
+            summaries,
+            num_pages,
+            num_retrieved_pages: 1,
+        })
     }
 }
 
@@ -518,6 +1004,7 @@ impl<S: Shell> Exec<Submit<Self, S>> for Atcoder<'_> {
         } = args;
 
         let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+        sess.warm_connection(BASE_URL.clone())?;
 
         let (contest, url) = match target {
             ProblemInContest::Index { contest, problem } => {
@@ -534,6 +1021,7 @@ impl<S: Shell> Exec<Submit<Self, S>> for Atcoder<'_> {
                 (contest, url)
             }
             ProblemInContest::Url { url } => {
+                let url = with_current_domain(url);
                 let contest = CaseConverted::new(contest_id_from_url(&url)?);
                 (contest, url)
             }
@@ -545,13 +1033,21 @@ impl<S: Shell> Exec<Submit<Self, S>> for Atcoder<'_> {
                 .map(|cs| cs[1].to_owned())
                 .with_context(|| "Could not extract screen name of the problem")?;
 
-        let csrf_token = sess
+        let problem_page = sess
             .get(url)
             .colorize_status_code(&[200], (), ..)
             .send()?
             .ensure_status(&[200])?
-            .html()?
-            .extract_csrf_token()?;
+            .html()?;
+
+        let csrf_token = problem_page.extract_csrf_token()?;
+
+        // Numeric IDs are sent as they are, as before.
+        let language_id = if language_id.bytes().all(|b| b.is_ascii_digit()) {
+            language_id
+        } else {
+            resolve_language_id(&problem_page.extract_langs()?, &language_id)?
+        };
 
         let res = sess
             .post(url!("/contests/{}/submit", contest))
@@ -631,6 +1127,95 @@ impl<S: Shell> Exec<WatchSubmissions<Self, S>> for Atcoder<'_> {
     }
 }
 
+impl<S: Shell> Exec<WatchSubmission<Self, S>> for Atcoder<'_> {
+    type Output = AtcoderLatestSubmission;
+
+    fn exec(args: WatchSubmission<Self, S>) -> anyhow::Result<AtcoderLatestSubmission> {
+        let WatchSubmission {
+            target: AtcoderWatchSubmissionsTarget { contest },
+            credentials:
+                AtcoderWatchSubmissionsCredentials {
+                    username_and_password,
+                },
+            interval,
+            max_duration,
+            cookie_storage,
+            timeout,
+            shell,
+        } = args;
+
+        let contest = CaseConverted::<LowerCase>::new(contest);
+
+        let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
+
+        let (summaries, _) =
+            retrieve_submission_summaries(&mut sess, &contest, 1, username_and_password)?;
+
+        let mut summary = summaries
+            .into_iter()
+            .next()
+            .with_context(|| format!("No submissions in `{}`", contest))?;
+
+        let pb = ProgressBar::with_draw_target(0, sess.shell().progress_draw_target());
+        pb.set_style(ProgressStyle::default_spinner().template("{prefix:.bold} {msg}"));
+        pb.set_prefix(&summary.task.display_name);
+
+        // Polled without `on_request`/`on_response` so that the status line stays on one line.
+        let rt = Runtime::new()?;
+        let mut url = url!("/contests/{}/submissions/me", contest);
+        url.query_pairs_mut().append_pair("page", "1");
+        let cookie_header = sess.cookie_header(&BASE_URL);
+        let started = Instant::now();
+
+        while matches!(
+            summary.status,
+            Verdict::Wj | Verdict::Wr | Verdict::Judging(..)
+        ) {
+            pb.set_message(&summary.status.to_string());
+
+            if let Some(max_duration) = max_duration {
+                if started.elapsed() >= max_duration {
+                    pb.abandon();
+                    bail!(
+                        "{} was not judged within {}s",
+                        summary.detail,
+                        max_duration.as_secs(),
+                    );
+                }
+            }
+
+            std::thread::sleep(interval);
+
+            let html = rt.block_on(async {
+                sess.async_client()
+                    .get(url.clone())
+                    .header(header::COOKIE, &cookie_header)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await
+            })?;
+
+            let (summaries, _) = Html::parse_document(&html).extract_submissions()?;
+
+            summary = summaries
+                .into_iter()
+                .find(|s| s.detail == summary.detail)
+                .with_context(|| format!("Not found: {}", summary.detail))?;
+        }
+
+        pb.finish_with_message(&summary.status.to_string());
+
+        Ok(AtcoderLatestSubmission {
+            verdict: summary.status.to_string(),
+            score: summary.score.trim().to_owned(),
+            exec_time: summary.exec_time.clone(),
+            url: summary.detail.clone(),
+        })
+    }
+}
+
 pub struct AtcoderLoginCredentials<'closures> {
     pub username_and_password: &'closures mut dyn FnMut() -> anyhow::Result<(String, String)>,
 }
@@ -665,6 +1250,11 @@ pub struct AtcoderRetrieveFullTestCasesCredentials {
 #[derive(Debug)]
 pub struct AtcoderRetrieveSubmissionSummariesTarget {
     pub contest: String,
+    /// Indexes or screen names. If specified, only the submissions for them are retrieved, and
+    /// the paging stops once each of them has one.
+    pub problems: Option<BTreeSet<String>>,
+    /// Pages after this are not retrieved.
+    pub max_pages: Option<u32>,
 }
 
 pub struct AtcoderRetrieveSubmissionSummariesCredentials<'closures> {
@@ -675,12 +1265,87 @@ pub struct AtcoderRetrieveSubmissionSummariesCredentials<'closures> {
 #[derive(Debug, Serialize)]
 pub struct AtcoderRetrieveSubmissionSummariesOutcome {
     summaries: Vec<SubmissionSummary>,
+    #[serde(skip)]
+    pub num_pages: u32,
+    #[serde(skip)]
+    pub num_retrieved_pages: u32,
 }
 
 impl AtcoderRetrieveSubmissionSummariesOutcome {
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).expect("should not fail")
     }
+
+    /// Returns the score of the most recent submission for `problem`.
+    ///
+    /// `problem` is either an index (e.g. "a") or a screen name (e.g. "abc100_a").
+    pub fn latest_score(&self, problem: &str) -> Option<&str> {
+        self.latest(problem)
+            .map(|SubmissionSummary { score, .. }| score.trim())
+    }
+
+    /// Returns the most recent submission for `problem`, in the same way as `latest_score`.
+    pub fn latest_submission(&self, problem: &str) -> Option<AtcoderLatestSubmission> {
+        self.latest(problem).map(|summary| AtcoderLatestSubmission {
+            verdict: summary.status.to_string(),
+            score: summary.score.trim().to_owned(),
+            exec_time: summary.exec_time.clone(),
+            url: summary.detail.clone(),
+        })
+    }
+
+    fn latest(&self, problem: &str) -> Option<&SubmissionSummary> {
+        self.summaries
+            .iter()
+            .find(|SubmissionSummary { task, .. }| task.is(problem))
+    }
+
+    pub fn to_table(&self) -> impl fmt::Display {
+        let mut table = super::new_table();
+
+        table.set_titles(row!["#", "User", "Exec Time", "Memory", "Language"]);
+
+        for (i, summary) in self.summaries.iter().enumerate() {
+            table.add_row(row![
+                r->i + 1,
+                summary.user.name,
+                r->summary.exec_time.as_deref().unwrap_or(""),
+                r->summary.memory.as_deref().unwrap_or(""),
+                summary.language,
+            ]);
+        }
+
+        table
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AtcoderLatestSubmission {
+    pub verdict: String,
+    pub score: String,
+    /// e.g. `"12 ms"`
+    pub exec_time: Option<String>,
+    pub url: Url,
+}
+
+impl AtcoderLatestSubmission {
+    pub fn is_accepted(&self) -> bool {
+        self.verdict == "AC"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AtcoderSubmittedCode {
+    pub url: Url,
+    pub submission_time: DateTime<FixedOffset>,
+    /// Language name on AtCoder (e.g. `"Rust (1.42.0)"`)
+    pub language: String,
+    pub score: String,
+    /// Verdict (e.g. `"AC"`)
+    pub status: String,
+    pub exec_time: Option<String>,
+    pub memory: Option<String>,
+    pub code: String,
 }
 
 #[derive(Debug)]
@@ -701,10 +1366,13 @@ fn retrieve_sample_test_cases(
     mut username_and_password: impl FnMut() -> anyhow::Result<(String, String)>,
     targets: &ProblemsInContest,
 ) -> anyhow::Result<RetrieveTestCasesOutcome> {
+    let mut difficulty_stars = HashMap::new();
+
     let problems = match targets.clone() {
         ProblemsInContest::Indexes { contest, problems } => {
             let contest = CaseConverted::<LowerCase>::new(contest);
-            let html = retrieve_tasks_page(&mut sess, username_and_password, &contest)?;
+            let html = retrieve_tasks_page(&mut sess, &mut username_and_password, &contest)?;
+            difficulty_stars.extend(html.extract_task_difficulty_stars_by_url()?);
 
             let contest_display_name = html
                 .extract_title()?
@@ -739,6 +1407,7 @@ fn retrieve_sample_test_cases(
             let mut problems: BTreeMap<_, (_, _, HashSet<_>)> = btreemap!();
 
             for url in urls {
+                let url = with_current_domain(url);
                 let contest = CaseConverted::new(contest_id_from_url(&url)?);
 
                 if let Some((_, _, only)) = problems.get_mut(&contest) {
@@ -746,6 +1415,7 @@ fn retrieve_sample_test_cases(
                 } else {
                     let html =
                         retrieve_tasks_page(&mut sess, &mut username_and_password, &contest)?;
+                    difficulty_stars.extend(html.extract_task_difficulty_stars_by_url()?);
                     let contest_display_name = html
                         .extract_title()?
                         .trim_start_matches("Tasks - ")
@@ -773,16 +1443,25 @@ fn retrieve_sample_test_cases(
         }
     };
 
-    let mut outcome = RetrieveTestCasesOutcome { problems: vec![] };
+    let mut outcome = RetrieveTestCasesOutcome {
+        problems: vec![],
+        num_reused_files: 0,
+    };
 
     for (contest, (contest_display_name, mut indexes_and_urls)) in problems {
-        let test_suites = sess
-            .get(url!("/contests/{}/tasks_print", contest))
-            .colorize_status_code(&[200], (), ..)
-            .send()?
-            .ensure_status(&[200])?
-            .html()?
-            .extract_samples();
+        let test_suites = super::send_with_relogin(
+            &mut sess,
+            "/login",
+            |sess| {
+                sess.get(url!("/contests/{}/tasks_print", contest))
+                    .colorize_status_code(&[200], (), ..)
+                    .send()
+            },
+            |sess| login(sess, &mut username_and_password),
+        )?
+        .ensure_status(&[200])?
+        .html()?
+        .extract_samples();
 
         if indexes_and_urls.len() > test_suites.len() {
             sess.shell().warn(format!(
@@ -801,7 +1480,7 @@ fn retrieve_sample_test_cases(
 
         for result in test_suites {
             match result {
-                Ok((index, display_name, test_suite)) => {
+                Ok((index, display_name, test_suite, statement, checker)) => {
                     if let Some(url) = indexes_and_urls.shift_remove(&*index) {
                         let screen_name = url
                             .path_segments()
@@ -817,12 +1496,16 @@ fn retrieve_sample_test_cases(
                                 TestSuite::Batch(BatchTestSuite {
                                     timelimit: None,
                                     r#match: Match::Lines,
+                                    float_tolerance: None,
+                                    generator: None,
                                     cases: vec![],
                                     extend: vec![],
                                 })
                             }
                         };
 
+                        let difficulty_stars = difficulty_stars.remove(&url);
+
                         outcome.problems.push(RetrieveTestCasesOutcomeProblem {
                             contest: Some(contest.clone()),
                             url,
@@ -831,6 +1514,10 @@ fn retrieve_sample_test_cases(
                             display_name,
                             test_suite,
                             text_files: indexmap![],
+                            points: statement.as_deref().and_then(parse_points),
+                            difficulty_stars,
+                            statement,
+                            checker,
                         });
                     }
                 }
@@ -912,7 +1599,11 @@ fn participate(
 
     let html = res.html()?;
 
-    let status = ContestStatus::now(html.extract_contest_duration()?, contest);
+    let status = ContestStatus::at(
+        Utc::now() + sess.clock_offset(),
+        html.extract_contest_duration()?,
+        contest,
+    );
 
     if !explicit {
         status.raise_if_not_begun()?;
@@ -950,14 +1641,20 @@ fn participate(
 
 fn retrieve_tasks_page(
     mut sess: impl SessionMut,
-    username_and_password: impl FnMut() -> anyhow::Result<(String, String)>,
+    mut username_and_password: impl FnMut() -> anyhow::Result<(String, String)>,
     contest: &CaseConverted<LowerCase>,
 ) -> anyhow::Result<Html> {
-    let res = sess
-        .get(url!("/contests/{}/tasks", contest))
-        .colorize_status_code(&[200], &[404], ..)
-        .send()?
-        .ensure_status(&[200, 404])?;
+    let res = super::send_with_relogin(
+        &mut sess,
+        "/login",
+        |sess| {
+            sess.get(url!("/contests/{}/tasks", contest))
+                .colorize_status_code(&[200], &[404], ..)
+                .send()
+        },
+        |sess| login(sess, &mut username_and_password),
+    )?
+    .ensure_status(&[200, 404])?;
 
     if res.status() == 200 {
         res.html().map_err(Into::into)
@@ -1284,9 +1981,13 @@ enum ContestStatus {
 }
 
 impl ContestStatus {
-    fn now(dur: (DateTime<Utc>, DateTime<Utc>), contest_id: &CaseConverted<LowerCase>) -> Self {
+    /// `now` is in the clock of the service.
+    fn at(
+        now: DateTime<Utc>,
+        dur: (DateTime<Utc>, DateTime<Utc>),
+        contest_id: &CaseConverted<LowerCase>,
+    ) -> Self {
         let (start, end) = dur;
-        let now = Utc::now();
         if now < start {
             ContestStatus::NotBegun(contest_id.to_owned(), start.with_timezone(&Local))
         } else if now > end {
@@ -1337,6 +2038,18 @@ struct SubmissionSummaryTask {
     url: Url,
 }
 
+impl SubmissionSummaryTask {
+    /// `problem` is either an index (e.g. "a") or a screen name (e.g. "abc100_a").
+    fn is(&self, problem: &str) -> bool {
+        self.display_name
+            .starts_with(&format!("{} - ", problem.to_uppercase()))
+            || matches!(
+                self.url.path_segments().and_then(Iterator::last),
+                Some(s) if s.eq_ignore_ascii_case(problem)
+            )
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct SubmissionSummaryUser {
     name: String,
@@ -1391,6 +2104,20 @@ impl Verdict {
         static JUDGING: Lazy<Regex> = lazy_regex!(r"\A\s*([0-9]{1,3})/([0-9]{1,3})\s*(\S*)\s*\z");
     }
 
+    fn is(&self, verdict: SubmissionVerdict) -> bool {
+        matches!(
+            (self, verdict),
+            (Self::Ac, SubmissionVerdict::Ac)
+                | (Self::Wa, SubmissionVerdict::Wa)
+                | (Self::Tle, SubmissionVerdict::Tle)
+                | (Self::Mle, SubmissionVerdict::Mle)
+                | (Self::Ole, SubmissionVerdict::Ole)
+                | (Self::Re, SubmissionVerdict::Re)
+                | (Self::Ce, SubmissionVerdict::Ce)
+                | (Self::Ie, SubmissionVerdict::Ie)
+        )
+    }
+
     fn color_spec(&self) -> ColorSpec {
         return match self {
             Self::Ac => from_fg_and_bold(Some(Color::Green), true),
@@ -1449,7 +2176,7 @@ impl Serialize for Verdict {
     }
 }
 
-#[ext]
+#[ext(HtmlExt)]
 impl Html {
     fn extract_title(&self) -> anyhow::Result<&str> {
         self.select(static_selector!(":root > head > title"))
@@ -1490,6 +2217,20 @@ impl Html {
         .with_context(|| "Could not find the contest duration")
     }
 
+    /// `None` for unrated contests.
+    fn extract_rated_range(&self) -> anyhow::Result<Option<(u32, u32)>> {
+        self.select(static_selector!("#main-container span.small"))
+            .map(|r| r.text().collect::<String>())
+            .find_map(|text| {
+                let text = text.trim();
+                let value = ["Rated Range:", "Rated対象:"]
+                    .iter()
+                    .find_map(|prefix| text.strip_prefix(prefix))?;
+                parse_rated_range(value)
+            })
+            .with_context(|| "Could not find the rated range")
+    }
+
     fn contains_registration_button(&self) -> anyhow::Result<bool> {
         let insert_participant_box = self
             .select(static_selector!("#main-container .insert-participant-box"))
@@ -1521,7 +2262,46 @@ impl Html {
         .with_context(|| "Could not extract task indexes and URLs")
     }
 
-    fn extract_samples(&self) -> Vec<anyhow::Result<(String, String, anyhow::Result<TestSuite>)>> {
+    /// Difficulty stars (e.g. `☆☆★`) of each row of the task table, in the same order as
+    /// `extract_task_indexes_and_urls`. Most contests do not show them.
+    fn extract_task_difficulty_stars(&self) -> anyhow::Result<Vec<Option<String>>> {
+        let stars = self
+            .select(static_selector!(
+                "#main-container > div.row > div.col-sm-12 > div.panel > table.table > tbody > tr",
+            ))
+            .map(|tr| {
+                tr.select(static_selector!("td"))
+                    .map(|td| td.text().collect::<String>().trim().to_owned())
+                    .find(|text| !text.is_empty() && text.chars().all(|c| c == '☆' || c == '★'))
+            })
+            .collect();
+        Ok(stars)
+    }
+
+    fn extract_task_difficulty_stars_by_url(&self) -> anyhow::Result<HashMap<Url, String>> {
+        let urls = self
+            .extract_task_indexes_and_urls()?
+            .into_iter()
+            .map(|(_, url)| url);
+
+        Ok(urls
+            .zip(self.extract_task_difficulty_stars()?)
+            .flat_map(|(url, stars)| Some((url, stars?)))
+            .collect())
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn extract_samples(
+        &self,
+    ) -> Vec<
+        anyhow::Result<(
+            String,
+            String,
+            anyhow::Result<TestSuite>,
+            Option<String>,
+            Option<Url>,
+        )>,
+    > {
         return self
             .select(static_selector!(
                 "#main-container > div.row div[class=\"col-sm-12\"]",
@@ -1552,19 +2332,24 @@ impl Html {
                         .map_err(|_| "Could not extract the timelimit")?;
 
                     // In `tasks_print`, there are multiple `#task-statement`s.
-                    let samples = div
+                    let task_statement = div
                         .select(static_selector!(":scope > div[id=\"task-statement\"]"))
                         .exactly_one()
-                        .ok()
-                        .and_then(extract_samples)
+                        .map_err(|_| "Could not extract the sample cases")?;
+
+                    let samples = extract_samples(task_statement)
                         .ok_or("Could not extract the sample cases")?;
 
+                    let explanations = extract_sample_explanations(task_statement);
+
                     Ok::<_, &str>(if timelimit == Duration::new(0, 0) {
                         TestSuite::Unsubmittable
                     } else if let Samples::Batch(r#match, samples) = samples {
                         TestSuite::Batch(BatchTestSuite {
                             timelimit: Some(timelimit),
                             r#match,
+                            float_tolerance: None,
+                            generator: None,
                             cases: samples
                                 .into_iter()
                                 .enumerate()
@@ -1574,6 +2359,8 @@ impl Html {
                                     out: Some(output.into()),
                                     timelimit: None,
                                     r#match: None,
+                                    origin: Some(TestCaseOrigin::Sample),
+                                    note: explanations.get(i).cloned().flatten(),
                                 })
                                 .collect(),
                             extend: vec![],
@@ -1586,7 +2373,31 @@ impl Html {
                 })()
                 .map_err(|e| anyhow!("{}: {}", index, e));
 
-                Ok((index, display_name, test_suite))
+                let statement = div
+                    .select(static_selector!(":scope > div[id=\"task-statement\"]"))
+                    .next()
+                    .map(|task_statement| {
+                        task_statement
+                            .select(static_selector!("span.lang-en"))
+                            .next()
+                            .unwrap_or(task_statement)
+                            .text()
+                            .collect::<String>()
+                    });
+
+                let checker = div
+                    .select(static_selector!(
+                        ":scope > div[id=\"task-statement\"] a[href]"
+                    ))
+                    .flat_map(|a| a.value().attr("href"))
+                    .filter(|href| {
+                        let href = href.split(&['?', '#'][..]).next().unwrap_or(href);
+                        [".py", ".rb", ".cpp"].iter().any(|ext| href.ends_with(ext))
+                    })
+                    .flat_map(|href| BASE_URL.join(href))
+                    .next();
+
+                Ok((index, display_name, test_suite, statement, checker))
             })
             .collect();
 
@@ -1611,6 +2422,11 @@ impl Html {
             Some(Duration::from_millis(timelimit))
         }
 
+        static IN_JA: Lazy<Regex> = lazy_regex!(r"\A[\s\n]*入力例\s*(\d{1,2})[.\n]*\z");
+        static OUT_JA: Lazy<Regex> = lazy_regex!(r"\A[\s\n]*出力例\s*(\d{1,2})[.\n]*\z");
+        static IN_EN: Lazy<Regex> = lazy_regex!(r"\ASample Input\s?([0-9]{1,2}).*\z");
+        static OUT_EN: Lazy<Regex> = lazy_regex!(r"\ASample Output\s?([0-9]{1,2}).*\z");
+
         fn extract_samples(task_statement: ElementRef<'_>) -> Option<Samples> {
             // TODO:
             // - https://atcoder.jp/contests/arc019/tasks/arc019_4 (interactive)
@@ -1619,11 +2435,6 @@ impl Html {
             // - https://atcoder.jp/contests/jag2016-domestic/tasks
             // - https://atcoder.jp/contests/chokudai001/tasks/chokudai_001_a
 
-            static IN_JA: Lazy<Regex> = lazy_regex!(r"\A[\s\n]*入力例\s*(\d{1,2})[.\n]*\z");
-            static OUT_JA: Lazy<Regex> = lazy_regex!(r"\A[\s\n]*出力例\s*(\d{1,2})[.\n]*\z");
-            static IN_EN: Lazy<Regex> = lazy_regex!(r"\ASample Input\s?([0-9]{1,2}).*\z");
-            static OUT_EN: Lazy<Regex> = lazy_regex!(r"\ASample Output\s?([0-9]{1,2}).*\z");
-
             // Current style (Japanese)
             static P1_HEAD: Lazy<Selector> =
                 lazy_selector!("span.lang > span.lang-ja > div.part > section > h3");
@@ -1634,6 +2445,26 @@ impl Html {
                 lazy_selector!("span.lang > span.lang-en > div.part > section > h3");
             static P2_CONTENT: Lazy<Selector> =
                 lazy_selector!("span.lang>span.lang-en>div.part>section>pre");
+            // Samples in `<details>` (Japanese)
+            static P9_HEAD: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-ja details > summary");
+            static P9_CONTENT: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-ja details > pre");
+            // Samples in `<details>` (English)
+            static P10_HEAD: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-en details > summary");
+            static P10_CONTENT: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-en details > pre");
+            // `<h4>` headings (Japanese)
+            static P11_HEAD: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-ja div.part > section > h4");
+            static P11_CONTENT: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-ja div.part > section > pre");
+            // `<h4>` headings (English)
+            static P12_HEAD: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-en div.part > section > h4");
+            static P12_CONTENT: Lazy<Selector> =
+                lazy_selector!("span.lang > span.lang-en div.part > section > pre");
             // ARC019..ARC057 \ {ARC019/C, ARC046/D, ARC050, ARC052/{A, C}, ARC053, ARC055},
             // ABC007..ABC040 \ {ABC036}, ATC001, ATC002
             static P3_HEAD: Lazy<Selector> = lazy_selector!("div.part > section > h3");
@@ -1661,6 +2492,10 @@ impl Html {
             let stmt = task_statement;
             try_extract_samples(stmt, &P1_HEAD, &P1_CONTENT, &IN_JA, &OUT_JA)
                 .or_else(|| try_extract_samples(stmt, &P2_HEAD, &P2_CONTENT, &IN_EN, &OUT_EN))
+                .or_else(|| try_extract_samples(stmt, &P9_HEAD, &P9_CONTENT, &IN_JA, &OUT_JA))
+                .or_else(|| try_extract_samples(stmt, &P10_HEAD, &P10_CONTENT, &IN_EN, &OUT_EN))
+                .or_else(|| try_extract_samples(stmt, &P11_HEAD, &P11_CONTENT, &IN_JA, &OUT_JA))
+                .or_else(|| try_extract_samples(stmt, &P12_HEAD, &P12_CONTENT, &IN_EN, &OUT_EN))
                 .or_else(|| try_extract_samples(stmt, &P3_HEAD, &P3_CONTENT, &IN_JA, &OUT_JA))
                 .or_else(|| try_extract_samples(stmt, &P4_HEAD, &P4_CONTENT, &IN_JA, &OUT_JA))
                 .or_else(|| try_extract_samples(stmt, &P5_HEAD, &P5_CONTENT, &IN_JA, &OUT_JA))
@@ -1669,6 +2504,46 @@ impl Html {
                 .or_else(|| try_extract_samples(stmt, &P8_HEAD, &P8_CONTENT, &IN_JA, &OUT_JA))
         }
 
+        /// Extracts the paragraphs following each sample output, in the order of the samples.
+        ///
+        /// Only the current style, where each sample output is in its own `section`, is supported.
+        fn extract_sample_explanations(task_statement: ElementRef<'_>) -> Vec<Option<String>> {
+            let (stmt, re_output) = match task_statement
+                .select(static_selector!("span.lang > span.lang-en"))
+                .next()
+            {
+                Some(stmt) => (stmt, &*OUT_EN),
+                None => (task_statement, &*OUT_JA),
+            };
+
+            let mut explanations = BTreeMap::<usize, _>::new();
+
+            for section in stmt.select(static_selector!("section")) {
+                let n = section
+                    .select(static_selector!(":scope > h3"))
+                    .next()
+                    .and_then(|h3| {
+                        let text = h3.collect_text();
+                        let caps = re_output.captures(&text)?;
+                        parse_zenkaku(&caps[1]).ok()
+                    });
+
+                if let Some(n) = n {
+                    let explanation = section
+                        .children()
+                        .flat_map(ElementRef::wrap)
+                        .filter(|e| !["h3", "pre"].contains(&e.value().name()))
+                        .map(|e| e.collect_text().trim().to_owned())
+                        .filter(|s| !s.is_empty())
+                        .join("\n");
+
+                    explanations.insert(n, Some(explanation).filter(|s| !s.is_empty()));
+                }
+            }
+
+            explanations.into_values().collect()
+        }
+
         fn try_extract_samples(
             task_statement: ElementRef<'_>,
             selector_for_header: &'static Selector,
@@ -1726,11 +2601,12 @@ impl Html {
             let mut next = None;
             let selector = or(selector_for_header, selector_for_content);
             for elem_ref in task_statement.select(&selector) {
-                if elem_ref.value().name() == "h3" {
+                if ["h3", "h4", "summary"].contains(&elem_ref.value().name()) {
                     let text = elem_ref.collect_text();
-                    if let Some(caps) = re_input.captures(&text) {
+                    let text = text.trim();
+                    if let Some(caps) = re_input.captures(text) {
                         next = Some((true, parse_zenkaku(&caps[1]).ok()?));
-                    } else if let Some(caps) = re_output.captures(&text) {
+                    } else if let Some(caps) = re_output.captures(text) {
                         next = Some((false, parse_zenkaku(&caps[1]).ok()?));
                     }
                 } else if ["pre", "section"].contains(&elem_ref.value().name()) {
@@ -1968,4 +2844,241 @@ impl Html {
         })()
         .with_context(|| "Could not parse the submissions page")
     }
+
+    fn extract_submitted_code(&self) -> anyhow::Result<String> {
+        self.select(static_selector!("#submission-code"))
+            .next()
+            .map(|pre| pre.text().collect())
+            .with_context(|| "Could not find the code in the submission page")
+    }
+}
+
+/// Extracts the points from a statement (e.g. `Score : 100 points` or `配点 : 100 点`).
+fn parse_points(statement: &str) -> Option<u64> {
+    static_regex!(r"(?:Score\s*:\s*([0-9]+)\s*points?|配点\s*[:：]\s*([0-9]+)\s*点)")
+        .captures(statement)
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .and_then(|m| m.as_str().parse().ok())
+}
+
+/// Parses the value of "Rated Range" (e.g. `~ 1999`, `1200 - 2799`, `1200 ~`, `All`, or `-`).
+///
+/// Returns `Some(None)` for unrated contests and `None` if `value` is not recognized.
+fn parse_rated_range(value: &str) -> Option<Option<(u32, u32)>> {
+    let value = value.trim();
+
+    if value == "-" {
+        return Some(None);
+    }
+    if ["All", "全員"].contains(&value) {
+        return Some(Some((0, u32::MAX)));
+    }
+
+    let sep = value.find(&['~', '-'][..])?;
+    let (low, high) = (&value[..sep], &value[sep + 1..]);
+
+    let bound = |s: &str, default| match s.trim() {
+        "" => Some(default),
+        s => s.parse().ok(),
+    };
+
+    Some(Some((bound(low, 0)?, bound(high, u32::MAX)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlExt as _;
+    use crate::{
+        testsuite::{BatchTestSuite, TestSuite},
+        web::SubmissionVerdict,
+    };
+    use indexmap::indexmap;
+    use scraper::Html;
+    use std::time::Duration;
+    use url::Url;
+
+    #[test]
+    fn resolve_language_id() -> anyhow::Result<()> {
+        let names_by_id = indexmap!(
+            "4003".to_owned() => "C++ (GCC 9.2.1)".to_owned(),
+            "4004".to_owned() => "C++ (Clang 10.0.0)".to_owned(),
+            "4006".to_owned() => "Python (3.8.2)".to_owned(),
+        );
+
+        assert_eq!("4004", super::resolve_language_id(&names_by_id, "4004")?);
+        assert_eq!(
+            "4003",
+            super::resolve_language_id(&names_by_id, "C++ (GCC")?
+        );
+        assert_eq!(
+            "4006",
+            super::resolve_language_id(&names_by_id, "/^Python/")?
+        );
+        assert!(super::resolve_language_id(&names_by_id, "C++").is_err());
+        assert!(super::resolve_language_id(&names_by_id, "Rust").is_err());
+        assert!(super::resolve_language_id(&names_by_id, "/(/").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn beta_domain() -> anyhow::Result<()> {
+        let url = "https://beta.atcoder.jp/contests/abc100/tasks/abc100_a".parse::<Url>()?;
+
+        assert_eq!("abc100", super::contest_id_from_url(&url)?);
+        assert_eq!(
+            "https://atcoder.jp/contests/abc100/tasks/abc100_a",
+            super::with_current_domain(url).as_str(),
+        );
+        assert!(super::contest_id_from_url(
+            &"https://atcoder.jp.example.com/contests/abc100/".parse()?
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn extract_samples_in_details_and_h4() {
+        let html = Html::parse_document(include_str!(
+            "../../tests/fixtures/atcoder-tasks-print-details.html"
+        ));
+
+        let problems = html
+            .extract_samples()
+            .into_iter()
+            .map(|result| {
+                let (index, _, test_suite, _, _) = result.unwrap();
+                let (timelimit, cases) = match test_suite.unwrap() {
+                    TestSuite::Batch(BatchTestSuite {
+                        timelimit, cases, ..
+                    }) => (timelimit, cases),
+                    test_suite => panic!("expected `Batch`: {:?}", test_suite),
+                };
+                let cases = cases
+                    .into_iter()
+                    .map(|c| (c.r#in.to_string(), c.out.unwrap().to_string()))
+                    .collect::<Vec<_>>();
+                (index, timelimit, cases)
+            })
+            .collect::<Vec<_>>();
+
+        let case = |i: &str, o: &str| (i.to_owned(), o.to_owned());
+
+        assert_eq!(
+            problems,
+            [
+                (
+                    "A".to_owned(),
+                    Some(Duration::from_secs(2)),
+                    vec![case("1 2\n", "3\n"), case("100 200\n", "300\n")],
+                ),
+                (
+                    "B".to_owned(),
+                    Some(Duration::from_secs(3)),
+                    vec![case("5\n", "25\n")],
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn extract_submissions() -> anyhow::Result<()> {
+        let html = Html::parse_document(include_str!(
+            "../../tests/fixtures/atcoder-contests-abc100-submissions-me.html"
+        ));
+
+        let (summaries, num_pages) = html.extract_submissions()?;
+        assert_eq!(1, num_pages);
+
+        let verdicts = [
+            SubmissionVerdict::Ac,
+            SubmissionVerdict::Wa,
+            SubmissionVerdict::Tle,
+        ];
+        let classified = summaries
+            .iter()
+            .map(|summary| verdicts.iter().copied().find(|&v| summary.status.is(v)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            [
+                Some(SubmissionVerdict::Ac),
+                Some(SubmissionVerdict::Tle),
+                Some(SubmissionVerdict::Wa),
+            ],
+            &*classified,
+        );
+        assert!(summaries.iter().all(|s| s.task.is("a")));
+        Ok(())
+    }
+
+    #[test]
+    fn extract_task_difficulty_stars() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            r#"<div id="main-container"><div class="row"><div class="col-sm-12"><div class="panel">
+<table class="table"><tbody>
+<tr><td class="text-center"><a href="/contests/practice2/tasks/practice2_a">A</a></td><td>Disjoint Set Union</td><td>☆☆★</td></tr>
+<tr><td class="text-center"><a href="/contests/practice2/tasks/practice2_b">B</a></td><td>Fenwick Tree</td><td></td></tr>
+</tbody></table>
+</div></div></div></div>"#,
+        );
+
+        assert_eq!(
+            vec![Some("☆☆★".to_owned()), None],
+            html.extract_task_difficulty_stars()?,
+        );
+        assert_eq!(1, html.extract_task_difficulty_stars_by_url()?.len());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_points() {
+        let f = super::parse_points;
+        assert_eq!(Some(100), f("Score : 100 points\n\nProblem Statement"));
+        assert_eq!(Some(1), f("Score: 1 point"));
+        assert_eq!(Some(300), f("配点 : 300 点"));
+        assert_eq!(None, f("Problem Statement"));
+    }
+
+    #[test]
+    fn parse_rated_range() {
+        let f = super::parse_rated_range;
+        assert_eq!(Some(Some((0, 1999))), f(" ~ 1999"));
+        assert_eq!(Some(Some((1200, 2799))), f("1200 - 2799"));
+        assert_eq!(Some(Some((1200, u32::MAX))), f("1200 ~ "));
+        assert_eq!(Some(Some((0, u32::MAX))), f("All"));
+        assert_eq!(Some(None), f("-"));
+        assert_eq!(None, f("Rated"));
+    }
+
+    #[test]
+    fn submission_summary_task_is() {
+        let task = super::SubmissionSummaryTask {
+            display_name: "B - Ringo's Favorite Numbers".to_owned(),
+            url: "https://atcoder.jp/contests/abc100/tasks/abc100_b"
+                .parse()
+                .unwrap(),
+        };
+
+        assert!(task.is("b"));
+        assert!(task.is("B"));
+        assert!(task.is("abc100_b"));
+        assert!(!task.is("a"));
+        assert!(!task.is("abc100_a"));
+    }
+
+    #[test]
+    fn dropbox_content_hash() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            super::dropbox_content_hash(b""),
+        );
+        assert_eq!(
+            "2825fd9c46ab04797831e84fe5a436850251587100602c876b7eb10ec1e7d821",
+            super::dropbox_content_hash(b"1 2\n"),
+        );
+        assert_eq!(
+            "5f858b62ccd88447586305aec6fd53c96747cfebf527cbba129a6dfed47d9624",
+            super::dropbox_content_hash(&vec![b'a'; 4 * 1024 * 1024 + 1]),
+        );
+    }
 }
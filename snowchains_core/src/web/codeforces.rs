@@ -1,11 +1,11 @@
 use crate::{
-    testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestSuite},
+    testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestCaseOrigin, TestSuite},
     web::{
         codeforces::api::SessionMutExt as _, CookieStorage, Exec, Login, LoginOutcome, Participate,
         ParticipateOutcome, Platform, ProblemInContest, ProblemsInContest, ResponseExt as _,
         RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveTestCases, RetrieveTestCasesOutcome,
-        RetrieveTestCasesOutcomeProblem, RetrieveTestCasesOutcomeProblemContest, Session,
-        SessionMut, Shell, Submit, SubmitOutcome,
+        RetrieveTestCasesOutcomeProblem, RetrieveTestCasesOutcomeProblemContest, ServiceErrorKind,
+        Session, SessionMut, Shell, Submit, SubmitOutcome,
     },
 };
 use anyhow::{bail, Context as _};
@@ -30,6 +30,8 @@ pub fn contest_id_from_url(url: &Url) -> anyhow::Result<u64> {
 
 static BASE_URL: Lazy<Url> = lazy_url!("https://codeforces.com");
 
+const DUPLICATE_SUBMISSION: &str = "You have submitted exactly the same code before";
+
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Codeforces<'closures> {
     Infallible(Infallible, PhantomData<fn() -> &'closures ()>),
@@ -168,7 +170,10 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Codeforces<'_> {
         };
 
         let mut sess = Session::new(timeout, Some(cookie_storage), shell)?;
-        let mut outcome = RetrieveTestCasesOutcome { problems: vec![] };
+        let mut outcome = RetrieveTestCasesOutcome {
+            problems: vec![],
+            num_reused_files: 0,
+        };
 
         for (contest, problems) in targets {
             let (_, contest_name, _) = participate(&mut sess, &mut username_and_password, contest)?;
@@ -180,6 +185,14 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Codeforces<'_> {
                     .collect::<BTreeSet<_>>()
             });
 
+            let (_, api_problems, _) =
+                sess.api_contest_standings(contest, None, Some(1), "", "", false)?;
+
+            let points = api_problems
+                .into_iter()
+                .flat_map(|api::Problem { index, points, .. }| Some((index, points? as u64)))
+                .collect::<HashMap<_, _>>();
+
             let contest = &RetrieveTestCasesOutcomeProblemContest {
                 id: contest.to_string(),
                 display_name: contest_name,
@@ -202,21 +215,27 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Codeforces<'_> {
                             }
                         }
 
-                        let test_suite = sess
+                        let html = sess
                             .get(url.clone())
                             .colorize_status_code(&[200], (), ..)
                             .send()?
-                            .html()?
-                            .extract_test_cases()?;
+                            .html()?;
+
+                        let test_suite = html.extract_test_cases()?;
+                        let statement = html.extract_statement();
 
                         Ok(Some(RetrieveTestCasesOutcomeProblem {
                             contest: Some(contest.clone()),
+                            points: points.get(&index).copied(),
+                            difficulty_stars: None,
                             index,
                             url,
                             screen_name: None,
                             display_name,
                             test_suite,
                             text_files: indexmap!(),
+                            statement,
+                            checker: None,
                         }))
                     })
                     .flat_map(Result::transpose)
@@ -300,7 +319,13 @@ impl<S: Shell> Exec<Submit<Self, S>> for Codeforces<'_> {
             .ensure_status(&[200, 302])?;
 
         if res.status() == 200 {
-            bail!("Submission rejected");
+            match res.html()?.extract_submit_error() {
+                Some(err) if err == DUPLICATE_SUBMISSION => {
+                    Err(ServiceErrorKind::DuplicateSubmission.into())
+                }
+                Some(err) => bail!("Submission rejected: {}", err),
+                None => bail!("Submission rejected"),
+            }
         } else {
             let submissions_url = res.location_url()?;
 
@@ -464,7 +489,7 @@ fn is_gym(contest_id: u64) -> bool {
     contest_id >= 100_000
 }
 
-#[ext]
+#[ext(HtmlExt)]
 impl Html {
     fn extract_hidden_values(&self, form: &Selector) -> anyhow::Result<HashMap<String, String>> {
         let mut values = self
@@ -512,6 +537,12 @@ impl Html {
         .with_context(|| "Could not extract the available languages")
     }
 
+    fn extract_statement(&self) -> Option<String> {
+        self.select(static_selector!("#pageContent div.problem-statement"))
+            .next()
+            .map(|r| r.text().collect())
+    }
+
     fn extract_problems(&self) -> anyhow::Result<Vec<(String, String, Url)>> {
         self.select(static_selector!("table.problems > tbody > tr"))
             .skip(1)
@@ -611,12 +642,16 @@ impl Html {
                 out: Some(out.into()),
                 r#match: None,
                 timelimit: None,
+                origin: Some(TestCaseOrigin::Sample),
+                note: None,
             })
             .collect();
 
         return Ok(TestSuite::Batch(BatchTestSuite {
             timelimit: Some(timelimit),
             r#match,
+            float_tolerance: None,
+            generator: None,
             cases,
             extend: vec![],
         }));
@@ -636,6 +671,13 @@ impl Html {
         }
     }
 
+    /// Extracts the error shown on the re-rendered submit page, if any.
+    fn extract_submit_error(&self) -> Option<String> {
+        self.select(static_selector!("form.submit-form span.error"))
+            .map(|r| r.text().collect::<String>().trim().to_owned())
+            .find(|text| !text.is_empty())
+    }
+
     fn extract_meta_x_csrf_token(&self) -> anyhow::Result<String> {
         self.select(static_selector!("meta[name=\"X-Csrf-Token\"]"))
             .next()
@@ -701,6 +743,8 @@ mod api {
         pub(super) index: String,
         /// > String. Localized.
         pub(super) name: String,
+        /// > Floating point number. Can be absent. Maximum amount of points for the problem.
+        pub(super) points: Option<f64>,
         // __rest: (),
     }
 
@@ -897,3 +941,36 @@ mod api {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlExt as _;
+    use maplit::hashmap;
+    use scraper::Html;
+
+    #[test]
+    fn extract_submit_page() {
+        let html = Html::parse_document(include_str!(
+            "../../tests/fixtures/codeforces-contest-1349-submit-duplicate.html"
+        ));
+
+        assert_eq!(
+            html.extract_hidden_values(static_selector!("form.submit-form"))
+                .unwrap(),
+            hashmap!(
+                "csrf_token".to_owned() => "0123456789abcdef0123456789abcdef".to_owned(),
+                "ftaa".to_owned() => "0".repeat(18),
+                "bfaa".to_owned() => "n/a".to_owned(),
+                "action".to_owned() => "submitSolutionFormSubmitted".to_owned(),
+            ),
+        );
+        assert_eq!(
+            html.extract_meta_x_csrf_token().unwrap(),
+            "0123456789abcdef0123456789abcdef",
+        );
+        assert_eq!(
+            html.extract_submit_error().as_deref(),
+            Some(super::DUPLICATE_SUBMISSION),
+        );
+    }
+}
@@ -53,18 +53,21 @@ macro_rules! url {
     };
 }
 
+mod aoj;
 mod atcoder;
 mod codeforces;
 mod yukicoder;
 
 pub use crate::web::{
+    aoj::{Aoj, AojRetrieveTestCasesTargets},
     atcoder::{
-        contest_id_from_url as atcoder_contest_id, Atcoder, AtcoderLoginCredentials,
-        AtcoderParticipateCredentials, AtcoderParticipateTarget,
+        contest_id_from_url as atcoder_contest_id,
+        resolve_language_id as atcoder_resolve_language_id, Atcoder, AtcoderLatestSubmission,
+        AtcoderLoginCredentials, AtcoderParticipateCredentials, AtcoderParticipateTarget,
         AtcoderRetrieveFullTestCasesCredentials, AtcoderRetrieveLanguagesCredentials,
         AtcoderRetrieveLanguagesTarget, AtcoderRetrieveSampleTestCasesCredentials,
         AtcoderRetrieveSubmissionSummariesCredentials, AtcoderRetrieveSubmissionSummariesOutcome,
-        AtcoderRetrieveSubmissionSummariesTarget, AtcoderSubmitCredentials,
+        AtcoderRetrieveSubmissionSummariesTarget, AtcoderSubmitCredentials, AtcoderSubmittedCode,
         AtcoderWatchSubmissionsCredentials, AtcoderWatchSubmissionsTarget,
     },
     codeforces::{
@@ -74,13 +77,15 @@ pub use crate::web::{
         CodeforcesRetrieveSampleTestCasesCredentials, CodeforcesSubmitCredentials,
     },
     yukicoder::{
-        Yukicoder, YukicoderRetrieveFullTestCasesCredentials, YukicoderRetrieveTestCasesTargets,
+        Yukicoder, YukicoderRetrieveFullTestCasesCredentials,
+        YukicoderRetrieveRecentProblemsOutcome, YukicoderRetrieveTestCasesTargets,
         YukicoderSubmitCredentials, YukicoderSubmitTarget,
     },
 };
 
 use crate::testsuite::TestSuite;
 use anyhow::{anyhow, bail, Context as _};
+use chrono::{DateTime, Utc};
 use cookie_store::CookieStore;
 use derivative::Derivative;
 use derive_more::{Display, From};
@@ -88,8 +93,9 @@ use easy_ext::ext;
 use fs2::FileExt as _;
 use futures_util::StreamExt as _;
 use indexmap::IndexMap;
-use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use itertools::Itertools as _;
+use once_cell::sync::Lazy;
 use prettytable::{
     cell,
     format::{FormatBuilder, LinePosition, LineSeparator},
@@ -100,24 +106,25 @@ use scraper::Html;
 use serde::{Deserialize, Serialize, Serializer};
 use std::{
     any,
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
     cell::RefCell,
-    collections::BTreeSet,
+    collections::{hash_map, BTreeSet, HashMap},
     convert::TryInto,
-    fmt,
+    env, fmt,
     fs::File,
     hash::Hash,
     io::{self, BufReader, Seek as _, SeekFrom},
     marker::PhantomData,
+    num::NonZeroUsize,
     ops::{Deref, RangeFull, RangeInclusive},
     path::{Path, PathBuf},
     str,
-    sync::Mutex,
+    sync::{Arc, Mutex, PoisonError},
     time::Duration,
 };
 use strum::EnumString;
 use termcolor::Ansi;
-use tokio::runtime::Runtime;
+use tokio::{runtime::Runtime, sync::Semaphore};
 use unicode_width::UnicodeWidthStr as _;
 use url::Url;
 
@@ -159,17 +166,19 @@ pub enum PlatformKind {
     Atcoder,
     Codeforces,
     Yukicoder,
+    Aoj,
 }
 
 impl PlatformKind {
     pub const KEBAB_CASE_VARIANTS: &'static [&'static str] =
-        &["atcoder", "codeforces", "yukicoder"];
+        &["atcoder", "codeforces", "yukicoder", "aoj"];
 
     pub fn from_url(url: &Url) -> anyhow::Result<Self> {
         match url.domain() {
-            Some("atcoder.jp") => Ok(Self::Atcoder),
+            Some("atcoder.jp") | Some("beta.atcoder.jp") => Ok(Self::Atcoder),
             Some("codeforces.com") => Ok(Self::Codeforces),
             Some("yukicoder.me") => Ok(Self::Yukicoder),
+            Some("onlinejudge.u-aizu.ac.jp") | Some("judge.u-aizu.ac.jp") => Ok(Self::Aoj),
             Some(_) => bail!("unknown domain: {}", url),
             None => bail!("missing domain: {}", url),
         }
@@ -180,6 +189,7 @@ impl PlatformKind {
             Self::Atcoder => "atcoder",
             Self::Codeforces => "codeforces",
             Self::Yukicoder => "yukicoder",
+            Self::Aoj => "aoj",
         }
     }
 
@@ -188,6 +198,7 @@ impl PlatformKind {
             Self::Atcoder => "Atcoder",
             Self::Codeforces => "Codeforces",
             Self::Yukicoder => "Yukicoder",
+            Self::Aoj => "Aoj",
         }
     }
 }
@@ -266,17 +277,7 @@ impl RetrieveLanguagesOutcome {
     }
 
     pub fn to_table(&self) -> impl fmt::Display {
-        let mut table = Table::new();
-
-        *table.get_format() = FormatBuilder::new()
-            .padding(1, 1)
-            .column_separator('│')
-            .borders('│')
-            .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
-            .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
-            .separator(LinePosition::Intern, LineSeparator::new('─', '┼', '├', '┤'))
-            .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
-            .build();
+        let mut table = new_table();
 
         table.set_titles(row!["ID", "Name"]);
 
@@ -299,12 +300,31 @@ pub struct RetrieveTestCases<P: Platform, S: Shell> {
 
 pub struct RetrieveFullTestCases<P: Platform> {
     pub credentials: P::RetrieveFullTestCasesCredentials,
-}
+    /// Free space at the destination in bytes. If the listed files are larger, nothing is
+    /// downloaded.
+    pub available_space: Option<u64>,
+    /// Where a file is saved, from the problem index, `"in"` or `"out"`, and the file name.
+    ///
+    /// If set, files already there with the same content are not downloaded again, and each
+    /// downloaded file is written there so that an interrupted download can be resumed. Only
+    /// AtCoder uses this.
+    pub saved_path: Option<SavedPath>,
+    /// Maximum number of files downloaded at once.
+    ///
+    /// Sending all of the requests at once gets rate-limited for problems with many test files.
+    pub parallelism: NonZeroUsize,
+}
+
+/// See [`RetrieveFullTestCases::saved_path`].
+pub type SavedPath = Box<dyn Fn(&str, &str, &str) -> PathBuf>;
 
 #[non_exhaustive]
 #[derive(Debug, Serialize)]
 pub struct RetrieveTestCasesOutcome {
     pub problems: Vec<RetrieveTestCasesOutcomeProblem>,
+    /// Number of the full test case files reused from [`RetrieveFullTestCases::saved_path`].
+    #[serde(skip)]
+    pub num_reused_files: usize,
 }
 
 #[non_exhaustive]
@@ -317,6 +337,18 @@ pub struct RetrieveTestCasesOutcomeProblem {
     pub display_name: String,
     pub test_suite: TestSuite,
     pub text_files: IndexMap<String, RetrieveTestCasesOutcomeProblemTextFiles>,
+    #[serde(skip)]
+    pub statement: Option<String>,
+    /// Link to a checker program (`.py`, `.rb`, or `.cpp`) in the statement. Only for AtCoder.
+    #[serde(skip)]
+    pub checker: Option<Url>,
+    /// Points of the problem. Only for AtCoder and Codeforces.
+    #[serde(skip)]
+    pub points: Option<u64>,
+    /// Difficulty stars (e.g. `☆☆★`) in the task table. Only for AtCoder, and only some contests
+    /// show them.
+    #[serde(skip)]
+    pub difficulty_stars: Option<String>,
 }
 
 #[non_exhaustive]
@@ -336,6 +368,103 @@ pub struct RetrieveSubmissionSummaries<P: Platform, S: Shell> {
     pub shell: S,
 }
 
+pub struct RetrieveTopSubmissions<P: Platform, S: Shell> {
+    pub target: ProblemInContest,
+    pub credentials: P::RetrieveSubmissionSummariesCredentials,
+    pub limit: usize,
+    pub cookie_storage: P::CookieStorage,
+    pub timeout: Option<Duration>,
+    pub shell: S,
+}
+
+pub struct RetrieveSubmittedCode<P: Platform, S: Shell> {
+    pub target: ProblemInContest,
+    /// Part of the language name on the service (e.g. "Rust"). Any language if `None`.
+    pub language: Option<String>,
+    /// Verdicts to look for. Only accepted submissions if `None`.
+    pub verdicts: Option<Vec<SubmissionVerdict>>,
+    pub credentials: P::RetrieveSubmissionSummariesCredentials,
+    pub cookie_storage: P::CookieStorage,
+    pub timeout: Option<Duration>,
+    pub shell: S,
+}
+
+/// Final verdict of a submission.
+#[derive(EnumString, Debug, strum::Display, Copy, Clone, Eq, PartialEq, Hash)]
+#[strum(serialize_all = "lowercase")]
+pub enum SubmissionVerdict {
+    Ac,
+    Wa,
+    Tle,
+    Mle,
+    Ole,
+    Re,
+    Ce,
+    Ie,
+}
+
+impl SubmissionVerdict {
+    pub const VARIANTS: &'static [&'static str] =
+        &["ac", "wa", "tle", "mle", "ole", "re", "ce", "ie"];
+}
+
+pub struct RetrieveContestDuration<P: Platform, S: Shell> {
+    pub contest: String,
+    pub cookie_storage: P::CookieStorage,
+    pub timeout: Option<Duration>,
+    pub shell: S,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ContestDuration {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// The clock of the service minus the local clock. Zero unless the skew exceeds
+    /// [`CLOCK_SKEW_THRESHOLD`].
+    #[serde(rename = "clock_offset_millis", serialize_with = "serialize_millis")]
+    pub clock_offset: chrono::Duration,
+}
+
+impl ContestDuration {
+    /// `start` in the local clock.
+    pub fn local_start(&self) -> DateTime<Utc> {
+        self.start - self.clock_offset
+    }
+
+    /// `end` in the local clock.
+    pub fn local_end(&self) -> DateTime<Utc> {
+        self.end - self.clock_offset
+    }
+}
+
+fn serialize_millis<S: Serializer>(
+    duration: &chrono::Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_i64(duration.num_milliseconds())
+}
+
+pub struct RetrieveRatedRange<P: Platform, S: Shell> {
+    pub contest: String,
+    pub cookie_storage: P::CookieStorage,
+    pub timeout: Option<Duration>,
+    pub shell: S,
+}
+
+/// Unbounded ends are `0` and `u32::MAX`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RatedRange {
+    pub low: u32,
+    pub high: u32,
+}
+
+pub struct RetrieveRecentProblems<P: Platform, S: Shell> {
+    pub limit: usize,
+    pub cookie_storage: P::CookieStorage,
+    pub timeout: Option<Duration>,
+    pub shell: S,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RetrieveTestCasesOutcomeProblemTextFiles {
     pub r#in: String,
@@ -351,6 +480,18 @@ pub struct WatchSubmissions<P: Platform, S: Shell> {
     pub shell: S,
 }
 
+/// Polls the most recent submission until it is judged.
+pub struct WatchSubmission<P: Platform, S: Shell> {
+    pub target: P::WatchSubmissionsTarget,
+    pub credentials: P::WatchSubmissionsCredentials,
+    pub interval: Duration,
+    /// Fails if the submission is not judged within this.
+    pub max_duration: Option<Duration>,
+    pub cookie_storage: P::CookieStorage,
+    pub timeout: Option<Duration>,
+    pub shell: S,
+}
+
 struct AnsiColored(Vec<u8>);
 
 impl AnsiColored {
@@ -391,6 +532,18 @@ impl SubmitOutcome {
     }
 }
 
+/// An error reported by a service itself, as opposed to a failure of scraping.
+///
+/// Returned inside `anyhow::Error`. Use `downcast_ref` to distinguish it.
+#[non_exhaustive]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceErrorKind {
+    #[display(fmt = "You have submitted exactly the same code before")]
+    DuplicateSubmission,
+}
+
+impl std::error::Error for ServiceErrorKind {}
+
 #[derive(Debug, Clone, Ord, Eq, PartialOrd, PartialEq)]
 pub enum ProblemsInContest {
     Indexes {
@@ -408,15 +561,122 @@ pub enum ProblemInContest {
     Url { url: Url },
 }
 
+/// Format of the cookie jar written by this version: one JSON object per cookie and line.
+pub const COOKIE_JAR_FORMAT: u32 = 1;
+
+/// Written next to a cookie jar, so that versions of `snowchains_core` with different formats do
+/// not overwrite each other's jar.
+///
+/// A jar without it is in format 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CookieJarManifest {
+    pub format: u32,
+    pub writer: String,
+}
+
+impl CookieJarManifest {
+    fn current() -> Self {
+        Self {
+            format: COOKIE_JAR_FORMAT,
+            writer: concat!("snowchains_core ", env!("CARGO_PKG_VERSION")).to_owned(),
+        }
+    }
+
+    /// `cookies.jsonl` → `cookies.manifest.json`
+    pub fn path(jar: &Path) -> PathBuf {
+        jar.with_extension("manifest.json")
+    }
+
+    pub fn load(jar: &Path) -> anyhow::Result<Option<Self>> {
+        let path = Self::path(jar);
+        if !path.exists() {
+            return Ok(None);
+        }
+        std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|s| serde_json::from_str(&s).map_err(Into::into))
+            .with_context(|| format!("Could not read `{}`", path.display()))
+            .map(Some)
+    }
+
+    fn save_current(jar: &Path) -> anyhow::Result<()> {
+        let path = Self::path(jar);
+        std::fs::write(&path, serde_json::to_string(&Self::current())?)
+            .with_context(|| format!("Could not write `{}`", path.display()))
+    }
+
+    fn ensure_compatible(manifest: Option<&Self>, jar: &Path) -> anyhow::Result<()> {
+        match manifest {
+            Some(manifest) if manifest.format != COOKIE_JAR_FORMAT => {
+                let current = Self::current();
+                bail!(
+                    "`{}` is in format {} written by {}, but this is {} with format {}. Refusing to \
+                     overwrite it. Run `snowchains migrate cookies --format {}` with {} to convert \
+                     it",
+                    jar.display(),
+                    manifest.format,
+                    manifest.writer,
+                    current.writer,
+                    current.format,
+                    current.format,
+                    manifest.writer,
+                );
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 pub struct CookieStorage {
     pub cookie_store: CookieStore,
     pub on_update: Box<dyn Fn(&CookieStore) -> anyhow::Result<()>>,
 }
 
 impl CookieStorage {
+    /// Converts the jar at `path` into `format`, and records it in the [`CookieJarManifest`].
+    ///
+    /// Only [`COOKIE_JAR_FORMAT`] can be read and written by this version.
+    pub fn migrate_jsonl<P: AsRef<Path>>(path: P, format: u32) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        if format != COOKIE_JAR_FORMAT {
+            bail!(
+                "This version can only write format {}, not {}",
+                COOKIE_JAR_FORMAT,
+                format,
+            );
+        }
+
+        if let Some(manifest) = CookieJarManifest::load(path)? {
+            if manifest.format != COOKIE_JAR_FORMAT {
+                bail!(
+                    "`{}` is in format {}, which this version cannot read. Run `snowchains \
+                     migrate cookies --format {}` with {}",
+                    path.display(),
+                    manifest.format,
+                    format,
+                    manifest.writer,
+                );
+            }
+        }
+
+        if path.exists() {
+            let Self { cookie_store, .. } = Self::with_jsonl(path)?;
+            let mut file = create_secret_file(path)
+                .with_context(|| format!("Could not open `{}`", path.display()))?;
+            cookie_store
+                .save_json(&mut file)
+                .map_err(|e| anyhow!("{}", e))
+                .with_context(|| format!("Could not write `{}`", path.display()))?;
+        }
+        CookieJarManifest::save_current(path)
+    }
+
     pub fn with_jsonl<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
         let path = path.as_ref();
 
+        CookieJarManifest::ensure_compatible(CookieJarManifest::load(path)?.as_ref(), path)?;
+
         let cookie_store = if path.exists() {
             File::open(&path)
                 .map_err(anyhow::Error::from)
@@ -429,12 +689,14 @@ impl CookieStorage {
         };
 
         let file = LazyLockedFile::new(&path);
+        let jar = path.to_owned();
 
         let on_update = Box::new(move |cookie_store: &CookieStore| -> _ {
             file.overwrite(|file| {
                 cookie_store.save_json(file).map_err(|e| anyhow!("{}", e))?;
                 Ok(())
-            })
+            })?;
+            CookieJarManifest::save_current(&jar)
         });
 
         return Ok(Self {
@@ -473,7 +735,7 @@ impl CookieStorage {
                         }
                     }
 
-                    let new_file = File::create(&path)
+                    let new_file = create_secret_file(path)
                         .with_context(|| format!("Could not open `{}`", path.display()))?;
 
                     new_file
@@ -498,6 +760,72 @@ impl CookieStorage {
     }
 }
 
+/// Creates or truncates a file that only the owner can read and write.
+///
+/// Existing files are narrowed too. On non-Unix platforms this is the same as `File::create`.
+fn create_secret_file(path: &Path) -> io::Result<File> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt as _, PermissionsExt as _};
+
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+
+    #[cfg(not(unix))]
+    {
+        File::create(path)
+    }
+}
+
+/// Masks cookie values, credentials in headers, and values of secret-looking keys such as
+/// `password`, `token`, or `apiSig`.
+///
+/// This is meant for text shown to users or dumped for debugging. It is not a guarantee.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let mut text = Cow::Borrowed(text);
+
+    // A `Set-Cookie` has one cookie followed by the attributes, and a `Cookie` has only cookies.
+    let cookie = static_regex!(r"(?im)^([ \t]*(set-)?cookie:[ \t]*)(.*)$");
+    if cookie.is_match(&text) {
+        text = cookie
+            .replace_all(&text, |caps: &regex::Captures<'_>| {
+                let pair = static_regex!(r"([^=;\s]+)=[^;]*");
+                let pairs = if caps.get(2).is_some() {
+                    pair.replace(&caps[3], "${1}=***")
+                } else {
+                    pair.replace_all(&caps[3], "${1}=***")
+                };
+                format!("{}{}", &caps[1], pairs)
+            })
+            .into_owned()
+            .into();
+    }
+
+    let authorization = static_regex!(r"(?im)^([ \t]*(?:proxy-)?authorization:[ \t]*).*$");
+    if authorization.is_match(&text) {
+        text = authorization
+            .replace_all(&text, "${1}***")
+            .into_owned()
+            .into();
+    }
+
+    let key_value = static_regex!(
+        r#"(?i)(["']?\b(?:access_token|api_?key|api_?secret|api_?sig|csrf_?token|password|passwd|secret|token|REVEL_SESSION)\b["']?[ \t]*[:=][ \t]*["']?)[^"'&\s,;}]+"#
+    );
+    if key_value.is_match(&text) {
+        text = key_value.replace_all(&text, "${1}***").into_owned().into();
+    }
+
+    text
+}
+
 pub trait Shell {
     fn progress_draw_target(&self) -> ProgressDrawTarget {
         ProgressDrawTarget::hidden()
@@ -511,10 +839,15 @@ pub trait Shell {
         Ok(())
     }
 
+    /// Called right before each request is sent, after the cookies are attached.
+    ///
+    /// Downloads of test case files and `HEAD` requests to warm connections do not go through
+    /// this.
     fn on_request(&mut self, _request: &reqwest::blocking::Request) -> io::Result<()> {
         Ok(())
     }
 
+    /// Called right after each response is received, before the cookies are stored.
     fn on_response(
         &mut self,
         _response: &reqwest::blocking::Response,
@@ -612,10 +945,42 @@ pub enum StatusCodeColor {
     Unknown,
 }
 
+/// Skews of the local clock up to this are ignored.
+pub const CLOCK_SKEW_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Timeout of [`Session::warm_connection`], which is independent of the one of the session.
+const WARM_CONNECTION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Every option that the clients of [`Session`] are built with.
+///
+/// The clients have no cookie store. Each session attaches the cookies in its own jar to the
+/// requests, so sharing a client does not share cookies.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct ClientOptions {
+    user_agent: &'static str,
+    gzip: bool,
+    /// Whether the client follows redirects. `SessionRequestBuilder` follows them by itself.
+    redirect: bool,
+    timeout: Option<Duration>,
+}
+
+impl ClientOptions {
+    fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            user_agent: "snowchains <https://github.com/qryxip/snowchains>",
+            gzip: true,
+            redirect: false,
+            timeout,
+        }
+    }
+}
+
 struct Session<S> {
     async_client: reqwest::Client,
     blocking_client: reqwest::blocking::Client,
     cookie_storage: Option<CookieStorage>,
+    /// Measured from the `Date` header of the first response.
+    clock_offset: Option<chrono::Duration>,
     shell: S,
 }
 
@@ -625,11 +990,40 @@ impl<S: Shell> Session<S> {
         cookie_storage: Option<CookieStorage>,
         shell: S,
     ) -> anyhow::Result<Self> {
+        Self::with_options(timeout, cookie_storage, shell, false)
+    }
+
+    /// The blocking client is shared with the other sessions with the same [`ClientOptions`] in
+    /// this process so that connections are reused across them. Each session still has its own
+    /// cookie jar. `force_new_client` opts out of it.
+    fn with_options(
+        timeout: Option<Duration>,
+        cookie_storage: Option<CookieStorage>,
+        shell: S,
+        force_new_client: bool,
+    ) -> anyhow::Result<Self> {
+        static BLOCKING_CLIENTS: Lazy<Mutex<HashMap<ClientOptions, reqwest::blocking::Client>>> =
+            Lazy::new(Default::default);
+
+        let options = ClientOptions::new(timeout);
+
         macro_rules! client(($builder:path) => {{
+            let ClientOptions {
+                user_agent,
+                gzip,
+                redirect,
+                timeout,
+            } = options;
+
             let client = $builder()
-                .user_agent(USER_AGENT)
+                .user_agent(user_agent)
+                .gzip(gzip)
                 .cookie_store(false)
-                .redirect(Policy::none());
+                .redirect(if redirect {
+                    Policy::default()
+                } else {
+                    Policy::none()
+                });
 
             if let Some(timeout) = timeout {
                 client.timeout(timeout).build()
@@ -638,18 +1032,34 @@ impl<S: Shell> Session<S> {
             }
         }});
 
+        // Not shared. Its connections belong to the Tokio runtime that opened them, and each
+        // download creates its own runtime.
         let async_client = client!(reqwest::ClientBuilder::new)?;
-        let blocking_client = client!(reqwest::blocking::ClientBuilder::new)?;
+
+        let blocking_client = if force_new_client {
+            client!(reqwest::blocking::ClientBuilder::new)?
+        } else {
+            // Only built clients are inserted, so the map is intact even if the lock is poisoned.
+            match BLOCKING_CLIENTS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(options)
+            {
+                hash_map::Entry::Occupied(entry) => entry.get().clone(),
+                hash_map::Entry::Vacant(entry) => entry
+                    .insert(client!(reqwest::blocking::ClientBuilder::new)?)
+                    .clone(),
+            }
+        };
 
         return Ok(Self {
             async_client,
             blocking_client,
             cookie_storage,
+            clock_offset: None,
             shell,
         });
 
-        static USER_AGENT: &str = "snowchains <https://github.com/qryxip/snowchains>";
-
         trait DummyMethod: Sized {
             fn cookie_store(self, _: bool) -> Self {
                 self
@@ -659,6 +1069,26 @@ impl<S: Shell> Session<S> {
         impl DummyMethod for reqwest::ClientBuilder {}
         impl DummyMethod for reqwest::blocking::ClientBuilder {}
     }
+
+    /// Sends a `HEAD` request to `url` and discards the response so that the TCP connection and
+    /// the TLS session are already pooled when the first meaningful request is sent.
+    ///
+    /// The request has its own [`WARM_CONNECTION_TIMEOUT`]. Failing to connect is an error, since
+    /// the following requests would fail in the same way. Any other failure including the timeout
+    /// is ignored, and the first meaningful request just opens a new connection.
+    fn warm_connection(&mut self, url: Url) -> anyhow::Result<()> {
+        match self
+            .blocking_client
+            .head(url.clone())
+            .timeout(WARM_CONNECTION_TIMEOUT)
+            .send()
+        {
+            Err(err) if err.is_connect() => {
+                Err(err).with_context(|| format!("Could not connect to {}", url))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 trait SessionMut: Sized {
@@ -670,6 +1100,10 @@ trait SessionMut: Sized {
 
     fn cookie_store(&self) -> Option<&CookieStore>;
 
+    /// The clock of the service minus the local clock. Zero if it is not measured yet or the skew
+    /// is small enough.
+    fn clock_offset(&self) -> chrono::Duration;
+
     fn request(&mut self, method: Method, url: Url) -> SessionRequestBuilder<'_, Self::Shell>;
 
     fn cookie_header(&self, url: &Url) -> String {
@@ -705,6 +1139,10 @@ impl<S: Shell> SessionMut for Session<S> {
             .map(|CookieStorage { cookie_store, .. }| cookie_store)
     }
 
+    fn clock_offset(&self) -> chrono::Duration {
+        self.clock_offset.unwrap_or_else(chrono::Duration::zero)
+    }
+
     fn request(&mut self, method: Method, url: Url) -> SessionRequestBuilder<'_, S> {
         SessionRequestBuilder {
             inner: self.blocking_client.request(method, url.clone()),
@@ -731,11 +1169,52 @@ impl<S: SessionMut> SessionMut for &'_ mut S {
         (**self).cookie_store()
     }
 
+    fn clock_offset(&self) -> chrono::Duration {
+        (**self).clock_offset()
+    }
+
     fn request(&mut self, method: Method, url: Url) -> SessionRequestBuilder<'_, S::Shell> {
         (**self).request(method, url)
     }
 }
 
+/// How many times [`send_with_relogin`] logs in again before giving up.
+const MAX_RELOGINS: usize = 2;
+
+/// Sends a request with `send`. If the response redirects to `login_path` because the session has
+/// expired in the middle of a long operation, logs in again with `login` and resends it.
+fn send_with_relogin<S: SessionMut>(
+    mut sess: S,
+    login_path: &str,
+    mut send: impl FnMut(&mut S) -> anyhow::Result<reqwest::blocking::Response>,
+    mut login: impl FnMut(&mut S) -> anyhow::Result<()>,
+) -> anyhow::Result<reqwest::blocking::Response> {
+    let mut relogins = 0;
+
+    loop {
+        let res = send(&mut sess)?;
+
+        let expired = res.status().is_redirection()
+            && matches!(res.location_url(), Ok(url) if url.path() == login_path);
+
+        if !expired {
+            return Ok(res);
+        }
+        if relogins == MAX_RELOGINS {
+            bail!(
+                "Redirected to `{}` even after logging in {} times",
+                login_path,
+                MAX_RELOGINS,
+            );
+        }
+
+        sess.shell()
+            .warn("The session has expired. Logging in again")?;
+        login(&mut sess)?;
+        relogins += 1;
+    }
+}
+
 struct SessionRequestBuilder<'a, S> {
     inner: reqwest::blocking::RequestBuilder,
     url: Url,
@@ -816,6 +1295,25 @@ impl<S: Shell> SessionRequestBuilder<'_, S> {
         sess.shell
             .on_response(&res, colorize_status_code(res.status()))?;
 
+        if sess.clock_offset.is_none() {
+            if let Some(date) = res.headers().get(header::DATE) {
+                let offset = clock_offset(date, Utc::now());
+                if let Some(offset) = offset {
+                    sess.shell.warn(format!(
+                        "The local clock is {}s {} {}. The time of the service is used instead",
+                        offset.num_seconds().abs(),
+                        if offset > chrono::Duration::zero() {
+                            "behind"
+                        } else {
+                            "ahead of"
+                        },
+                        url.host_str().unwrap_or("the service"),
+                    ))?;
+                }
+                sess.clock_offset = Some(offset.unwrap_or_else(chrono::Duration::zero));
+            }
+        }
+
         if let Some(CookieStorage {
             cookie_store,
             on_update,
@@ -833,10 +1331,82 @@ impl<S: Shell> SessionRequestBuilder<'_, S> {
             }
         }
 
+        // The URL of a rebuilt response is lost, so redirects are kept as they are for
+        // `location_url`.
+        if matches!(env::var_os("SNOWCHAINS_LOG_BODIES"), Some(v) if v == "1")
+            && !res.status().is_redirection()
+        {
+            return log_body(res, &mut sess.shell);
+        }
+
         Ok(res)
     }
 }
 
+/// Returns `date` minus `now` if the difference exceeds [`CLOCK_SKEW_THRESHOLD`].
+fn clock_offset(date: &header::HeaderValue, now: DateTime<Utc>) -> Option<chrono::Duration> {
+    let date = DateTime::parse_from_rfc2822(date.to_str().ok()?).ok()?;
+    let offset = date.with_timezone(&Utc) - now;
+    let threshold = chrono::Duration::from_std(CLOCK_SKEW_THRESHOLD).unwrap();
+    if offset > threshold || offset < -threshold {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+/// Saves the body of `res` to a temporary file and prints the path, for debugging the scrapers.
+///
+/// The files are removed by later calls once they are older than 5 minutes.
+fn log_body(
+    res: reqwest::blocking::Response,
+    mut shell: impl Shell,
+) -> anyhow::Result<reqwest::blocking::Response> {
+    const TTL: Duration = Duration::from_secs(5 * 60);
+
+    let dir = env::temp_dir().join("snowchains").join("bodies");
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Could not create `{}`", dir.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("Could not restrict `{}`", dir.display()))?;
+    }
+
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let expired = matches!(
+            entry.metadata().and_then(|m| m.modified()).map(|t| t.elapsed()),
+            Ok(Ok(elapsed)) if elapsed > TTL
+        );
+        if expired {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+
+    let mut builder = http::Response::builder()
+        .status(res.status())
+        .version(res.version());
+    for (name, value) in res.headers() {
+        builder = builder.header(name, value);
+    }
+    let body = res.bytes()?;
+
+    let (_, path) = tempfile::Builder::new()
+        .prefix("body-")
+        .tempfile_in(&dir)?
+        .keep()?;
+    // `tempfile` already creates the file with `0600` on Unix.
+    // Decoded lossily so that a body that is not valid UTF-8 is redacted too.
+    let dumped = redact(&String::from_utf8_lossy(&body)).into_owned();
+    std::fs::write(&path, dumped)
+        .with_context(|| format!("Could not write `{}`", path.display()))?;
+
+    shell.print_ansi(format!("\x1b[2mbody:\x1b[0m {}\n", path.display()).as_bytes())?;
+
+    Ok(builder.body(body)?.into())
+}
+
 trait StatusCodeRange: 'static {
     fn contains(&self, status: StatusCode) -> bool;
 }
@@ -978,15 +1548,57 @@ where
     }
 }
 
+pub(crate) fn new_table() -> Table {
+    let mut table = Table::new();
+
+    *table.get_format() = FormatBuilder::new()
+        .padding(1, 1)
+        .column_separator('│')
+        .borders('│')
+        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Intern, LineSeparator::new('─', '┼', '├', '┤'))
+        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+        .build();
+
+    table
+}
+
 // https://github.com/rust-lang/rust-clippy/issues/5991
 #[allow(clippy::needless_collect)]
+fn ensure_available_space(required: u64, available: Option<u64>) -> anyhow::Result<()> {
+    if let Some(available) = available {
+        if required > available {
+            bail!(
+                "The test cases take {} but only {} is available at the destination",
+                HumanBytes(required),
+                HumanBytes(available),
+            );
+        }
+    }
+    Ok(())
+}
+
 fn download_with_progress(
     draw_target: ProgressDrawTarget,
+    parallelism: NonZeroUsize,
     dl_targets: Vec<(String, reqwest::RequestBuilder)>,
 ) -> anyhow::Result<Vec<String>> {
+    download_each_with_progress(draw_target, parallelism, dl_targets)?
+        .into_iter()
+        .collect()
+}
+
+/// Like [`download_with_progress`], but a failed download does not discard the other ones.
+fn download_each_with_progress(
+    draw_target: ProgressDrawTarget,
+    parallelism: NonZeroUsize,
+    dl_targets: Vec<(String, reqwest::RequestBuilder)>,
+) -> anyhow::Result<Vec<anyhow::Result<String>>> {
     let rt = Runtime::new()?;
     let mp = MultiProgress::with_draw_target(draw_target);
     let name_width = dl_targets.iter().map(|(s, _)| s.width()).max().unwrap_or(0);
+    let semaphore = Arc::new(Semaphore::new(parallelism.get()));
 
     let handles = dl_targets
         .into_iter()
@@ -995,7 +1607,14 @@ fn download_with_progress(
             pb.set_style(progress_style("{prefix:.bold} Waiting..."));
             pb.set_prefix(&align_left(&name, name_width));
 
+            let semaphore = semaphore.clone();
+
             rt.spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("the semaphore should not be closed");
+
                 let res = req.send().await?;
 
                 tokio::task::block_in_place(|| {
@@ -1034,9 +1653,13 @@ fn download_with_progress(
     return handles
         .into_iter()
         .map(|handle| {
-            String::from_utf8(rt.block_on(handle)??).with_context(|| "Invalid UTF-8 content")
+            let content = rt.block_on(handle)?;
+            Ok(content.map_err(Into::into).and_then(|content| {
+                String::from_utf8(content).with_context(|| "Invalid UTF-8 content")
+            }))
         })
-        .collect();
+        .collect::<Result<_, tokio::task::JoinError>>()
+        .map_err(Into::into);
 
     fn progress_style(template: &str) -> ProgressStyle {
         ProgressStyle::default_bar().template(template)
@@ -1047,3 +1670,281 @@ fn download_with_progress(
         s.chars().chain(itertools::repeat_n(' ', spaces)).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CookieJarManifest, ResponseExt as _, Session, SessionMut as _, Shell, COOKIE_JAR_FORMAT,
+    };
+    use anyhow::anyhow;
+    use chrono::{TimeZone as _, Utc};
+    use reqwest::header::HeaderValue;
+    use std::{
+        io::{self, Read as _, Write as _},
+        net::{TcpListener, TcpStream},
+        path::Path,
+        thread,
+        time::Duration,
+    };
+    use url::Url;
+
+    struct NullShell;
+
+    impl Shell for NullShell {}
+
+    fn read_request(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+        let mut req = vec![];
+        let mut buf = [0; 1024];
+        while !req.ends_with(b"\r\n\r\n") {
+            match stream.read(&mut buf)? {
+                0 => break,
+                n => req.extend_from_slice(&buf[..n]),
+            }
+        }
+        Ok(req)
+    }
+
+    #[test]
+    fn gzip() -> anyhow::Result<()> {
+        static BODY: &[u8] = include_bytes!("../../tests/fixtures/gzip-hello.html.gz");
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/", listener.local_addr()?).parse()?;
+
+        let server = thread::spawn(move || -> io::Result<String> {
+            let (mut stream, _) = listener.accept()?;
+            let req = read_request(&mut stream)?;
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: text/html\r\n\
+                 Content-Encoding: gzip\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\
+                 \r\n",
+                BODY.len(),
+            )?;
+            stream.write_all(BODY)?;
+            Ok(String::from_utf8_lossy(&req).to_lowercase())
+        });
+
+        let html = Session::new(Some(Duration::from_secs(10)), None, NullShell)?
+            .get(url)
+            .send()?
+            .html()?;
+
+        let req = server.join().unwrap()?;
+        assert!(req.contains("accept-encoding: gzip"), "{}", req);
+
+        let h1 = html
+            .select(static_selector!("h1"))
+            .flat_map(|r| r.text())
+            .collect::<String>();
+        assert_eq!("Hello", h1);
+        Ok(())
+    }
+
+    #[test]
+    fn clock_offset() {
+        let now = Utc.ymd(2020, 5, 10).and_hms(12, 0, 0);
+        let f = |date| super::clock_offset(&HeaderValue::from_static(date), now);
+
+        assert_eq!(None, f("Sun, 10 May 2020 12:00:29 GMT"));
+        assert_eq!(None, f("Sun, 10 May 2020 11:59:31 GMT"));
+        assert_eq!(
+            Some(chrono::Duration::minutes(4)),
+            f("Sun, 10 May 2020 12:04:00 GMT"),
+        );
+        assert_eq!(
+            Some(chrono::Duration::seconds(-45)),
+            f("Sun, 10 May 2020 11:59:15 GMT"),
+        );
+        assert_eq!(None, f("invalid"));
+    }
+
+    #[test]
+    fn shared_client() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/", listener.local_addr()?).parse::<Url>()?;
+
+        // Fails with a timeout unless the first two requests come through the same connection.
+        let server = thread::spawn(move || -> io::Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+            for _ in 0..2 {
+                read_request(&mut stream)?;
+                stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")?;
+            }
+
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept()?;
+                read_request(&mut stream)?;
+                stream.write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )?;
+            }
+            Ok(())
+        });
+
+        // Unusual timeouts so that no other test shares the clients. The third session has
+        // another timeout and the fourth one opts out, so each of them has its own client.
+        let sessions = [
+            (10_001, false),
+            (10_001, false),
+            (10_002, false),
+            (10_001, true),
+        ];
+
+        for &(timeout, force_new_client) in &sessions {
+            let timeout = Some(Duration::from_millis(timeout));
+            Session::with_options(timeout, None, NullShell, force_new_client)?
+                .get(url.clone())
+                .send()?
+                .text()?;
+            // Lets the connection go back to the pool.
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        server.join().unwrap()?;
+        Ok(())
+    }
+
+    #[test]
+    fn warm_connection() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/", listener.local_addr()?).parse::<Url>()?;
+
+        // Fails with a timeout unless the `GET` comes through the connection opened by the `HEAD`.
+        let server = thread::spawn(move || -> io::Result<Vec<u8>> {
+            let (mut stream, _) = listener.accept()?;
+            stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+            let head = read_request(&mut stream)?;
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")?;
+            read_request(&mut stream)?;
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+            Ok(head)
+        });
+
+        let mut sess = Session::with_options(None, None, NullShell, true)?;
+        sess.warm_connection(url.clone())?;
+        sess.get(url).send()?.text()?;
+
+        assert!(server.join().unwrap()?.starts_with(b"HEAD / HTTP/1.1\r\n"));
+
+        // Nothing listens on the port anymore.
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            format!("http://{}/", listener.local_addr()?).parse::<Url>()?
+        };
+        assert!(Session::new(None, None, NullShell)?
+            .warm_connection(url)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn send_with_relogin() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!(
+            "http://{}/contests/abc100/tasks_print",
+            listener.local_addr()?
+        )
+        .parse::<Url>()?;
+
+        let server = thread::spawn(move || -> io::Result<()> {
+            static RESPONSES: &[&str] = &[
+                "HTTP/1.1 302 Found\r\n\
+                 Location: /login?continue=https%3A%2F%2Fatcoder.jp%2Fcontests%2Fabc100%2Ftasks_print\r\n\
+                 Content-Length: 0\r\n\
+                 Connection: close\r\n\
+                 \r\n",
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Length: 0\r\n\
+                 Connection: close\r\n\
+                 \r\n",
+            ];
+
+            for res in RESPONSES {
+                let (mut stream, _) = listener.accept()?;
+                read_request(&mut stream)?;
+                stream.write_all(res.as_bytes())?;
+            }
+            Ok(())
+        });
+
+        let mut sess = Session::new(Some(Duration::from_secs(10)), None, NullShell)?;
+        let mut logins = 0;
+
+        let res = super::send_with_relogin(
+            &mut sess,
+            "/login",
+            |sess| sess.get(url.clone()).send(),
+            |_| {
+                logins += 1;
+                Ok(())
+            },
+        )?;
+
+        server.join().unwrap()?;
+
+        assert_eq!(200, res.status().as_u16());
+        assert_eq!(1, logins);
+        Ok(())
+    }
+
+    #[test]
+    fn cookie_jar_manifest() {
+        let jar = Path::new("snowchains").join("cookies.jsonl");
+
+        assert_eq!(
+            Path::new("snowchains").join("cookies.manifest.json"),
+            CookieJarManifest::path(&jar),
+        );
+
+        let current = CookieJarManifest::current();
+        assert!(CookieJarManifest::ensure_compatible(None, &jar).is_ok());
+        assert!(CookieJarManifest::ensure_compatible(Some(&current), &jar).is_ok());
+
+        let newer = CookieJarManifest {
+            format: COOKIE_JAR_FORMAT + 1,
+            writer: "snowchains_core 99.0.0".to_owned(),
+        };
+        let err = CookieJarManifest::ensure_compatible(Some(&newer), &jar)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("snowchains_core 99.0.0"), "{}", err);
+        assert!(err.contains(&current.writer), "{}", err);
+        assert!(err.contains("snowchains migrate cookies"), "{}", err);
+    }
+
+    #[test]
+    fn redact() {
+        let err = anyhow!("HTTP/1.1 302 Found\nSet-Cookie: REVEL_SESSION=d6e3f1; Path=/; HttpOnly")
+            .context("Could not log in");
+        let rendered = super::redact(&format!("{:?}", err)).into_owned();
+        assert!(!rendered.contains("d6e3f1"), "{}", rendered);
+        assert!(rendered.contains("Set-Cookie: REVEL_SESSION=***; Path=/; HttpOnly"));
+        assert!(rendered.contains("Could not log in"));
+
+        assert_eq!(
+            super::redact("Cookie: REVEL_SESSION=d6e3f1; REVEL_FLASH=abc"),
+            "Cookie: REVEL_SESSION=***; REVEL_FLASH=***",
+        );
+
+        assert_eq!(
+            super::redact("Authorization: Bearer abcdef"),
+            "Authorization: ***",
+        );
+        assert_eq!(
+            super::redact(r#"{"access_token":"abcdef","user":"wariuni"}"#),
+            r#"{"access_token":"***","user":"wariuni"}"#,
+        );
+        assert_eq!(
+            super::redact("https://codeforces.com/api/user.status?apiKey=k&time=1&apiSig=s"),
+            "https://codeforces.com/api/user.status?apiKey=***&time=1&apiSig=***",
+        );
+        assert_eq!(super::redact("nothing to hide"), "nothing to hide",);
+    }
+}
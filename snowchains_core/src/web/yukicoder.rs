@@ -1,12 +1,12 @@
 use crate::{
     testsuite::{
         BatchTestSuite, InteractiveTestSuite, Match, PartialBatchTestCase, PositiveFinite,
-        TestSuite,
+        TestCaseOrigin, TestSuite,
     },
     web::{
         yukicoder::api::SessionMutExt as _, Exec, Platform, ResponseExt as _,
-        RetrieveFullTestCases, RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveTestCases,
-        RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeProblem,
+        RetrieveFullTestCases, RetrieveLanguages, RetrieveLanguagesOutcome, RetrieveRecentProblems,
+        RetrieveTestCases, RetrieveTestCasesOutcome, RetrieveTestCasesOutcomeProblem,
         RetrieveTestCasesOutcomeProblemContest, RetrieveTestCasesOutcomeProblemTextFiles, Session,
         SessionMut, Shell, Submit, SubmitOutcome,
     },
@@ -17,8 +17,11 @@ use either::Either;
 use indexmap::indexmap;
 use itertools::Itertools as _;
 use once_cell::sync::Lazy;
+use prettytable::{cell, row};
 use scraper::{ElementRef, Html, Node};
-use std::{collections::BTreeSet, convert::Infallible, hash::Hash, time::Duration};
+use std::{
+    cmp::Reverse, collections::BTreeSet, convert::Infallible, fmt, hash::Hash, time::Duration,
+};
 use url::Url;
 
 static BASE_URL: Lazy<Url> = lazy_url!("https://yukicoder.me");
@@ -75,6 +78,70 @@ impl<S: Shell> Exec<RetrieveLanguages<Self, S>> for Yukicoder {
     }
 }
 
+impl<S: Shell> Exec<RetrieveRecentProblems<Self, S>> for Yukicoder {
+    type Output = YukicoderRetrieveRecentProblemsOutcome;
+
+    fn exec(
+        args: RetrieveRecentProblems<Self, S>,
+    ) -> anyhow::Result<YukicoderRetrieveRecentProblemsOutcome> {
+        let RetrieveRecentProblems {
+            limit,
+            cookie_storage: (),
+            timeout,
+            shell,
+        } = args;
+
+        let mut problems = Session::new(timeout, None, shell)?
+            .get_all_problems_raw()?
+            .into_iter()
+            .map(|raw| {
+                let summary = serde_json::from_value(raw.clone())?;
+                Ok((summary, raw))
+            })
+            .collect::<serde_json::Result<Vec<(api::ProblemSummary, _)>>>()?;
+
+        problems.sort_by_key(|(p, _)| Reverse(p.date));
+        problems.truncate(limit);
+
+        Ok(YukicoderRetrieveRecentProblemsOutcome { problems })
+    }
+}
+
+#[derive(Debug)]
+pub struct YukicoderRetrieveRecentProblemsOutcome {
+    problems: Vec<(api::ProblemSummary, serde_json::Value)>,
+}
+
+impl YukicoderRetrieveRecentProblemsOutcome {
+    /// Returns the elements of the API response as they are.
+    pub fn to_json(&self) -> String {
+        let raw = self.problems.iter().map(|(_, raw)| raw).collect::<Vec<_>>();
+        serde_json::to_string(&raw).expect("should not fail")
+    }
+
+    pub fn to_table(&self) -> impl fmt::Display {
+        let mut table = super::new_table();
+
+        table.set_titles(row!["No", "Title", "Level", "Tags"]);
+
+        for (
+            api::ProblemSummary {
+                no,
+                title,
+                level,
+                tags,
+                ..
+            },
+            _,
+        ) in &self.problems
+        {
+            table.add_row(row![r->no, title, r->level, tags.as_deref().unwrap_or("")]);
+        }
+
+        table
+    }
+}
+
 impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Yukicoder {
     type Output = RetrieveTestCasesOutcome;
 
@@ -94,6 +161,9 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Yukicoder {
 
         if let Some(RetrieveFullTestCases {
             credentials: YukicoderRetrieveFullTestCasesCredentials { api_key },
+            available_space: _,
+            saved_path: _,
+            parallelism,
         }) = full
         {
             for outcome_problem in &mut outcome.problems {
@@ -109,6 +179,7 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Yukicoder {
 
                 let in_contents = super::download_with_progress(
                     sess.shell.progress_draw_target(),
+                    parallelism,
                     in_file_names
                         .iter()
                         .map(|file_name| {
@@ -128,6 +199,7 @@ impl<S: Shell> Exec<RetrieveTestCases<Self, S>> for Yukicoder {
 
                 let out_contents = super::download_with_progress(
                     sess.shell.progress_draw_target(),
+                    parallelism,
                     out_file_names
                         .iter()
                         .map(|file_name| {
@@ -316,14 +388,17 @@ fn retrieve_samples(
     mut sess: impl SessionMut,
     targets: YukicoderRetrieveTestCasesTargets,
 ) -> anyhow::Result<RetrieveTestCasesOutcome> {
-    let mut outcome = RetrieveTestCasesOutcome { problems: vec![] };
+    let mut outcome = RetrieveTestCasesOutcome {
+        problems: vec![],
+        num_reused_files: 0,
+    };
 
     match targets {
         YukicoderRetrieveTestCasesTargets::ProblemNos(problem_nos) => {
             for problem_no in &problem_nos {
                 let problem_no = parse_problem_no(problem_no)?;
 
-                let (url, test_suite) = retrieve_samples(&mut sess, problem_no)?;
+                let (url, test_suite, statement) = retrieve_samples(&mut sess, problem_no)?;
                 let api::Problem {
                     problem_id, title, ..
                 } = sess.get_problem_by_problem_no(problem_no)?;
@@ -336,6 +411,10 @@ fn retrieve_samples(
                     display_name: title.clone(),
                     test_suite,
                     text_files: indexmap!(),
+                    statement,
+                    checker: None,
+                    points: None,
+                    difficulty_stars: None,
                 });
             }
         }
@@ -389,7 +468,7 @@ fn retrieve_samples(
                 }
 
                 let api::Problem { no, title, .. } = sess.get_problem_by_problem_id(problem_id)?;
-                let (url, test_suite) = retrieve_samples(&mut sess, no)?;
+                let (url, test_suite, statement) = retrieve_samples(&mut sess, no)?;
 
                 outcome.problems.push(RetrieveTestCasesOutcomeProblem {
                     contest: Some(contest.clone()),
@@ -399,6 +478,10 @@ fn retrieve_samples(
                     display_name: title,
                     test_suite,
                     text_files: indexmap!(),
+                    statement,
+                    checker: None,
+                    points: None,
+                    difficulty_stars: None,
                 });
             }
 
@@ -419,7 +502,7 @@ fn retrieve_samples(
                     Either::Right(problem_id) => sess.get_problem_by_problem_id(problem_id)?,
                 };
 
-                let (_, test_suite) = retrieve_samples(&mut sess, no)?;
+                let (_, test_suite, statement) = retrieve_samples(&mut sess, no)?;
 
                 outcome.problems.push(RetrieveTestCasesOutcomeProblem {
                     contest: None,
@@ -429,6 +512,10 @@ fn retrieve_samples(
                     display_name: title.clone(),
                     test_suite,
                     text_files: indexmap!(),
+                    statement,
+                    checker: None,
+                    points: None,
+                    difficulty_stars: None,
                 });
             }
         }
@@ -439,23 +526,31 @@ fn retrieve_samples(
     fn retrieve_samples(
         mut sess: impl SessionMut,
         problem_no: u64,
-    ) -> anyhow::Result<(Url, TestSuite)> {
+    ) -> anyhow::Result<(Url, TestSuite, Option<String>)> {
         let url = url!("/problems/no/{}", problem_no);
 
-        let test_suite = sess
+        let html = sess
             .get(url.clone())
             .colorize_status_code(&[200], (), ..)
             .send()?
             .ensure_status(&[200])?
-            .html()?
-            .extract_samples()?;
+            .html()?;
+
+        let test_suite = html.extract_samples()?;
+        let statement = html.extract_statement();
 
-        Ok((url, test_suite))
+        Ok((url, test_suite, statement))
     }
 }
 
 #[ext]
 impl Html {
+    fn extract_statement(&self) -> Option<String> {
+        self.select(static_selector!("#content > div.block"))
+            .next()
+            .map(|r| r.text().collect())
+    }
+
     fn extract_samples(&self) -> anyhow::Result<TestSuite> {
         let (timelimit, kind) = self
             .select(static_selector!("#content > div"))
@@ -517,6 +612,8 @@ impl Html {
                 let mut test_suite = BatchTestSuite {
                     timelimit: Some(timelimit),
                     r#match,
+                    float_tolerance: None,
+                    generator: None,
                     cases: vec![],
                     extend: vec![],
                 };
@@ -542,6 +639,8 @@ impl Html {
                             },
                             timelimit: None,
                             r#match: None,
+                            origin: Some(TestCaseOrigin::Sample),
+                            note: None,
                         });
                     } else {
                         bail!("Could not extract sample cases");
@@ -692,6 +791,18 @@ mod api {
                 .map_err(Into::into)
         }
 
+        /// Same as `get_all_problems`, but keeps every field.
+        fn get_all_problems_raw(&mut self) -> anyhow::Result<Vec<serde_json::Value>> {
+            let url = BASE_URL.join("problems").unwrap();
+
+            self.get(url)
+                .colorize_status_code(&[200], (), ..)
+                .send()?
+                .ensure_status(&[200])?
+                .json()
+                .map_err(Into::into)
+        }
+
         /// > Get available language
         ///
         /// > 利用できる言語を取得します。何度も呼び出すような想定ではありません
@@ -808,6 +919,16 @@ mod api {
         //date: chrono::DateTime<chrono::FixedOffset>,
     }
 
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub(super) struct ProblemSummary {
+        pub(super) no: u64,
+        pub(super) title: String,
+        pub(super) level: f64,
+        pub(super) tags: Option<String>,
+        pub(super) date: Option<chrono::DateTime<chrono::FixedOffset>>,
+    }
+
     #[derive(Debug, strum::Display)]
     #[strum(serialize_all = "lowercase")]
     pub(super) enum Which {
@@ -0,0 +1,35 @@
+use std::io::{BufRead, Write};
+use structopt::{clap, StructOpt};
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompletions {
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Shell
+    #[structopt(possible_values(&clap::Shell::variants()))]
+    pub shell: clap::Shell,
+}
+
+pub(crate) fn run(
+    opt: OptCompletions,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptCompletions {
+        color: _,
+        shell: target,
+    } = opt;
+
+    let crate::Context { mut shell, .. } = ctx;
+
+    crate::Opt::clap().gen_completions_to(env!("CARGO_PKG_NAME"), target, &mut shell.stdout);
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptFlushOutbox {
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+}
+
+pub(crate) fn run(
+    opt: OptFlushOutbox,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptFlushOutbox { config, color: _ } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (_, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    crate::outbox::flush(&workspace, &mut shell)
+}
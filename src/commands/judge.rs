@@ -1,7 +1,8 @@
 use crate::config;
 use human_size::Size;
-use snowchains_core::web::PlatformKind;
-use std::path::PathBuf;
+use indicatif::ProgressDrawTarget;
+use snowchains_core::{testsuite::TestCaseOrigin, web::PlatformKind};
+use std::{collections::HashSet, path::PathBuf};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use termcolor::WriteColor;
@@ -16,10 +17,50 @@ pub struct OptJudge {
     #[structopt(long, value_name("NAME"))]
     pub testcases: Option<Vec<String>>,
 
+    /// Test for only the sample cases
+    #[structopt(long, conflicts_with("system-only"))]
+    pub samples_only: bool,
+
+    /// Test for only the system test cases
+    #[structopt(long)]
+    pub system_only: bool,
+
+    /// Ignore trailing whitespace of each line and of the whole output
+    #[structopt(long)]
+    pub compare_ignore_trailing_whitespace: bool,
+
     /// Display limit
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
 
+    /// Saves the result under `.snowchains/runs` for `show last-judge-summary`
+    #[structopt(long)]
+    pub record: bool,
+
+    /// Judges again whenever the source file changes, until Ctrl-C
+    #[structopt(long)]
+    pub watch: bool,
+
+    /// Does not post to `notifications.webhook`
+    #[structopt(long)]
+    pub no_notify: bool,
+
+    /// Uses the existing binary instead of transpiling and compiling the code
+    #[structopt(long)]
+    pub skip_compile: bool,
+
+    /// Transpiles and compiles the code even if the binary is newer than it
+    #[structopt(long, conflicts_with("skip-compile"))]
+    pub force_compile: bool,
+
+    /// Does not show the diffs of the expected and actual outputs for `Wrong Answer`
+    #[structopt(long)]
+    pub no_diff: bool,
+
+    /// Prints the result as JSON to stdout. The other output goes to stderr
+    #[structopt(long, conflicts_with("watch"))]
+    pub json: bool,
+
     /// Path to `snowchains.dhall`
     #[structopt(long)]
     pub config: Option<PathBuf>,
@@ -49,6 +90,10 @@ pub struct OptJudge {
     #[structopt(short, long, value_name("STRING"))]
     pub language: Option<String>,
 
+    /// Alternative solution (e.g. "brute")
+    #[structopt(long, value_name("STRING"))]
+    pub variant: Option<String>,
+
     /// Problem index (e.g. "a", "b", "c")
     pub problem: Option<String>,
 }
@@ -60,50 +105,36 @@ pub(crate) fn run(
     let OptJudge {
         release,
         testcases,
+        samples_only,
+        system_only,
+        compare_ignore_trailing_whitespace,
         display_limit,
+        record,
+        watch,
+        no_notify,
+        skip_compile,
+        force_compile,
+        no_diff,
+        json,
         config,
         color: _,
         service,
         contest,
         language,
+        variant,
         problem,
     } = opt;
 
-    let crate::Context { cwd, shell } = ctx;
+    let crate::Context { cwd, mut shell } = ctx;
 
-    let progress_draw_target = shell.progress_draw_target();
-
-    let crate::shell::Shell {
-        stdout,
-        stderr,
-        stdin_process_redirection,
-        stdout_process_redirection,
-        stderr_process_redirection,
-        ..
-    } = shell;
-
-    let (
-        config::Target {
-            service,
-            contest,
-            problem,
-            ..
-        },
-        config::Language {
-            src,
-            transpile,
-            compile,
-            run,
-            languageId: _,
-        },
-        base_dir,
-    ) = config::target_and_language(
+    let (target, language, base_dir) = config::target_and_language(
         &cwd,
         config.as_deref(),
         service,
         contest.as_deref(),
         problem.as_deref(),
         language.as_deref(),
+        variant.as_deref(),
         if release {
             config::Mode::Release
         } else {
@@ -111,24 +142,103 @@ pub(crate) fn run(
         },
     )?;
 
-    let test_case_names = testcases.map(|ss| ss.into_iter().collect());
+    let extra_test_dirs = config::extra_test_dirs(&cwd, config.as_deref(), &target)?;
 
-    crate::judge::judge(crate::judge::Args {
-        stdout,
-        stderr,
-        stdin_process_redirection,
-        stdout_process_redirection,
-        stderr_process_redirection,
-        progress_draw_target,
-        base_dir,
+    let config::Target {
         service,
         contest,
         problem,
+        variant,
+        ..
+    } = target;
+
+    for warning in language.path_style_warnings(config::is_wsl()) {
+        shell.warn(warning)?;
+    }
+
+    let config::Language {
         src,
         transpile,
         compile,
         run,
-        test_case_names,
-        display_limit,
-    })
+        languageId: _,
+    } = language;
+
+    let config::JudgeOptions {
+        timelimitScale: timelimit_scale,
+        stdinEcho: stdin_echo,
+        exportEnv: export_env,
+        utf8Env: utf8_env,
+    } = config::judge_options(&cwd, config.as_deref())?;
+
+    let webhook = if no_notify {
+        None
+    } else {
+        config::webhook(&cwd, config.as_deref())?
+    };
+
+    let crate::shell::Shell {
+        mut stdout,
+        mut stderr,
+        stderr_tty,
+        stdin_process_redirection,
+        stdout_process_redirection,
+        stderr_process_redirection,
+        ..
+    } = shell;
+
+    let test_case_names = testcases.map(|ss| ss.into_iter().collect::<HashSet<_>>());
+
+    let origin = match (samples_only, system_only) {
+        (true, _) => Some(TestCaseOrigin::Sample),
+        (_, true) => Some(TestCaseOrigin::System),
+        _ => None,
+    };
+
+    let src_path = base_dir.join(&src);
+
+    let judge = |stdout: &mut _, stderr: &mut _| {
+        crate::judge::judge(crate::judge::Args {
+            stdout,
+            stderr,
+            stdin_process_redirection,
+            stdout_process_redirection,
+            stderr_process_redirection,
+            progress_draw_target: if stderr_tty {
+                ProgressDrawTarget::stderr()
+            } else {
+                ProgressDrawTarget::hidden()
+            },
+            base_dir: base_dir.clone(),
+            service,
+            contest: contest.clone(),
+            problem: problem.clone(),
+            variant: variant.clone(),
+            src: src.clone(),
+            transpile: transpile.clone(),
+            compile: compile.clone(),
+            run: run.clone(),
+            test_case_names: test_case_names.clone(),
+            origin,
+            ignore_trailing_whitespace: compare_ignore_trailing_whitespace,
+            timelimit_scale,
+            stdin_echo,
+            export_env,
+            utf8_env,
+            display_limit,
+            record,
+            webhook: webhook.clone(),
+            extra_test_dirs: extra_test_dirs.clone(),
+            skip_compile,
+            force_compile,
+            diff: !no_diff,
+            json_output: json,
+        })
+    };
+
+    if watch {
+        crate::watch::judge_on_change(vec![src_path], stdout, stderr, stderr_tty, judge)
+    } else {
+        judge(&mut stdout, &mut stderr)
+    }
 }
@@ -0,0 +1,98 @@
+use crate::config;
+use anyhow::Context as _;
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptLangSet {
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: String,
+
+    /// Language name in `languages`
+    pub language: String,
+}
+
+pub(crate) fn run(
+    opt: OptLangSet,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptLangSet {
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+        language,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    // Fails if `languages` does not return `language` for the problem.
+    config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        Some(service),
+        contest.as_deref(),
+        Some(&problem),
+        Some(&language),
+        None,
+        config::Mode::Debug,
+    )?;
+
+    let mut pinned_languages = config::PinnedLanguages::load(&workspace)?;
+    pinned_languages.set(service, contest.as_deref(), &problem, &language);
+    let path = pinned_languages.save(&workspace)?;
+
+    shell.stderr.set_color(color_spec!(Bold))?;
+    write!(shell.stderr, "{}:", problem)?;
+    shell.stderr.reset()?;
+    write!(shell.stderr, " Pinned `{}` in ", language)?;
+    shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+    write!(shell.stderr, "{}", path.display())?;
+    shell.stderr.reset()?;
+    writeln!(shell.stderr)?;
+    shell.stderr.flush()?;
+    Ok(())
+}
@@ -89,7 +89,9 @@ pub(crate) fn run(
                 shell: &shell,
             })
         }
-        PlatformKind::Yukicoder => unreachable!("should be filtered by `possible_values`"),
+        PlatformKind::Yukicoder | PlatformKind::Aoj => {
+            unreachable!("should be filtered by `possible_values`")
+        }
     }?;
 
     let message = if json {
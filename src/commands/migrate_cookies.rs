@@ -0,0 +1,47 @@
+use snowchains_core::web::{CookieJarManifest, CookieStorage, COOKIE_JAR_FORMAT};
+use std::io::{BufRead, Write};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptMigrateCookies {
+    /// Format to convert the cookie jar into. Defaults to the one of this version
+    #[structopt(long, value_name("NUMBER"))]
+    pub format: Option<u32>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+}
+
+pub(crate) fn run(
+    opt: OptMigrateCookies,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptMigrateCookies { format, color: _ } = opt;
+
+    let format = format.unwrap_or(COOKIE_JAR_FORMAT);
+
+    let crate::Context { mut shell, .. } = ctx;
+
+    let path = crate::web::credentials::cookie_store_path()?;
+
+    let from = CookieJarManifest::load(&path)?.map_or(1, |CookieJarManifest { format, .. }| format);
+
+    CookieStorage::migrate_jsonl(&path, format)?;
+
+    writeln!(
+        shell.stderr,
+        "Converted `{}` from format {} into format {}",
+        path.display(),
+        from,
+        format,
+    )?;
+    shell.stderr.flush()?;
+    Ok(())
+}
@@ -1,10 +1,41 @@
+pub(crate) mod completions;
+pub(crate) mod flush_outbox;
+pub(crate) mod generate;
 pub(crate) mod init;
 pub(crate) mod judge;
+pub(crate) mod lang_set;
 pub(crate) mod login;
+pub(crate) mod migrate_cookies;
+pub(crate) mod modify_match;
+pub(crate) mod modify_timelimit;
+pub(crate) mod open;
 pub(crate) mod participate;
 pub(crate) mod retrieve_languages;
+pub(crate) mod retrieve_problems;
 pub(crate) mod retrieve_submission_summaries;
 pub(crate) mod retrieve_testcases;
+pub(crate) mod show_all_config_keys;
+pub(crate) mod show_compile_flags;
+pub(crate) mod show_cookie_expiry;
+pub(crate) mod show_expected;
+pub(crate) mod show_in;
+pub(crate) mod show_last_judge_summary;
+pub(crate) mod show_problem_category;
+pub(crate) mod show_problem_difficulty;
+pub(crate) mod show_rated_range;
+pub(crate) mod show_recent_problems;
+pub(crate) mod show_submission_code;
+pub(crate) mod show_submission_score;
+pub(crate) mod show_test_case;
+pub(crate) mod show_test_case_hash;
+pub(crate) mod show_test_case_time_limit;
+pub(crate) mod show_top_submissions;
+pub(crate) mod show_total_ac_problems;
+pub(crate) mod show_unreachable_languages;
+pub(crate) mod show_wasm_size;
+pub(crate) mod stress;
 pub(crate) mod submit;
+pub(crate) mod validate;
+pub(crate) mod watch_submission;
 pub(crate) mod watch_submissions;
 pub(crate) mod xtask;
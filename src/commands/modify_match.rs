@@ -0,0 +1,176 @@
+use anyhow::{bail, Context as _};
+use snowchains_core::{
+    testsuite::{BatchTestSuite, Match, PositiveFinite, TestSuite},
+    web::PlatformKind,
+};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptModifyMatch {
+    /// Compares the outputs exactly
+    #[structopt(long, required_unless("float"), conflicts_with("float"))]
+    pub exact: bool,
+
+    /// Compares the numbers in the outputs with `--relative-error` and `--absolute-error`
+    #[structopt(long)]
+    pub float: bool,
+
+    /// Relative error for `--float`
+    #[structopt(long, value_name("EPS"), requires("float"))]
+    pub relative_error: Option<PositiveFinite<f64>>,
+
+    /// Absolute error for `--float`
+    #[structopt(long, value_name("EPS"), requires("float"))]
+    pub absolute_error: Option<PositiveFinite<f64>>,
+
+    /// Modifies every test suite in the contest
+    #[structopt(long, conflicts_with("problem"))]
+    pub all: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptModifyMatch,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptModifyMatch {
+        exact,
+        float,
+        relative_error,
+        absolute_error,
+        all,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let new_match = match (exact, float) {
+        (true, _) => Match::Exact,
+        (_, true) if relative_error.is_none() && absolute_error.is_none() => {
+            bail!("`--float` requires `--relative-error` and/or `--absolute-error`")
+        }
+        (_, true) => Match::Float {
+            relative_error,
+            absolute_error,
+        },
+        (false, false) => unreachable!("`--exact` or `--float` is required"),
+    };
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let paths = crate::commands::modify_timelimit::test_suite_paths(
+        &workspace,
+        service,
+        contest.as_deref(),
+        problem.or(detected_target.problem),
+        all,
+    )?;
+
+    for path in paths {
+        let path = crate::suite_link::resolve(&path)?;
+        let mut test_suite = crate::fs::read_yaml::<TestSuite, _>(&path)?;
+
+        let old = match &mut test_suite {
+            TestSuite::Batch(BatchTestSuite {
+                r#match,
+                float_tolerance,
+                ..
+            }) => {
+                if exact {
+                    // It would still make the numbers fuzzy.
+                    *float_tolerance = None;
+                }
+                std::mem::replace(r#match, new_match.clone())
+            }
+            _ => {
+                writeln!(
+                    shell.stderr,
+                    "{}: not a batch test suite. Skipping",
+                    path.display()
+                )?;
+                continue;
+            }
+        };
+
+        crate::fs::write(&path, test_suite.to_yaml_pretty(), false)?;
+
+        writeln!(
+            shell.stderr,
+            "{}: {} -> {}",
+            path.display(),
+            format_match(&old),
+            format_match(&new_match),
+        )?;
+    }
+
+    shell.stderr.flush()?;
+    Ok(())
+}
+
+fn format_match(r#match: &Match) -> String {
+    let format_error = |error: &Option<PositiveFinite<f64>>| {
+        error.map_or_else(|| "none".to_owned(), |e| e.get().to_string())
+    };
+
+    match r#match {
+        Match::Exact => "Exact".to_owned(),
+        Match::SplitWhitespace => "SplitWhitespace".to_owned(),
+        Match::Lines => "Lines".to_owned(),
+        Match::Float {
+            relative_error,
+            absolute_error,
+        } => format!(
+            "Float (relative_error: {}, absolute_error: {})",
+            format_error(relative_error),
+            format_error(absolute_error),
+        ),
+        Match::Checker { .. } => "Checker".to_owned(),
+        Match::CheckerCommand { .. } => "CheckerCommand".to_owned(),
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::{bail, ensure, Context as _};
+use snowchains_core::{
+    testsuite::{Additional, BatchTestSuite, InteractiveTestSuite, ScoringTestSuite, TestSuite},
+    web::PlatformKind,
+};
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptModifyTimelimit {
+    /// Sets the timelimit to the value
+    #[structopt(
+        long,
+        value_name("MILLIS"),
+        required_unless("scale"),
+        conflicts_with("scale")
+    )]
+    pub millis: Option<u64>,
+
+    /// Multiplies the timelimits (including ones of each case) by the factor
+    #[structopt(long, value_name("FACTOR"))]
+    pub scale: Option<f64>,
+
+    /// Modifies every test suite in the contest
+    #[structopt(long, conflicts_with("problem"))]
+    pub all: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptModifyTimelimit,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptModifyTimelimit {
+        millis,
+        scale,
+        all,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    if let Some(scale) = scale {
+        ensure!(
+            scale.is_finite() && scale > 0.0,
+            "`--scale` must be a positive finite number",
+        );
+    }
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let paths = test_suite_paths(
+        &workspace,
+        service,
+        contest.as_deref(),
+        problem.or(detected_target.problem),
+        all,
+    )?;
+
+    let modify = |timelimit: &mut Option<Duration>| match (millis, scale) {
+        (Some(millis), _) => *timelimit = Some(Duration::from_millis(millis)),
+        (None, Some(scale)) => {
+            if let Some(timelimit) = timelimit {
+                *timelimit =
+                    Duration::from_millis((timelimit.as_millis() as f64 * scale).round() as u64);
+            }
+        }
+        (None, None) => unreachable!("`--millis` or `--scale` is required"),
+    };
+
+    for path in paths {
+        let path = crate::suite_link::resolve(&path)?;
+        let mut test_suite = crate::fs::read_yaml::<TestSuite, _>(&path)?;
+
+        let (old, new) = match &mut test_suite {
+            TestSuite::Batch(BatchTestSuite {
+                timelimit,
+                cases,
+                extend,
+                ..
+            }) => {
+                let old = *timelimit;
+                modify(timelimit);
+
+                if scale.is_some() {
+                    for case in cases {
+                        modify(&mut case.timelimit);
+                    }
+                    for additional in extend {
+                        if let Additional::Text { timelimit, .. } = additional {
+                            modify(timelimit);
+                        }
+                    }
+                }
+
+                (old, *timelimit)
+            }
+            TestSuite::Interactive(InteractiveTestSuite { timelimit })
+            | TestSuite::Scoring(ScoringTestSuite { timelimit, .. }) => {
+                let old = *timelimit;
+                modify(timelimit);
+                (old, *timelimit)
+            }
+            TestSuite::Unsubmittable => {
+                writeln!(shell.stderr, "{}: unsubmittable. Skipping", path.display())?;
+                continue;
+            }
+        };
+
+        crate::fs::write(&path, test_suite.to_yaml_pretty(), false)?;
+
+        writeln!(
+            shell.stderr,
+            "{}: {} -> {}",
+            path.display(),
+            format_timelimit(old),
+            format_timelimit(new),
+        )?;
+    }
+
+    shell.stderr.flush()?;
+    Ok(())
+}
+
+/// The test suite of `problem`, or every test suite in the contest if `all`.
+pub(crate) fn test_suite_paths(
+    workspace: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: Option<String>,
+    all: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let test_suite_dir = crate::judge::test_suite_dir(workspace, service, contest);
+
+    if all {
+        let mut paths = std::fs::read_dir(&test_suite_dir)
+            .with_context(|| format!("Could not read `{}`", test_suite_dir.display()))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.retain(|p| matches!(p.extension(), Some(e) if e == "yml"));
+        paths.sort();
+        if paths.is_empty() {
+            bail!("No test suites in `{}`", test_suite_dir.display());
+        }
+        Ok(paths)
+    } else {
+        let problem = problem
+            .with_context(|| "`problem` was not detected. Specify with the positional argument")?;
+        Ok(vec![test_suite_dir.join(problem).with_extension("yml")])
+    }
+}
+
+fn format_timelimit(timelimit: Option<Duration>) -> String {
+    timelimit.map_or_else(|| "none".to_owned(), |t| format!("{}ms", t.as_millis()))
+}
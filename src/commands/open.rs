@@ -0,0 +1,241 @@
+use crate::{
+    commands::retrieve_problems::{ContestProblem, ContestProblems},
+    config,
+};
+use anyhow::{bail, Context as _};
+use snowchains_core::web::{PlatformKind, RetrieveTestCasesOutcomeProblem};
+use std::{
+    collections::BTreeSet,
+    ffi::OsStr,
+    io::{BufRead, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptOpen {
+    /// Also opens the source files
+    #[structopt(long)]
+    pub src: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name, used for `--src`
+    #[structopt(short, long, value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Problem indexes (e.g. "a", "b", "c"). Opens the contest page if none
+    #[structopt(short, long, value_name("STRING"))]
+    pub problems: Option<Vec<String>>,
+}
+
+pub(crate) fn run(
+    opt: OptOpen,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptOpen {
+        src,
+        config,
+        color: _,
+        service,
+        contest,
+        language,
+        problems,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let problems = match (problems.unwrap_or_default(), detected_target.problem) {
+        (problems, _) if !problems.is_empty() => problems,
+        (_, Some(problem)) => vec![problem],
+        (_, None) => vec![],
+    };
+
+    if problems.is_empty() {
+        let contest = contest.with_context(|| {
+            "`contest` was not detected. To specify it, add `--contest` to the arguments"
+        })?;
+        let url = contest_url(service, &contest)?;
+        open(&mut shell.stderr, url.as_str())?;
+        return Ok(());
+    }
+
+    let problem_naming = config::problem_naming(&cwd, config.as_deref(), service)?;
+
+    let indexes = problems
+        .iter()
+        .map(|p| problem_naming.remote_index(contest.as_deref(), p))
+        .collect::<Vec<_>>();
+
+    // The problems saved by `retrieve problems` are used without accessing the service.
+    let saved = contest
+        .as_deref()
+        .map(|contest| ContestProblems::path(&workspace, service, contest))
+        .filter(|path| path.exists())
+        .map(crate::fs::read_yaml::<ContestProblems, _>)
+        .transpose()?
+        .map(|ContestProblems { problems }| problems)
+        .unwrap_or_default();
+
+    let find_saved = |index: &str| {
+        saved
+            .iter()
+            .find(|ContestProblem { index: i, .. }| i.eq_ignore_ascii_case(index))
+            .map(|ContestProblem { url, .. }| url.clone())
+    };
+
+    let missing = indexes
+        .iter()
+        .filter(|index| find_saved(index).is_none())
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let retrieved = if missing.is_empty() {
+        vec![]
+    } else {
+        crate::commands::retrieve_testcases::retrieve(
+            service,
+            contest.clone(),
+            Some(missing),
+            None,
+            &mut shell,
+        )?
+        .problems
+    };
+
+    for (problem, index) in problems.iter().zip(&indexes) {
+        let url = find_saved(index)
+            .or_else(|| {
+                retrieved
+                    .iter()
+                    .find(|RetrieveTestCasesOutcomeProblem { index: i, .. }| {
+                        i.eq_ignore_ascii_case(index)
+                    })
+                    .map(|RetrieveTestCasesOutcomeProblem { url, .. }| url.clone())
+            })
+            .with_context(|| format!("No such problem: {:?}", problem))?;
+
+        open(&mut shell.stderr, url.as_str())?;
+
+        if src {
+            let (_, config::Language { src, .. }, base_dir) = config::target_and_language(
+                &cwd,
+                config.as_deref(),
+                Some(service),
+                contest.as_deref(),
+                Some(&**problem),
+                language.as_deref(),
+                None,
+                config::Mode::Debug,
+            )?;
+
+            open(&mut shell.stderr, base_dir.join(src))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn contest_url(service: PlatformKind, contest: &str) -> anyhow::Result<Url> {
+    let url = match service {
+        PlatformKind::Atcoder => format!("https://atcoder.jp/contests/{}/tasks", contest),
+        PlatformKind::Codeforces => format!("https://codeforces.com/contest/{}", contest),
+        PlatformKind::Yukicoder => format!("https://yukicoder.me/contests/{}", contest),
+        PlatformKind::Aoj => bail!("Specify problems for AOJ"),
+    };
+    url.parse().map_err(Into::into)
+}
+
+/// Opens a URL or a file with the default application.
+fn open(mut stderr: impl Write, target: impl AsRef<OsStr>) -> anyhow::Result<()> {
+    let target = target.as_ref();
+
+    writeln!(stderr, "Opening {}", target.to_string_lossy())?;
+    stderr.flush()?;
+
+    let (program, args): (_, &[_]) = if cfg!(windows) {
+        ("cmd", &["/C", "start", ""])
+    } else if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else {
+        ("xdg-open", &[])
+    };
+
+    let status = Command::new(program)
+        .args(args)
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .status()
+        .with_context(|| format!("Could not execute `{}`", program))?;
+
+    if !status.success() {
+        bail!(
+            "`{}` failed ({}) for {}",
+            program,
+            status,
+            target.to_string_lossy(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use snowchains_core::web::PlatformKind;
+
+    #[test]
+    fn contest_url() {
+        assert_eq!(
+            "https://atcoder.jp/contests/abc100/tasks",
+            super::contest_url(PlatformKind::Atcoder, "abc100")
+                .unwrap()
+                .as_str(),
+        );
+        assert_eq!(
+            "https://codeforces.com/contest/1234",
+            super::contest_url(PlatformKind::Codeforces, "1234")
+                .unwrap()
+                .as_str(),
+        );
+        assert!(super::contest_url(PlatformKind::Aoj, "ITP1").is_err());
+    }
+}
@@ -1,8 +1,10 @@
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
+use indexmap::IndexMap;
 use snowchains_core::web::{
-    Atcoder, AtcoderRetrieveLanguagesCredentials, AtcoderRetrieveLanguagesTarget, Codeforces,
-    CodeforcesRetrieveLanguagesCredentials, CodeforcesRetrieveLanguagesTarget, CookieStorage,
-    PlatformKind, RetrieveLanguages, Yukicoder,
+    atcoder_resolve_language_id, Atcoder, AtcoderRetrieveLanguagesCredentials,
+    AtcoderRetrieveLanguagesTarget, Codeforces, CodeforcesRetrieveLanguagesCredentials,
+    CodeforcesRetrieveLanguagesTarget, CookieStorage, PlatformKind, RetrieveLanguages,
+    RetrieveLanguagesOutcome, Yukicoder,
 };
 use std::{
     cell::RefCell,
@@ -76,60 +78,7 @@ pub(crate) fn run(
     let contest = contest.or(detected_target.contest);
     let problem = problem.or(detected_target.problem);
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
-
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
-
-    let outcome =
-        match service {
-            PlatformKind::Atcoder => {
-                let shell = RefCell::new(&mut shell);
-
-                let target = AtcoderRetrieveLanguagesTarget {
-                    contest_and_problem: contest.and_then(|c| problem.map(|p| (c, p))),
-                };
-
-                let credentials = AtcoderRetrieveLanguagesCredentials {
-                    username_and_password:
-                        &mut crate::web::credentials::atcoder_username_and_password(&shell),
-                };
-
-                Atcoder::exec(RetrieveLanguages {
-                    target,
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })
-            }
-            PlatformKind::Codeforces => {
-                let shell = RefCell::new(&mut shell);
-
-                let target = CodeforcesRetrieveLanguagesTarget {
-                    contest: contest.with_context(|| "`contest` is required for Codeforces")?,
-                };
-
-                let credentials = CodeforcesRetrieveLanguagesCredentials {
-                    username_and_password:
-                        &mut crate::web::credentials::atcoder_username_and_password(&shell),
-                };
-
-                Codeforces::exec(RetrieveLanguages {
-                    target,
-                    credentials,
-                    cookie_storage,
-                    timeout,
-                    shell: &shell,
-                })
-            }
-            PlatformKind::Yukicoder => Yukicoder::exec(RetrieveLanguages {
-                target: (),
-                credentials: (),
-                cookie_storage: (),
-                timeout,
-                shell: &mut shell,
-            }),
-        }?;
+    let outcome = retrieve(service, contest, problem, &mut shell)?;
 
     if json {
         writeln!(shell.stdout, "{}", outcome.to_json())
@@ -140,3 +89,102 @@ pub(crate) fn run(
     shell.stdout.flush()?;
     Ok(())
 }
+
+pub(crate) fn retrieve(
+    service: PlatformKind,
+    contest: Option<String>,
+    problem: Option<String>,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<RetrieveLanguagesOutcome> {
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+
+    let timeout = Some(crate::web::SESSION_TIMEOUT);
+
+    match service {
+        PlatformKind::Atcoder => {
+            let shell = RefCell::new(shell);
+
+            let target = AtcoderRetrieveLanguagesTarget {
+                contest_and_problem: contest.and_then(|c| problem.map(|p| (c, p))),
+            };
+
+            let credentials = AtcoderRetrieveLanguagesCredentials {
+                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
+                    &shell,
+                ),
+            };
+
+            Atcoder::exec(RetrieveLanguages {
+                target,
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Codeforces => {
+            let shell = RefCell::new(shell);
+
+            let target = CodeforcesRetrieveLanguagesTarget {
+                contest: contest.with_context(|| "`contest` is required for Codeforces")?,
+            };
+
+            let credentials = CodeforcesRetrieveLanguagesCredentials {
+                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
+                    &shell,
+                ),
+            };
+
+            Codeforces::exec(RetrieveLanguages {
+                target,
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Yukicoder => Yukicoder::exec(RetrieveLanguages {
+            target: (),
+            credentials: (),
+            cookie_storage: (),
+            timeout,
+            shell,
+        }),
+        PlatformKind::Aoj => bail!("`retrieve languages` is not supported for AOJ"),
+    }
+}
+
+/// Whether `language_id` in `snowchains.dhall` refers to one of `names_by_id`.
+///
+/// For AtCoder, it may also be a part of the name or a `/regex/`.
+pub(crate) fn is_available(
+    service: PlatformKind,
+    names_by_id: &IndexMap<String, String>,
+    language_id: &str,
+) -> bool {
+    match service {
+        PlatformKind::Atcoder => atcoder_resolve_language_id(names_by_id, language_id).is_ok(),
+        _ => names_by_id.contains_key(language_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use snowchains_core::web::PlatformKind;
+
+    #[test]
+    fn is_available() {
+        let names_by_id = indexmap!(
+            "4003".to_owned() => "C++ (GCC 9.2.1)".to_owned(),
+            "4050".to_owned() => "Rust (1.42.0)".to_owned(),
+        );
+        let f = |service, id| super::is_available(service, &names_by_id, id);
+
+        assert!(f(PlatformKind::Atcoder, "4050"));
+        assert!(f(PlatformKind::Atcoder, "Rust"));
+        assert!(!f(PlatformKind::Atcoder, "5054"));
+        assert!(f(PlatformKind::Codeforces, "4003"));
+        assert!(!f(PlatformKind::Codeforces, "Rust"));
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use snowchains_core::web::{PlatformKind, RetrieveTestCasesOutcomeProblem};
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptRetrieveProblems {
+    /// Prints JSON data
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+}
+
+/// Stored in `.snowchains/contests/{service}/{contest}.yml`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContestProblems {
+    pub(crate) problems: Vec<ContestProblem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContestProblem {
+    pub(crate) index: String,
+    pub(crate) screen_name: Option<String>,
+    pub(crate) display_name: String,
+    pub(crate) points: Option<u64>,
+    /// e.g. `☆☆★`
+    pub(crate) difficulty_stars: Option<String>,
+    pub(crate) url: Url,
+}
+
+impl ContestProblems {
+    pub(crate) fn path(workspace: &Path, service: PlatformKind, contest: &str) -> PathBuf {
+        workspace
+            .join(".snowchains")
+            .join("contests")
+            .join(service.to_kebab_case_str())
+            .join(contest)
+            .with_extension("yml")
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        crate::fs::write(path, serde_yaml::to_string(self)?, true)
+    }
+}
+
+pub(crate) fn run(
+    opt: OptRetrieveProblems,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptRetrieveProblems {
+        json,
+        config,
+        color: _,
+        service,
+        contest,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest).with_context(|| {
+        "`contest` was not detected. To specify it, add `--contest` to the arguments"
+    })?;
+
+    // Only the test suites are discarded. Nothing under `.snowchains/tests` is touched.
+    let outcome = crate::commands::retrieve_testcases::retrieve(
+        service,
+        Some(contest.clone()),
+        None,
+        None,
+        &mut shell,
+    )?;
+
+    let problems = ContestProblems {
+        problems: outcome
+            .problems
+            .into_iter()
+            .map(
+                |RetrieveTestCasesOutcomeProblem {
+                     index,
+                     url,
+                     screen_name,
+                     display_name,
+                     points,
+                     difficulty_stars,
+                     ..
+                 }| ContestProblem {
+                    index,
+                    screen_name,
+                    display_name,
+                    points,
+                    difficulty_stars,
+                    url,
+                },
+            )
+            .collect(),
+    };
+
+    problems.save(&ContestProblems::path(&workspace, service, &contest))?;
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&problems)?)?;
+    } else {
+        for ContestProblem {
+            index,
+            display_name,
+            points,
+            difficulty_stars,
+            url,
+            ..
+        } in &problems.problems
+        {
+            let points = points
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "-".to_owned());
+            let difficulty_stars = difficulty_stars
+                .as_ref()
+                .map(|s| format!(" {}", s))
+                .unwrap_or_default();
+            writeln!(
+                shell.stdout,
+                "{:<4} {:>6}  {}{}  {}",
+                index, points, display_name, difficulty_stars, url
+            )?;
+        }
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
 use snowchains_core::web::{
     Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
     AtcoderRetrieveSubmissionSummariesTarget, CookieStorage, PlatformKind,
@@ -15,6 +15,10 @@ use termcolor::WriteColor;
 
 #[derive(StructOpt, Debug)]
 pub struct OptRetrieveSubmissionSummaries {
+    /// Retrieves at most this number of pages
+    #[structopt(long, value_name("N"))]
+    pub max_pages: Option<u32>,
+
     /// Path to `snowchains.dhall`
     #[structopt(long)]
     pub config: Option<PathBuf>,
@@ -34,6 +38,10 @@ pub struct OptRetrieveSubmissionSummaries {
     /// Contest ID
     #[structopt(short, long, value_name("STRING"))]
     pub contest: Option<String>,
+
+    /// Problem indexes (e.g. "a", "b", "c"). Stops paging once each of them has a submission
+    #[structopt(short, long, value_name("STRING"))]
+    pub problems: Option<Vec<String>>,
 }
 
 pub(crate) fn run(
@@ -41,10 +49,12 @@ pub(crate) fn run(
     ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
 ) -> anyhow::Result<()> {
     let OptRetrieveSubmissionSummaries {
+        max_pages,
         config,
         color: _,
         service,
         contest,
+        problems,
     } = opt;
 
     let crate::Context { cwd, mut shell } = ctx;
@@ -70,6 +80,8 @@ pub(crate) fn run(
 
                 let target = AtcoderRetrieveSubmissionSummariesTarget {
                     contest: contest.with_context(|| "`contest` is required for AtCoder")?,
+                    problems: problems.map(|ps| ps.into_iter().collect()),
+                    max_pages,
                 };
 
                 let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
@@ -86,6 +98,17 @@ pub(crate) fn run(
                 })?
             };
 
+            writeln!(
+                shell.stderr,
+                "Retrieved {} of {} page(s) ({} skipped)",
+                outcome.num_retrieved_pages,
+                outcome.num_pages,
+                outcome
+                    .num_pages
+                    .saturating_sub(outcome.num_retrieved_pages),
+            )?;
+            shell.stderr.flush()?;
+
             writeln!(shell.stdout, "{}", outcome.to_json())?;
             shell.stdout.flush()?;
             Ok(())
@@ -96,5 +119,6 @@ pub(crate) fn run(
         PlatformKind::Yukicoder => {
             todo!("`retrieve submission-summaries` for yukicoder is not implemented");
         }
+        PlatformKind::Aoj => bail!("`retrieve submission-summaries` is not supported for AOJ"),
     }
 }
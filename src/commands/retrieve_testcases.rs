@@ -1,22 +1,34 @@
-use crate::web::CaseConversions;
-use anyhow::Context as _;
+use crate::{config::ProblemNaming, messages::Message, web::CaseConversions};
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, Utc};
+use indicatif::HumanBytes;
 use maplit::btreeset;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use snowchains_core::{
     color_spec,
-    testsuite::{Additional, BatchTestSuite, TestSuite},
+    terminal::escape_control_chars,
+    testsuite::{
+        Additional, BatchTestSuite, PartialBatchTestCase, Scorer, ScoringTestSuite, TestCaseOrigin,
+        TestSuite,
+    },
     web::{
-        Atcoder, AtcoderRetrieveFullTestCasesCredentials,
+        Aoj, AojRetrieveTestCasesTargets, Atcoder, AtcoderRetrieveFullTestCasesCredentials,
         AtcoderRetrieveSampleTestCasesCredentials, Codeforces,
-        CodeforcesRetrieveSampleTestCasesCredentials, CookieStorage, PlatformKind,
-        ProblemsInContest, RetrieveFullTestCases, RetrieveTestCases, Yukicoder,
-        YukicoderRetrieveFullTestCasesCredentials, YukicoderRetrieveTestCasesTargets,
+        CodeforcesRetrieveSampleTestCasesCredentials, ContestDuration, CookieStorage, PlatformKind,
+        ProblemsInContest, RetrieveContestDuration, RetrieveFullTestCases, RetrieveTestCases,
+        RetrieveTestCasesOutcome, SavedPath, Yukicoder, YukicoderRetrieveFullTestCasesCredentials,
+        YukicoderRetrieveTestCasesTargets,
     },
 };
 use std::{
     cell::RefCell,
+    collections::BTreeSet,
+    ffi::OsStr,
     io::{BufRead, Write},
-    path::PathBuf,
+    mem,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 use structopt::StructOpt;
 use strum::VariantNames as _;
@@ -29,10 +41,48 @@ pub struct OptRetrieveTestcases {
     #[structopt(long)]
     pub full: bool,
 
+    /// Skips the disk-space and path-length checks for `--full`, and downloads the files again
+    /// even if they are already saved
+    #[structopt(long)]
+    pub force: bool,
+
+    /// Merges newly scraped samples into the existing test files, reporting the changes
+    #[structopt(long)]
+    pub refresh: bool,
+
+    /// Also downloads a checker program linked from the statement (AtCoder only)
+    #[structopt(long)]
+    pub extract_checker: bool,
+
+    /// Waits until the contest starts before retrieving (AtCoder only)
+    #[structopt(long)]
+    pub at_start: bool,
+
+    /// Waits until the time (e.g. "2024-06-22T21:00+09:00") before retrieving
+    #[structopt(
+        long,
+        value_name("DATETIME"),
+        conflicts_with("at-start"),
+        parse(try_from_str = parse_datetime)
+    )]
+    pub at: Option<DateTime<Utc>>,
+
+    /// Seconds to wait after the start time, since problem pages are not published before it
+    #[structopt(long, value_name("SECONDS"), default_value("1"))]
+    pub delay: u64,
+
     /// Prints JSON data
     #[structopt(long)]
     pub json: bool,
 
+    /// Retrieves every entry in a YAML file of `{ service, contest, problems }`s
+    #[structopt(
+        long,
+        value_name("PATH"),
+        conflicts_with_all(&["service", "contest", "problems"])
+    )]
+    pub from: Option<PathBuf>,
+
     /// Path to `snowchains.dhall`
     #[structopt(long)]
     pub config: Option<PathBuf>,
@@ -86,6 +136,8 @@ struct OutcomeContest {
 #[derive(Debug, Serialize)]
 struct OutcomeProblem {
     index: CaseConversions,
+    /// `index` converted with `problemNaming`. Used for the file names.
+    local_name: CaseConversions,
     url: Url,
     screen_name: Option<String>,
     display_name: String,
@@ -104,7 +156,14 @@ pub(crate) fn run(
 ) -> anyhow::Result<()> {
     let OptRetrieveTestcases {
         full,
+        force,
+        refresh,
+        extract_checker,
+        at_start,
+        at,
+        delay,
         json,
+        from,
         config,
         color: _,
         service,
@@ -116,6 +175,23 @@ pub(crate) fn run(
 
     let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
 
+    let options = Options {
+        full,
+        force,
+        refresh,
+        extract_checker,
+        at_start,
+        at,
+        delay,
+        json,
+        config: config.as_deref(),
+    };
+
+    if let Some(from) = from {
+        let entries = group_by_service(crate::fs::read_yaml(cwd.join(from))?)?;
+        return retrieve_entries(&cwd, &workspace, options, &entries, &mut shell);
+    }
+
     let service = service
         .map(Ok)
         .or_else(|| detected_target.parse_service().transpose())
@@ -131,105 +207,334 @@ pub(crate) fn run(
         (problems, _) => Some(problems.iter().cloned().collect()),
     };
 
-    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+    retrieve_and_save(
+        &cwd, &workspace, options, service, contest, problems, &mut shell,
+    )
+}
 
-    let timeout = Some(crate::web::SESSION_TIMEOUT);
+/// Flags that apply to every target.
+#[derive(Clone, Copy)]
+struct Options<'a> {
+    full: bool,
+    force: bool,
+    refresh: bool,
+    extract_checker: bool,
+    at_start: bool,
+    at: Option<DateTime<Utc>>,
+    delay: u64,
+    json: bool,
+    config: Option<&'a Path>,
+}
 
-    let outcome = match service {
-        PlatformKind::Atcoder => {
-            let shell = RefCell::new(&mut shell);
+/// An entry of the file for `--from`.
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+    service: String,
+    #[serde(default)]
+    contest: Option<String>,
+    #[serde(default)]
+    problems: Option<Vec<String>>,
+}
 
-            let targets = {
-                let contest = contest
-                    .clone()
-                    .with_context(|| "`contest` is required for AtCoder")?;
-                ProblemsInContest::Indexes { contest, problems }
-            };
+impl ManifestEntry {
+    fn label(&self) -> String {
+        let mut label = self.service.clone();
+        if let Some(contest) = &self.contest {
+            label += &format!(" {}", contest);
+        }
+        if let Some(problems) = &self.problems {
+            label += &format!(" ({})", problems.join(", "));
+        }
+        label
+    }
+}
 
-            let credentials = AtcoderRetrieveSampleTestCasesCredentials {
-                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
-                    &shell,
-                ),
-            };
+/// Parses the services and sorts the entries by them so that the entries for one service are
+/// retrieved in a row. The order in each service is kept.
+fn group_by_service(
+    entries: Vec<ManifestEntry>,
+) -> anyhow::Result<Vec<(PlatformKind, ManifestEntry)>> {
+    let mut entries = entries
+        .into_iter()
+        .map(|entry| {
+            let service = entry
+                .service
+                .parse::<PlatformKind>()
+                .with_context(|| format!("Unknown service: {:?}", entry.service))?;
+            Ok((service, entry))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    entries.sort_by_key(|&(service, _)| service);
+    Ok(entries)
+}
 
-            let full = if full {
-                Some(RetrieveFullTestCases {
-                    credentials: AtcoderRetrieveFullTestCasesCredentials {
-                        dropbox_access_token: crate::web::credentials::dropbox_access_token()?,
-                    },
-                })
-            } else {
-                None
-            };
+/// Retrieves all of `entries` even if some of them fail, and prints a summary.
+///
+/// The sessions share the cookie jar, so that each service asks for the credentials at most once.
+fn retrieve_entries(
+    cwd: &Path,
+    workspace: &Path,
+    options: Options<'_>,
+    entries: &[(PlatformKind, ManifestEntry)],
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let mut results = vec![];
 
-            Atcoder::exec(RetrieveTestCases {
-                targets,
-                credentials,
-                full,
-                cookie_storage,
-                timeout,
-                shell: &shell,
-            })
+    for (service, entry) in entries {
+        let problems = entry
+            .problems
+            .as_ref()
+            .map(|problems| problems.iter().cloned().collect());
+
+        let result = retrieve_and_save(
+            cwd,
+            workspace,
+            options,
+            *service,
+            entry.contest.clone(),
+            problems,
+            shell,
+        );
+
+        if let Err(err) = &result {
+            shell.warn(format!("{}: {:?}", entry.label(), err))?;
         }
-        PlatformKind::Codeforces => {
-            let shell = RefCell::new(&mut shell);
+        results.push((entry.label(), result));
+    }
 
-            let targets = {
-                let contest = contest
-                    .clone()
-                    .with_context(|| "`contest` is required for Codeforces")?;
-                ProblemsInContest::Indexes { contest, problems }
-            };
+    let num_failures = results.iter().filter(|(_, r)| r.is_err()).count();
 
-            let credentials = CodeforcesRetrieveSampleTestCasesCredentials {
-                username_and_password:
-                    &mut crate::web::credentials::codeforces_username_and_password(&shell),
-            };
+    writeln!(shell.stderr)?;
+    for (label, result) in &results {
+        shell.stderr.set_color(color_spec!(Bold))?;
+        write!(shell.stderr, "{}:", label)?;
+        shell.stderr.reset()?;
+        write!(shell.stderr, " ")?;
+        match result {
+            Ok(()) => {
+                shell.stderr.set_color(color_spec!(Fg(Color::Green)))?;
+                write!(shell.stderr, "OK")?;
+            }
+            Err(err) => {
+                shell.stderr.set_color(color_spec!(Fg(Color::Red)))?;
+                write!(shell.stderr, "Failed")?;
+                shell.stderr.reset()?;
+                write!(shell.stderr, " ({})", err)?;
+            }
+        }
+        shell.stderr.reset()?;
+        writeln!(shell.stderr)?;
+    }
+    shell.stderr.flush()?;
 
-            Codeforces::exec(RetrieveTestCases {
-                targets,
-                credentials,
-                full: None,
-                cookie_storage,
-                timeout,
-                shell: &shell,
-            })
+    if num_failures > 0 {
+        bail!("{} of {} entries failed", num_failures, results.len());
+    }
+    Ok(())
+}
+
+/// Retrieves the test cases of `problems` in `contest`, and saves them.
+fn retrieve_and_save(
+    cwd: &Path,
+    workspace: &Path,
+    options: Options<'_>,
+    service: PlatformKind,
+    contest: Option<String>,
+    problems: Option<BTreeSet<String>>,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let Options {
+        full,
+        force,
+        refresh,
+        extract_checker,
+        at_start,
+        at,
+        delay,
+        json,
+        config,
+    } = options;
+
+    let problem_naming = crate::config::problem_naming(cwd, config, service)?;
+
+    let problems = problems.map(|problems| {
+        problems
+            .iter()
+            .map(|p| problem_naming.remote_index(contest.as_deref(), p))
+            .collect()
+    });
+
+    let default_problems = if problems.is_none() {
+        crate::config::default_problems(cwd, config, service, contest.as_deref())?.map(|problems| {
+            problems
+                .iter()
+                .map(|p| problem_naming.remote_index(contest.as_deref(), p))
+                .collect::<Vec<_>>()
+        })
+    } else {
+        None
+    };
+
+    let start = if at_start {
+        Some(contest_start(service, contest.as_deref(), shell)?)
+    } else {
+        at
+    };
+
+    if let Some(start) = start {
+        let deadline = start + chrono::Duration::seconds(delay as _);
+        crate::wait::wait_until(deadline, &mut shell.stderr, shell.stderr_tty)?;
+    }
+
+    let to_local_name = |naming: ProblemNaming, index: &str| -> CaseConversions {
+        CaseConversions::new(naming.local_name(contest.as_deref(), index))
+    };
+
+    let test_suite_path = |local_name: &CaseConversions| -> PathBuf {
+        suite_path(workspace, service, contest.as_deref(), local_name)
+    };
+
+    let available_space = if full && !force {
+        Some(crate::fs::available_space(
+            workspace.join(".snowchains").join("tests"),
+        )?)
+    } else {
+        None
+    };
+
+    // Full test cases already saved by an interrupted run are reused. Only AtCoder (Dropbox)
+    // provides content hashes to check them with.
+    let reuse = full && !force && service == PlatformKind::Atcoder;
+
+    let saved_path: Option<SavedPath> = if reuse {
+        let (workspace, contest) = (workspace.to_owned(), contest.clone());
+
+        Some(Box::new(move |index, dir_file_name, name| {
+            let local_name =
+                CaseConversions::new(problem_naming.local_name(contest.as_deref(), index));
+            let path = suite_path(&workspace, service, contest.as_deref(), &local_name);
+            txt_path(&path, &local_name.kebab, dir_file_name, name)
+        }))
+    } else {
+        None
+    };
+
+    let full_options = if full {
+        Some(FullOptions {
+            available_space,
+            saved_path,
+            parallelism: crate::config::download_parallelism(cwd, config)?,
+        })
+    } else {
+        None
+    };
+
+    let outcome = if let Some(default_problems) = default_problems {
+        // `defaultProblems` filters the problems in the contest rather than naming them, so that
+        // one missing in an old contest is not an error.
+        let mut outcome = retrieve(service, contest.clone(), None, None, shell)?;
+
+        let is_default = |index: &str| {
+            default_problems
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(index))
+        };
+
+        let (selected, filtered_out): (Vec<_>, Vec<_>) = mem::take(&mut outcome.problems)
+            .into_iter()
+            .partition(|p| is_default(&p.index));
+
+        for missing in default_problems
+            .iter()
+            .filter(|p| !selected.iter().any(|s| s.index.eq_ignore_ascii_case(p)))
+        {
+            shell.warn(format!(
+                "`{}` in `defaultProblems.{}` is not in the contest",
+                missing,
+                service.to_kebab_case_str(),
+            ))?;
         }
-        PlatformKind::Yukicoder => {
-            let targets = if let Some(contest) = &contest {
-                YukicoderRetrieveTestCasesTargets::Contest(contest.clone(), problems)
-            } else {
-                let nos = problems
-                    .with_context(|| "`contest` or `problem`s are required for yukicoder")?
-                    .iter()
-                    .map(|s| s.parse())
-                    .collect::<Result<_, _>>()
-                    .with_context(|| "`problem`s for yukicoder must be unsigned integer")?;
-                YukicoderRetrieveTestCasesTargets::ProblemNos(nos)
-            };
 
-            let full = if full {
-                Some(RetrieveFullTestCases {
-                    credentials: YukicoderRetrieveFullTestCasesCredentials {
-                        api_key: crate::web::credentials::yukicoder_api_key(&mut shell)?,
-                    },
-                })
-            } else {
-                None
-            };
+        let join = |problems: &[snowchains_core::web::RetrieveTestCasesOutcomeProblem]| {
+            problems
+                .iter()
+                .map(|p| &*p.index)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
 
-            let shell = RefCell::new(&mut shell);
+        writeln!(
+            shell.stderr,
+            "Retrieving {} per `defaultProblems.{}` ({} filtered out by the config). Add \
+             `--problems` to override",
+            join(&selected),
+            service.to_kebab_case_str(),
+            if filtered_out.is_empty() {
+                "none".to_owned()
+            } else {
+                join(&filtered_out)
+            },
+        )?;
+        shell.stderr.flush()?;
 
-            Yukicoder::exec(RetrieveTestCases {
-                targets,
-                credentials: (),
-                full,
-                cookie_storage: (),
-                timeout,
+        if full_options.is_some() && !selected.is_empty() {
+            let selected = selected.into_iter().map(|p| p.index).collect();
+            retrieve(
+                service,
+                contest.clone(),
+                Some(selected),
+                full_options,
                 shell,
-            })
+            )?
+        } else {
+            outcome.problems = selected;
+            outcome
         }
-    }?;
+    } else {
+        retrieve(service, contest.clone(), problems, full_options, shell)?
+    };
+
+    let num_reused_files = outcome.num_reused_files;
+    let mut num_text_files = 0;
+
+    if !force {
+        let mut total_size = 0;
+
+        for snowchains_core::web::RetrieveTestCasesOutcomeProblem {
+            index, text_files, ..
+        } in &outcome.problems
+        {
+            let local_name = to_local_name(problem_naming, index);
+            let path = test_suite_path(&local_name);
+
+            crate::fs::ensure_path_len(&path)?;
+
+            for (
+                name,
+                snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles { r#in, out },
+            ) in text_files
+            {
+                crate::fs::ensure_path_len(&txt_path(&path, &local_name.kebab, "in", name))?;
+                total_size += r#in.len();
+
+                if let Some(out) = out {
+                    crate::fs::ensure_path_len(&txt_path(&path, &local_name.kebab, "out", name))?;
+                    total_size += out.len();
+                }
+            }
+        }
+
+        if let Some(available_space) = available_space {
+            if total_size as u64 > available_space {
+                bail!(
+                    "The test cases take {} but only {} is available. To save them anyway, add \
+                     `--force`",
+                    HumanBytes(total_size as _),
+                    HumanBytes(available_space),
+                );
+            }
+        }
+    }
 
     let mut acc = Outcome {
         contest: outcome
@@ -264,33 +569,92 @@ pub(crate) fn run(
         display_name,
         mut test_suite,
         text_files,
+        checker,
         ..
     } in outcome.problems
     {
+        let local_name = to_local_name(problem_naming, &index);
         let index = CaseConversions::new(index);
 
-        let path = workspace
-            .join(".snowchains")
-            .join("tests")
-            .join(service.to_kebab_case_str())
-            .join(contest.as_deref().unwrap_or(""))
-            .join(&index.kebab)
-            .with_extension("yml");
-
-        let txt_path = |dir_file_name: &str, txt_file_name: &str| -> _ {
-            path.with_file_name(&index.kebab)
-                .join(dir_file_name)
-                .join(txt_file_name)
-                .with_extension("txt")
+        let path = test_suite_path(&local_name);
+
+        if !path.exists() {
+            let old_path = ProblemNaming::ALL
+                .iter()
+                .filter(|&&naming| naming != problem_naming)
+                .map(|&naming| test_suite_path(&to_local_name(naming, &index.original)))
+                .find(|p| *p != path && p.exists());
+
+            if let Some(old_path) = old_path {
+                shell.warn(format!(
+                    "`{}` exists but `problemNaming.{}` is now `{}`. Saving to `{}`. Rename the \
+                     old test files and source files to keep using them",
+                    old_path.display(),
+                    service.to_kebab_case_str(),
+                    problem_naming,
+                    path.display(),
+                ))?;
+            }
+        }
+
+        // A problem from another contest is written to the shared location, and `path` links to it.
+        let (storage, dir_name) = match shared_storage(
+            workspace,
+            service,
+            contest.as_deref(),
+            screen_name.as_deref(),
+            &path,
+            shell,
+        )? {
+            Some(storage) => (storage, screen_name.clone().unwrap_or_default()),
+            None => (path.clone(), local_name.kebab.clone()),
         };
 
-        for (name, snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles { r#in, out }) in
-            &text_files
-        {
-            crate::fs::write(txt_path("in", name), &r#in, true)?;
-            if let Some(out) = out {
-                crate::fs::write(txt_path("out", name), out, true)?;
+        // If any of the files cannot be written, removes the ones already written so that no
+        // incomplete test cases are left.
+        let mut written = vec![];
+
+        // The files at `path` were already written or checked against the content hashes.
+        let is_saved = |file: &Path, content: &str| {
+            reuse
+                && storage == path
+                && file
+                    .metadata()
+                    .is_ok_and(|m| m.len() == content.len() as u64)
+        };
+
+        let result = text_files.iter().try_for_each(
+            |(
+                name,
+                snowchains_core::web::RetrieveTestCasesOutcomeProblemTextFiles { r#in, out },
+            )|
+             -> anyhow::Result<_> {
+                let in_path = txt_path(&storage, &dir_name, "in", name);
+                num_text_files += 1;
+                if !is_saved(&in_path, r#in) {
+                    written.push(in_path.clone());
+                    crate::fs::write(in_path, &r#in, true)?;
+                }
+
+                if let Some(out) = out {
+                    let out_path = txt_path(&storage, &dir_name, "out", name);
+                    num_text_files += 1;
+                    if !is_saved(&out_path, out) {
+                        written.push(out_path.clone());
+                        crate::fs::write(out_path, out, true)?;
+                    }
+                }
+                Ok(())
+            },
+        );
+
+        if let Err(err) = result {
+            for path in written.iter().filter(|p| p.exists()) {
+                if let Err(err) = crate::fs::remove_file(path) {
+                    shell.warn(format!("{:?}", err))?;
+                }
             }
+            return Err(err.context(format!("{}: Could not save the test cases", index.original)));
         }
 
         if !text_files.is_empty() {
@@ -298,34 +662,96 @@ pub(crate) fn run(
                 cases.clear();
 
                 extend.push(Additional::Text {
-                    path: format!("./{}", index.kebab).into(),
+                    path: format!("./{}", dir_name).into(),
                     r#in: "/in/*.txt".to_owned(),
                     out: "/out/*.txt".to_owned(),
                     timelimit: None,
                     r#match: None,
+                    origin: Some(TestCaseOrigin::System),
                 })
             }
         }
 
-        crate::fs::write(&path, test_suite.to_yaml_pretty(), true)?;
+        let contest_id = contest.as_deref().unwrap_or("");
+
+        if service == PlatformKind::Atcoder
+            && text_files.is_empty()
+            && is_heuristic(contest_id, &test_suite)
+        {
+            match (storage.exists(), &test_suite) {
+                (true, _) if matches!(crate::fs::read_yaml(&storage)?, TestSuite::Scoring(_)) => {
+                    // Keeps the `scorer` filled in by the user.
+                    test_suite = crate::fs::read_yaml(&storage)?;
+                }
+                (
+                    _,
+                    TestSuite::Batch(BatchTestSuite {
+                        timelimit, cases, ..
+                    }),
+                ) => {
+                    for (i, case) in cases.iter().enumerate() {
+                        let name = case.name.clone().unwrap_or_else(|| (i + 1).to_string());
+                        let in_path = txt_path(&storage, &dir_name, "in", &name);
+                        crate::fs::write(in_path, &*case.r#in, true)?;
+                    }
+
+                    test_suite = TestSuite::Scoring(ScoringTestSuite {
+                        timelimit: *timelimit,
+                        path: format!("./{}", dir_name).into(),
+                        r#in: "/in/*.txt".to_owned(),
+                        scorer: Scorer {
+                            command: vec!["TODO: path/to/the/local/tester".to_owned()],
+                            parse: r"Score = (\d+)".to_owned(),
+                            minimize: false,
+                        },
+                    });
+
+                    shell.warn(format!(
+                        "{}: heuristic problem. Fill in `scorer` in {}",
+                        index.original,
+                        path.display(),
+                    ))?;
+                }
+                _ => {}
+            }
+        }
+
+        if refresh && text_files.is_empty() && storage.exists() {
+            if let (TestSuite::Batch(existing), TestSuite::Batch(scraped)) =
+                (crate::fs::read_yaml(&storage)?, &mut test_suite)
+            {
+                let changes = merge_samples(existing, scraped);
+                print_sample_changes(&mut shell.stderr, &index.original, &changes)?;
+            }
+        }
+
+        crate::fs::write(&storage, test_suite.to_yaml_pretty(), true)?;
+
+        if storage != path {
+            crate::suite_link::link(&path, &storage)?;
+        }
 
         shell.stderr.set_color(color_spec!(Bold))?;
         write!(shell.stderr, "{}:", index.original)?;
         shell.stderr.reset()?;
 
-        write!(shell.stderr, " Saved to ")?;
+        write!(shell.stderr, " {} ", Message::SavedTo.get())?;
 
         shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
         if text_files.is_empty() {
-            write!(shell.stderr, "{}", path.display())
+            write!(shell.stderr, "{}", storage.display())
         } else {
             write!(
                 shell.stderr,
                 "{}",
-                path.with_file_name(format!("{{{index}.yml, {index}/}}", index = index.kebab))
+                storage
+                    .with_file_name(format!("{{{name}.yml, {name}/}}", name = dir_name))
                     .display(),
             )
         }?;
+        if storage != path {
+            write!(shell.stderr, " (shared, linked from {})", path.display())?;
+        }
         shell.stderr.reset()?;
 
         write!(shell.stderr, " (")?;
@@ -333,13 +759,19 @@ pub(crate) fn run(
         let (msg, color) = match &test_suite {
             TestSuite::Batch(BatchTestSuite { cases, .. }) => {
                 match cases.len() + text_files.len() {
-                    0 => ("no test cases".to_owned(), Color::Yellow),
-                    1 => ("1 test case".to_owned(), Color::Green),
-                    n => (format!("{} test cases", n), Color::Green),
+                    0 => (Message::NoTestCases.get().to_owned(), Color::Yellow),
+                    1 => (Message::OneTestCase.get().to_owned(), Color::Green),
+                    n => (Message::TestCases.format(n), Color::Green),
                 }
             }
-            TestSuite::Interactive(_) => ("interactive problem".to_owned(), Color::Yellow),
-            TestSuite::Unsubmittable => ("unsubmittable problem".to_owned(), Color::Yellow),
+            TestSuite::Interactive(_) => {
+                (Message::InteractiveProblem.get().to_owned(), Color::Yellow)
+            }
+            TestSuite::Unsubmittable => (
+                Message::UnsubmittableProblem.get().to_owned(),
+                Color::Yellow,
+            ),
+            TestSuite::Scoring(_) => (Message::ScoringProblem.get().to_owned(), Color::Green),
         };
 
         shell.stderr.set_color(color_spec!(Fg(color)))?;
@@ -349,8 +781,52 @@ pub(crate) fn run(
         writeln!(shell.stderr, ")")?;
         shell.stderr.flush()?;
 
+        // Zero samples for a batch problem almost always means the statement has a layout the
+        // scraper does not know.
+        if let TestSuite::Batch(BatchTestSuite { cases, extend, .. }) = &test_suite {
+            if cases.is_empty() && extend.is_empty() {
+                shell.warn(format!(
+                    "{}: No sample cases were found. Check the statement and add them to {}",
+                    index.original,
+                    path.display(),
+                ))?;
+            }
+        }
+
+        if extract_checker {
+            if let Some(checker) = checker {
+                let ext = Path::new(checker.path())
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or("txt")
+                    .to_owned();
+                let checker_path =
+                    path.with_file_name(format!("{}_checker.{}", local_name.kebab, ext));
+
+                let content = crate::web::download(checker, shell)?;
+                crate::fs::write(&checker_path, content, true)?;
+
+                shell.stderr.set_color(color_spec!(Bold))?;
+                write!(shell.stderr, "{}:", index.original)?;
+                shell.stderr.reset()?;
+                write!(shell.stderr, " {} ", Message::SavedCheckerTo.get())?;
+                shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+                write!(shell.stderr, "{}", checker_path.display())?;
+                shell.stderr.reset()?;
+                writeln!(shell.stderr)?;
+                shell.stderr.flush()?;
+            } else {
+                shell.warn(format!(
+                    "{}: {}",
+                    index.original,
+                    Message::NoCheckerFound.get()
+                ))?;
+            }
+        }
+
         acc.problems.push(OutcomeProblem {
             index,
+            local_name,
             url,
             screen_name,
             display_name,
@@ -364,6 +840,16 @@ pub(crate) fn run(
         });
     }
 
+    if full && num_text_files > 0 {
+        writeln!(
+            shell.stderr,
+            "Full test cases: {} downloaded, {} reused",
+            num_text_files - num_reused_files,
+            num_reused_files,
+        )?;
+        shell.stderr.flush()?;
+    }
+
     if json {
         writeln!(shell.stdout, "{}", acc.to_json())?;
         shell.stdout.flush()?;
@@ -371,3 +857,574 @@ pub(crate) fn run(
 
     Ok(())
 }
+
+fn suite_path(
+    workspace: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    local_name: &CaseConversions,
+) -> PathBuf {
+    workspace
+        .join(".snowchains")
+        .join("tests")
+        .join(service.to_kebab_case_str())
+        .join(contest.unwrap_or(""))
+        .join(&local_name.kebab)
+        .with_extension("yml")
+}
+
+/// Returns the shared location if the problem should be stored there.
+///
+/// Existing real files are replaced with links only if the user agrees. One with a directory of
+/// full test cases next to it is left as it is.
+fn shared_storage(
+    workspace: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    screen_name: Option<&str>,
+    path: &Path,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<Option<PathBuf>> {
+    let (contest, screen_name) = match (service, contest, screen_name) {
+        (PlatformKind::Atcoder, Some(contest), Some(screen_name)) => (contest, screen_name),
+        _ => return Ok(None),
+    };
+
+    let shared_path = crate::suite_link::shared_path(workspace, service, screen_name);
+
+    if !(crate::suite_link::is_shared(contest, screen_name) || shared_path.exists()) {
+        return Ok(None);
+    }
+
+    let move_to_shared = |path: &Path, shell: &mut crate::shell::Shell<_, _, _>| {
+        if crate::suite_link::link_target(path)?.is_some() || !path.exists() {
+            return Ok(true);
+        }
+        if path.with_extension("").exists() {
+            shell.warn(format!(
+                "{} has full test cases next to it. Not sharing it with other contests",
+                path.display(),
+            ))?;
+            return Ok(false);
+        }
+
+        shell.warn(format!(
+            "{} is the same problem as {}",
+            path.display(),
+            shared_path.display(),
+        ))?;
+        if !shell.confirm(Message::ReplaceWithLinkPrompt.get())? {
+            return Ok(false);
+        }
+
+        if shared_path.exists() {
+            crate::fs::remove_file(path)?;
+        } else {
+            crate::fs::create_dir_all(shared_path.parent().unwrap())?;
+            crate::fs::rename(path, &shared_path)?;
+        }
+        crate::suite_link::link(path, &shared_path)?;
+        Ok::<_, anyhow::Error>(true)
+    };
+
+    // The original contest may have been downloaded before the sharing was found.
+    if let Some(home_path) = crate::suite_link::home_path(workspace, service, screen_name) {
+        if home_path != path {
+            move_to_shared(&home_path, shell)?;
+        }
+    }
+
+    Ok(move_to_shared(path, shell)?.then(|| shared_path.clone()))
+}
+
+/// AtCoder Heuristic Contests and the other contests with long time limits.
+fn is_heuristic(contest: &str, test_suite: &TestSuite) -> bool {
+    contest.to_lowercase().starts_with("ahc")
+        || matches!(
+            test_suite,
+            TestSuite::Batch(BatchTestSuite { timelimit: Some(timelimit), .. })
+                if *timelimit >= Duration::from_secs(10)
+        )
+}
+
+fn txt_path(
+    test_suite_path: &Path,
+    index: &str,
+    dir_file_name: &str,
+    txt_file_name: &str,
+) -> PathBuf {
+    test_suite_path
+        .with_file_name(index)
+        .join(dir_file_name)
+        .join(txt_file_name)
+        .with_extension("txt")
+}
+
+/// Arguments for `--full` other than the credentials.
+pub(crate) struct FullOptions {
+    pub(crate) available_space: Option<u64>,
+    pub(crate) saved_path: Option<SavedPath>,
+    pub(crate) parallelism: NonZeroUsize,
+}
+
+pub(crate) fn retrieve(
+    service: PlatformKind,
+    contest: Option<String>,
+    problems: Option<BTreeSet<String>>,
+    full: Option<FullOptions>,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<RetrieveTestCasesOutcome> {
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+
+    let timeout = Some(crate::web::SESSION_TIMEOUT);
+
+    match service {
+        PlatformKind::Atcoder => {
+            let shell = RefCell::new(&mut *shell);
+
+            let targets = {
+                let contest = contest.with_context(|| "`contest` is required for AtCoder")?;
+                ProblemsInContest::Indexes { contest, problems }
+            };
+
+            let credentials = AtcoderRetrieveSampleTestCasesCredentials {
+                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
+                    &shell,
+                ),
+            };
+
+            let full = if let Some(FullOptions {
+                available_space,
+                saved_path,
+                parallelism,
+            }) = full
+            {
+                Some(RetrieveFullTestCases {
+                    credentials: AtcoderRetrieveFullTestCasesCredentials {
+                        dropbox_access_token: crate::web::credentials::dropbox_access_token()?,
+                    },
+                    available_space,
+                    saved_path,
+                    parallelism,
+                })
+            } else {
+                None
+            };
+
+            Atcoder::exec(RetrieveTestCases {
+                targets,
+                credentials,
+                full,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Codeforces => {
+            let shell = RefCell::new(&mut *shell);
+
+            let targets = {
+                let contest = contest.with_context(|| "`contest` is required for Codeforces")?;
+                ProblemsInContest::Indexes { contest, problems }
+            };
+
+            let credentials = CodeforcesRetrieveSampleTestCasesCredentials {
+                username_and_password:
+                    &mut crate::web::credentials::codeforces_username_and_password(&shell),
+            };
+
+            Codeforces::exec(RetrieveTestCases {
+                targets,
+                credentials,
+                full: None,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Yukicoder => {
+            let targets = if let Some(contest) = contest {
+                YukicoderRetrieveTestCasesTargets::Contest(contest, problems)
+            } else {
+                let nos = problems
+                    .with_context(|| "`contest` or `problem`s are required for yukicoder")?
+                    .iter()
+                    .map(|s| s.parse())
+                    .collect::<Result<_, _>>()
+                    .with_context(|| "`problem`s for yukicoder must be unsigned integer")?;
+                YukicoderRetrieveTestCasesTargets::ProblemNos(nos)
+            };
+
+            let full = if let Some(FullOptions {
+                available_space,
+                saved_path,
+                parallelism,
+            }) = full
+            {
+                Some(RetrieveFullTestCases {
+                    credentials: YukicoderRetrieveFullTestCasesCredentials {
+                        api_key: crate::web::credentials::yukicoder_api_key(shell)?,
+                    },
+                    available_space,
+                    saved_path,
+                    parallelism,
+                })
+            } else {
+                None
+            };
+
+            let shell = RefCell::new(&mut *shell);
+
+            Yukicoder::exec(RetrieveTestCases {
+                targets,
+                credentials: (),
+                full,
+                cookie_storage: (),
+                timeout,
+                shell,
+            })
+        }
+        PlatformKind::Aoj => {
+            let problems = problems.with_context(|| "`problem`s are required for AOJ")?;
+
+            let targets = if let Some(course) = contest {
+                AojRetrieveTestCasesTargets::Course(course, problems)
+            } else {
+                AojRetrieveTestCasesTargets::ProblemIds(problems)
+            };
+
+            let full = if let Some(FullOptions {
+                available_space,
+                saved_path,
+                parallelism,
+            }) = full
+            {
+                Some(RetrieveFullTestCases {
+                    credentials: (),
+                    available_space,
+                    saved_path,
+                    parallelism,
+                })
+            } else {
+                None
+            };
+
+            let shell = RefCell::new(&mut *shell);
+
+            Aoj::exec(RetrieveTestCases {
+                targets,
+                credentials: (),
+                full,
+                cookie_storage: (),
+                timeout,
+                shell,
+            })
+        }
+    }
+}
+
+pub(crate) fn parse_datetime(s: &str) -> anyhow::Result<DateTime<Utc>> {
+    let datetime = DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M%:z"))
+        .with_context(|| "Expected RFC 3339 (e.g. \"2024-06-22T21:00+09:00\")")?;
+    Ok(datetime.with_timezone(&Utc))
+}
+
+fn contest_start(
+    service: PlatformKind,
+    contest: Option<&str>,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<DateTime<Utc>> {
+    if service != PlatformKind::Atcoder {
+        bail!("`--at-start` is only supported for AtCoder. Specify the time with `--at` instead",);
+    }
+
+    let contest = contest.with_context(|| "`contest` is required for AtCoder")?;
+
+    // The countdown runs on the local clock.
+    Ok(contest_duration(contest, shell)?.local_start())
+}
+
+/// Retrieves the duration of an AtCoder contest.
+pub(crate) fn contest_duration(
+    contest: &str,
+    shell: &mut crate::shell::Shell<impl Sized, impl Sized, impl WriteColor>,
+) -> anyhow::Result<ContestDuration> {
+    let shell = RefCell::new(shell);
+
+    Atcoder::exec(RetrieveContestDuration {
+        contest: contest.to_owned(),
+        cookie_storage: CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?,
+        timeout: Some(crate::web::SESSION_TIMEOUT),
+        shell: &shell,
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum SampleChange {
+    Added(String),
+    Changed(String),
+    Removed(String),
+}
+
+/// Merges `existing` into `scraped`.
+///
+/// Cases in `existing` that are not tagged as samples (or, if untagged, not named `sample{n}`) are
+/// considered to be written by hand and are appended to `scraped` tagged as `manual`. So is
+/// `extend`. Differences only in whitespace are not reported.
+///
+/// The header of `existing` (`timelimit`, `match`, `float_tolerance`, and `generator`) is kept
+/// as well, since it may have been modified with `modify` or by hand.
+fn merge_samples(existing: BatchTestSuite, scraped: &mut BatchTestSuite) -> Vec<SampleChange> {
+    let BatchTestSuite {
+        timelimit,
+        r#match,
+        float_tolerance,
+        generator,
+        cases,
+        extend,
+    } = existing;
+
+    let is_sample = |case: &PartialBatchTestCase| match case.origin {
+        Some(origin) => origin == TestCaseOrigin::Sample,
+        None => matches!(
+            case.name.as_deref(),
+            Some(name) if name.len() > 6
+                && name.starts_with("sample")
+                && name[6..].chars().all(|c| c.is_ascii_digit())
+        ),
+    };
+
+    let (existing_samples, mut manual): (Vec<_>, Vec<_>) = cases.into_iter().partition(is_sample);
+
+    for case in &mut manual {
+        case.origin.get_or_insert(TestCaseOrigin::Manual);
+    }
+
+    let mut changes = vec![];
+
+    for case in &scraped.cases {
+        let name = case.name.clone().unwrap_or_default();
+
+        match existing_samples.iter().find(|c| c.name == case.name) {
+            None => changes.push(SampleChange::Added(name)),
+            Some(old) => {
+                if normalize(&old.r#in) != normalize(&case.r#in)
+                    || old.out.as_deref().map(normalize) != case.out.as_deref().map(normalize)
+                {
+                    changes.push(SampleChange::Changed(name));
+                }
+            }
+        }
+    }
+
+    for case in &existing_samples {
+        if !scraped.cases.iter().any(|c| c.name == case.name) {
+            changes.push(SampleChange::Removed(case.name.clone().unwrap_or_default()));
+        }
+    }
+
+    scraped.timelimit = timelimit;
+    scraped.r#match = r#match;
+    scraped.float_tolerance = float_tolerance;
+    scraped.generator = generator;
+    scraped.cases.extend(manual);
+    scraped.extend.extend(extend);
+    return changes;
+
+    fn normalize(s: &str) -> Vec<&str> {
+        s.split_whitespace().collect()
+    }
+}
+
+fn print_sample_changes(
+    mut stderr: impl WriteColor,
+    index: &str,
+    changes: &[SampleChange],
+) -> std::io::Result<()> {
+    stderr.set_color(color_spec!(Bold))?;
+    write!(stderr, "{}:", index)?;
+    stderr.reset()?;
+
+    if changes.is_empty() {
+        writeln!(stderr, " No changes in the samples")?;
+    } else {
+        writeln!(stderr)?;
+    }
+
+    for change in changes {
+        let (name, msg, color) = match change {
+            SampleChange::Added(name) => (name, "added", Color::Green),
+            SampleChange::Changed(name) => (name, "changed", Color::Yellow),
+            SampleChange::Removed(name) => (name, "removed", Color::Red),
+        };
+
+        write!(stderr, "  {}: ", escape_control_chars(name))?;
+        stderr.set_color(color_spec!(Fg(color)))?;
+        write!(stderr, "{}", msg)?;
+        stderr.reset()?;
+        writeln!(stderr)?;
+    }
+
+    stderr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ManifestEntry, SampleChange};
+    use snowchains_core::testsuite::{
+        BatchTestSuite, Match, PartialBatchTestCase, TestCaseOrigin, TestSuite,
+    };
+    use snowchains_core::web::PlatformKind;
+    use std::time::Duration;
+
+    #[test]
+    fn group_by_service() -> anyhow::Result<()> {
+        let entries = serde_yaml::from_str::<Vec<ManifestEntry>>(
+            r#"
+- service: yukicoder
+  problems: ["1", "2"]
+- service: atcoder
+  contest: abc100
+  problems: [a, b]
+- service: codeforces
+  contest: "1349"
+- service: atcoder
+  contest: arc100
+"#,
+        )?;
+
+        let grouped = super::group_by_service(entries)?
+            .into_iter()
+            .map(|(service, entry)| (service, entry.label()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            [
+                (PlatformKind::Atcoder, "atcoder abc100 (a, b)".to_owned()),
+                (PlatformKind::Atcoder, "atcoder arc100".to_owned()),
+                (PlatformKind::Codeforces, "codeforces 1349".to_owned()),
+                (PlatformKind::Yukicoder, "yukicoder (1, 2)".to_owned()),
+            ],
+            &*grouped,
+        );
+
+        assert!(super::group_by_service(vec![ManifestEntry {
+            service: "topcoder".to_owned(),
+            contest: None,
+            problems: None,
+        }])
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_samples() {
+        fn suite(cases: &[(&str, &str, &str)]) -> BatchTestSuite {
+            BatchTestSuite {
+                timelimit: None,
+                r#match: Match::Lines,
+                float_tolerance: None,
+                generator: None,
+                cases: cases
+                    .iter()
+                    .map(|&(name, r#in, out)| PartialBatchTestCase {
+                        name: Some(name.to_owned()),
+                        r#in: r#in.into(),
+                        out: Some(out.into()),
+                        timelimit: None,
+                        r#match: None,
+                        origin: None,
+                        note: None,
+                    })
+                    .collect(),
+                extend: vec![],
+            }
+        }
+
+        let existing = suite(&[
+            ("sample1", "1 2\n", "3\n"),
+            ("sample2", "3 4\n", "7\n"),
+            ("sample3", "5 6\n", "11\n"),
+            ("mine", "0 0\n", "0\n"),
+        ]);
+
+        let mut scraped = suite(&[
+            ("sample1", "1  2\n", "3\n"),
+            ("sample2", "3 4\n", "8\n"),
+            ("sample4", "7 8\n", "15\n"),
+        ]);
+
+        assert_eq!(
+            super::merge_samples(existing, &mut scraped),
+            [
+                SampleChange::Changed("sample2".to_owned()),
+                SampleChange::Added("sample4".to_owned()),
+                SampleChange::Removed("sample3".to_owned()),
+            ],
+        );
+
+        assert_eq!(
+            scraped
+                .cases
+                .iter()
+                .map(|c| c.name.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            ["sample1", "sample2", "sample4", "mine"],
+        );
+        assert_eq!(scraped.cases[3].origin, Some(TestCaseOrigin::Manual));
+
+        // Set with `modify timelimit`, `modify match`, or by hand.
+        let existing = BatchTestSuite {
+            timelimit: Some(Duration::from_secs(3)),
+            r#match: Match::Float {
+                relative_error: Some("1e-6".parse().unwrap()),
+                absolute_error: None,
+            },
+            float_tolerance: Some("1e-9".parse().unwrap()),
+            generator: Some(vec!["python3".to_owned(), "gen.py".to_owned()]),
+            ..suite(&[("sample1", "1 2\n", "3\n")])
+        };
+
+        let mut scraped = BatchTestSuite {
+            timelimit: Some(Duration::from_secs(2)),
+            ..suite(&[("sample1", "1 2\n", "3\n")])
+        };
+
+        let expected_header = (
+            existing.timelimit,
+            existing.r#match.clone(),
+            existing.float_tolerance,
+            existing.generator.clone(),
+        );
+
+        assert_eq!(super::merge_samples(existing, &mut scraped), []);
+        assert_eq!(
+            (
+                scraped.timelimit,
+                scraped.r#match,
+                scraped.float_tolerance,
+                scraped.generator,
+            ),
+            expected_header,
+        );
+    }
+
+    #[test]
+    fn is_heuristic() {
+        let batch = |secs| {
+            TestSuite::Batch(BatchTestSuite {
+                timelimit: Some(Duration::from_secs(secs)),
+                r#match: Match::Lines,
+                float_tolerance: None,
+                generator: None,
+                cases: vec![],
+                extend: vec![],
+            })
+        };
+        assert!(super::is_heuristic("ahc001", &batch(5)));
+        assert!(super::is_heuristic("future-contest-2021-qual", &batch(10)));
+        assert!(!super::is_heuristic("abc100", &batch(2)));
+        assert!(!super::is_heuristic("abc100", &TestSuite::Unsubmittable));
+    }
+}
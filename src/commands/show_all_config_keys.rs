@@ -0,0 +1,64 @@
+use crate::config::{ConfigKey, ALL_KEYS};
+use snowchains_core::web::PlatformKind;
+use std::io::{BufRead, Write};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowAllConfigKeys {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+}
+
+pub(crate) fn run(
+    opt: OptShowAllConfigKeys,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowAllConfigKeys { json, color: _ } = opt;
+
+    let crate::Context { mut shell, .. } = ctx;
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(ALL_KEYS)?)?;
+    } else {
+        let key_width = ALL_KEYS.iter().map(|k| k.key.len()).max().unwrap_or(0);
+
+        for ConfigKey {
+            key,
+            r#type,
+            default,
+        } in ALL_KEYS
+        {
+            write!(
+                shell.stdout,
+                "{:width$} : {}",
+                key,
+                r#type,
+                width = key_width
+            )?;
+            if let Some(default) = default {
+                write!(shell.stdout, "  -- optional, default: {}", default)?;
+            }
+            writeln!(shell.stdout)?;
+        }
+
+        writeln!(
+            shell.stdout,
+            "-- <service>: {}",
+            PlatformKind::KEBAB_CASE_VARIANTS.join(", "),
+        )?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
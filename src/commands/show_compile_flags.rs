@@ -0,0 +1,128 @@
+use crate::config;
+use anyhow::Context as _;
+use itertools::Itertools as _;
+use snowchains_core::web::PlatformKind;
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowCompileFlags {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Shows the command for `Release` mode
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name
+    #[structopt(short, long, alias("lang"), value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Alternative solution (e.g. "slow")
+    #[structopt(long, value_name("STRING"))]
+    pub variant: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowCompileFlags,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowCompileFlags {
+        json,
+        release,
+        config,
+        color: _,
+        service,
+        contest,
+        language,
+        variant,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (_, config::Language { compile, .. }, _) = config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        service,
+        contest.as_deref(),
+        problem.as_deref(),
+        language.as_deref(),
+        variant.as_deref(),
+        if release {
+            config::Mode::Release
+        } else {
+            config::Mode::Debug
+        },
+    )?;
+
+    let config::Compile { command, .. } =
+        compile.with_context(|| "The language does not have `compile`")?;
+
+    // A script is written to a temporary file, whose path is appended as the only argument.
+    let (args, script) = match command {
+        config::Command::Args(args) => (args, None),
+        config::Command::Script(config::Script {
+            program,
+            extension,
+            content,
+        }) => (
+            vec![program, format!("<script>.{}", extension)],
+            Some(content),
+        ),
+    };
+
+    if json {
+        let json = serde_json::json!({ "command": args, "script": script });
+        writeln!(shell.stdout, "{}", json)?;
+    } else {
+        writeln!(
+            shell.stdout,
+            "{}",
+            args.iter()
+                .map(|s| shell_escape::unix::escape(s.into()))
+                .format(" "),
+        )?;
+        if let Some(script) = script {
+            writeln!(shell.stdout)?;
+            write!(shell.stdout, "{}", script)?;
+        }
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
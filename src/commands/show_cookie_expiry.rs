@@ -0,0 +1,145 @@
+use anyhow::Context as _;
+use cookie_store::Cookie;
+use serde::{Deserialize, Serialize};
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::io::BufRead;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowCookieExpiry {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Shows only cookies for the service
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+}
+
+pub(crate) fn run(
+    opt: OptShowCookieExpiry,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowCookieExpiry {
+        json,
+        color: _,
+        service,
+    } = opt;
+
+    let crate::Context { mut shell, .. } = ctx;
+
+    let path = crate::web::credentials::cookie_store_path()?;
+
+    // `CookieStore::load_json` drops expired cookies, so read the file by ourselves.
+    let mut entries = if path.exists() {
+        crate::fs::read_to_string(&path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let parse = || -> serde_json::Result<_> {
+                    Ok(Entry::new(
+                        &serde_json::from_str(line)?,
+                        serde_json::from_str(line)?,
+                    ))
+                };
+                parse().with_context(|| format!("Could not parse a cookie in `{}`", path.display()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+        vec![]
+    };
+
+    if let Some(service) = service {
+        let domain = match service {
+            PlatformKind::Atcoder => "atcoder.jp",
+            PlatformKind::Codeforces => "codeforces.com",
+            PlatformKind::Yukicoder => "yukicoder.me",
+            PlatformKind::Aoj => "u-aizu.ac.jp",
+        };
+        entries.retain(|Entry { domain: d, .. }| d.trim_start_matches('.').ends_with(domain));
+    }
+
+    entries.sort_by(|e1, e2| match (&e1.expires, &e2.expires) {
+        (Some(t1), Some(t2)) => t1.cmp(t2),
+        (t1, t2) => t2.is_some().cmp(&t1.is_some()),
+    });
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&entries)?)?;
+        shell.stdout.flush()?;
+        return Ok(());
+    }
+
+    for Entry {
+        name,
+        expires,
+        expired,
+        ..
+    } in &entries
+    {
+        write!(shell.stdout, "{}: ", name)?;
+        if *expired {
+            shell.stdout.set_color(color_spec!(Fg(Color::Red)))?;
+        }
+        write!(
+            shell.stdout,
+            "{}",
+            expires.as_deref().unwrap_or("end of session")
+        )?;
+        shell.stdout.reset()?;
+        writeln!(shell.stdout)?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Entry {
+    domain: String,
+    name: String,
+    expires: Option<String>,
+    expired: bool,
+}
+
+impl Entry {
+    /// `cookie_store` does not export `CookieExpiration`, so the expiry is read from the same line
+    /// separately. It is always in the `%Y-%m-%dT%H:%M:%SZ` format, which sorts chronologically.
+    fn new(cookie: &Cookie<'_>, StoredExpires { expires }: StoredExpires) -> Self {
+        Self {
+            domain: String::from(&cookie.domain),
+            name: cookie.name().to_owned(),
+            expires: match expires {
+                StoredExpiration::AtUtc(at) => Some(at),
+                StoredExpiration::SessionEnd => None,
+            },
+            expired: cookie.is_expired(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct StoredExpires {
+    expires: StoredExpiration,
+}
+
+#[derive(Deserialize)]
+enum StoredExpiration {
+    AtUtc(String),
+    SessionEnd,
+}
@@ -0,0 +1,90 @@
+use anyhow::Context as _;
+use snowchains_core::{testsuite::TestCaseSource, web::PlatformKind};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowExpected {
+    /// Writes to the file instead of the standard output
+    #[structopt(long, value_name("PATH"))]
+    pub out: Option<PathBuf>,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: String,
+
+    /// 1-based position of the test case, as shown by `judge`
+    pub nth: usize,
+}
+
+pub(crate) fn run(
+    opt: OptShowExpected,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowExpected {
+        out,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+        nth,
+    } = opt;
+
+    let crate::Context { cwd, shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let TestCaseSource {
+        name,
+        out: expected,
+        ..
+    } = crate::judge::nth_test_case_source(&workspace, service, contest.as_deref(), &problem, nth)?;
+
+    let expected = expected.with_context(|| {
+        format!(
+            "The test case {} has no expected output",
+            name.as_deref().unwrap_or("(no name)"),
+        )
+    })?;
+
+    crate::judge::copy_text_source(&expected, out.as_deref(), shell.stdout)
+}
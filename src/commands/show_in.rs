@@ -0,0 +1,96 @@
+use anyhow::Context as _;
+use snowchains_core::{testsuite::TextSource, web::PlatformKind};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowIn {
+    /// Writes to the file instead of the standard output
+    #[structopt(long, value_name("PATH"))]
+    pub out: Option<PathBuf>,
+
+    /// Encodes the input with the standard base64
+    #[structopt(long)]
+    pub base64: bool,
+
+    /// Omits the newline after the output of `--base64`
+    #[structopt(long)]
+    pub raw: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: String,
+
+    /// 1-based position of the test case, as shown by `judge`
+    pub nth: usize,
+}
+
+pub(crate) fn run(
+    opt: OptShowIn,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowIn {
+        out,
+        base64,
+        raw,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+        nth,
+    } = opt;
+
+    let crate::Context { cwd, shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let source =
+        crate::judge::nth_test_case_source(&workspace, service, contest.as_deref(), &problem, nth)?;
+
+    if base64 {
+        let encoded = base64::encode(crate::judge::read_text_source(&source.r#in)?);
+        let source = TextSource::Inline(if raw { encoded } else { encoded + "\n" }.into());
+        crate::judge::copy_text_source(&source, out.as_deref(), shell.stdout)
+    } else {
+        crate::judge::copy_text_source(&source.r#in, out.as_deref(), shell.stdout)
+    }
+}
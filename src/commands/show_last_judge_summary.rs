@@ -0,0 +1,131 @@
+use crate::judge::RunLog;
+use snowchains_core::{color_spec, judge::JudgeSummaryCase};
+use std::{io::BufRead, path::PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowLastJudgeSummary {
+    /// Also prints the input and the outputs of the failed test cases
+    #[structopt(long)]
+    pub full: bool,
+
+    /// Prints the result as JSON
+    #[structopt(long, conflicts_with("full"))]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+}
+
+pub(crate) fn run(
+    opt: OptShowLastJudgeSummary,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowLastJudgeSummary {
+        full,
+        json,
+        config,
+        color: _,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (_, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let (path, run_log) = RunLog::load_latest(&workspace)?;
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&run_log)?)?;
+        shell.stdout.flush()?;
+        return Ok(());
+    }
+
+    let RunLog {
+        service,
+        contest,
+        problem,
+        src,
+        summary,
+    } = run_log;
+
+    shell.stderr.set_color(color_spec!(Bold))?;
+    write!(
+        shell.stderr,
+        "{} {}{} ({})",
+        service.to_kebab_case_str(),
+        contest.map(|c| format!("{}/", c)).unwrap_or_default(),
+        problem,
+        src,
+    )?;
+    shell.stderr.reset()?;
+    writeln!(shell.stderr, " from {}", path.display())?;
+    shell.stderr.flush()?;
+
+    write!(shell.stdout, "{}", summary.to_table())?;
+
+    if full {
+        let stdout = &mut shell.stdout;
+        let num_cases = summary.cases.len();
+
+        for (i, case) in summary.cases.iter().enumerate() {
+            let JudgeSummaryCase {
+                name,
+                accepted,
+                summary: verdict,
+                stdin,
+                expected,
+                stdout: actual_stdout,
+                stderr: actual_stderr,
+            } = case;
+
+            if *accepted {
+                continue;
+            }
+
+            writeln!(stdout)?;
+            stdout.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+            write!(
+                stdout,
+                "{}/{} ({:?}) {}",
+                i + 1,
+                num_cases,
+                name.as_deref().unwrap_or(""),
+                verdict,
+            )?;
+            stdout.reset()?;
+            writeln!(stdout)?;
+
+            for (header, text) in &[
+                ("stdin:", stdin),
+                ("expected:", expected),
+                ("actual:", actual_stdout),
+                ("stderr:", actual_stderr),
+            ] {
+                if let Some(text) = text {
+                    stdout.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+                    write!(stdout, "{}", header)?;
+                    stdout.reset()?;
+                    writeln!(stdout)?;
+                    write!(stdout, "{}", text)?;
+                    if !text.ends_with('\n') {
+                        writeln!(stdout)?;
+                    }
+                }
+            }
+        }
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use anyhow::Context as _;
+use maplit::btreeset;
+use serde::Serialize;
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowProblemCategory {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowProblemCategory,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowProblemCategory {
+        json,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let problem = problem
+        .or(detected_target.problem)
+        .with_context(|| "`problem` was not detected. Specify with the positional argument")?;
+
+    let outcome = crate::commands::retrieve_testcases::retrieve(
+        service,
+        contest,
+        Some(btreeset!(problem.clone())),
+        None,
+        &mut shell,
+    )?;
+
+    let statement = outcome
+        .problems
+        .into_iter()
+        .next()
+        .with_context(|| format!("Could not find `{}`", problem))?
+        .statement
+        .with_context(|| "Could not extract the problem statement")?;
+
+    let categories = classify(&statement);
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&categories)?)?;
+    } else if categories.is_empty() {
+        shell.stderr.set_color(color_spec!(Fg(Color::Yellow)))?;
+        write!(shell.stderr, "No category was guessed.")?;
+        shell.stderr.reset()?;
+        writeln!(shell.stderr)?;
+        shell.stderr.flush()?;
+    } else {
+        for Category { name, keywords } in &categories {
+            writeln!(shell.stdout, "{} ({})", name, keywords.join(", "))?;
+        }
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Category {
+    name: &'static str,
+    keywords: Vec<&'static str>,
+}
+
+/// Guesses the categories of a problem from its statement.
+///
+/// This is a plain keyword matching. ASCII keywords only match whole words. The result is sorted
+/// by the number of matched keywords.
+fn classify(statement: &str) -> Vec<Category> {
+    static KEYWORDS: &[(&str, &[&str])] = &[
+        (
+            "graph",
+            &[
+                "graph",
+                "vertex",
+                "vertices",
+                "edge",
+                "shortest path",
+                "tree",
+                "グラフ",
+                "頂点",
+                "最短経路",
+                "木構造",
+            ],
+        ),
+        (
+            "math",
+            &[
+                "modulo",
+                "mod 10^9+7",
+                "mod 998244353",
+                "prime",
+                "gcd",
+                "divisor",
+                "で割った余り",
+                "素数",
+                "約数",
+            ],
+        ),
+        (
+            "dp",
+            &[
+                "dp[",
+                "number of ways",
+                "maximum total",
+                "subsequence",
+                "通り数",
+                "部分列",
+            ],
+        ),
+        (
+            "string",
+            &["string", "substring", "palindrome", "文字列", "回文"],
+        ),
+        (
+            "geometry",
+            &[
+                "coordinate",
+                "polygon",
+                "circle",
+                "angle",
+                "座標",
+                "多角形",
+                "円周",
+            ],
+        ),
+        (
+            "interactive",
+            &[
+                "interactive",
+                "standard output and flush",
+                "インタラクティブ",
+            ],
+        ),
+    ];
+
+    let statement = statement.to_lowercase();
+    let statement = statement
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" + ", "+");
+
+    let mut categories = KEYWORDS
+        .iter()
+        .map(|&(name, keywords)| Category {
+            name,
+            keywords: keywords
+                .iter()
+                .copied()
+                .filter(|k| contains_keyword(&statement, k))
+                .collect(),
+        })
+        .filter(|Category { keywords, .. }| !keywords.is_empty())
+        .collect::<Vec<_>>();
+
+    categories.sort_by_key(|Category { keywords, .. }| std::cmp::Reverse(keywords.len()));
+    categories
+}
+
+/// Whether `statement` contains `keyword`.
+///
+/// An ASCII keyword has to be on word boundaries like `\b{keyword}\b`, except that it may be
+/// followed by a plural "s". "tree" matches "trees" but not "street".
+fn contains_keyword(statement: &str, keyword: &str) -> bool {
+    if !keyword.is_ascii() {
+        return statement.contains(keyword);
+    }
+
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    statement.match_indices(keyword).any(|(i, _)| {
+        let before = &statement[..i];
+        let after = &statement[i + keyword.len()..];
+        let after = after.strip_prefix('s').unwrap_or(after);
+
+        !(keyword.starts_with(is_word_char) && before.ends_with(is_word_char)
+            || keyword.ends_with(is_word_char) && after.starts_with(is_word_char))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Category;
+
+    #[test]
+    fn classify() {
+        assert_eq!(
+            super::classify(
+                "Given a weighted graph with N vertices, find the shortest path. \
+                 Print the answer modulo 998244353.",
+            ),
+            [
+                Category {
+                    name: "graph",
+                    keywords: vec!["graph", "vertices", "shortest path"],
+                },
+                Category {
+                    name: "math",
+                    keywords: vec!["modulo"],
+                },
+            ],
+        );
+
+        assert_eq!(super::classify("Print `Hello`."), []);
+
+        assert_eq!(
+            super::classify(
+                "Takahashi, who has knowledge of every street in the town, bought a gift for \
+                 1000 円. Print the number of the shops he visited.",
+            ),
+            [],
+        );
+
+        assert_eq!(
+            super::classify("Given N strings, find the number of edges."),
+            [
+                Category {
+                    name: "graph",
+                    keywords: vec!["edge"],
+                },
+                Category {
+                    name: "string",
+                    keywords: vec!["string"],
+                },
+            ],
+        );
+    }
+}
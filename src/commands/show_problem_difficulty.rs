@@ -0,0 +1,176 @@
+use anyhow::{bail, Context as _};
+use maplit::btreeset;
+use serde::{Deserialize, Serialize};
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    collections::HashMap,
+    env,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowProblemDifficulty {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c") or screen name (e.g. "abc100_a")
+    pub problem: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ProblemModel {
+    #[serde(default)]
+    pub(crate) difficulty: Option<f64>,
+    #[serde(default)]
+    is_experimental: bool,
+}
+
+pub(crate) fn run(
+    opt: OptShowProblemDifficulty,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowProblemDifficulty {
+        json,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    if service != PlatformKind::Atcoder {
+        bail!("`show problem-difficulty` is only supported for AtCoder");
+    }
+
+    let contest = contest.or(detected_target.contest);
+
+    let problem = problem
+        .or(detected_target.problem)
+        .with_context(|| "`problem` was not detected. Specify with the positional argument")?;
+
+    let mut models = problem_models(&mut shell)?;
+
+    // Most of the screen names are `{contest}_{index}`. For the others, looks up the actual one.
+    let candidates = itertools::chain(
+        Some(problem.to_lowercase()),
+        contest
+            .as_ref()
+            .map(|contest| format!("{}_{}", contest, problem).to_lowercase()),
+    )
+    .collect::<Vec<_>>();
+
+    let model = match candidates.iter().find_map(|c| models.remove(c)) {
+        Some(model) => model,
+        None => {
+            let outcome = crate::commands::retrieve_testcases::retrieve(
+                service,
+                contest,
+                Some(btreeset!(problem.clone())),
+                None,
+                &mut shell,
+            )?;
+
+            outcome
+                .problems
+                .into_iter()
+                .next()
+                .and_then(|p| p.screen_name)
+                .and_then(|screen_name| models.remove(&screen_name))
+                .with_context(|| format!("`{}` is not found in AtCoder Problems", problem))?
+        }
+    };
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&model)?)?;
+    } else if let Some(difficulty) = model.difficulty {
+        let experimental = if model.is_experimental {
+            " (experimental)"
+        } else {
+            ""
+        };
+        writeln!(shell.stdout, "{}{}", difficulty.round(), experimental)?;
+    } else {
+        shell.stderr.set_color(color_spec!(Fg(Color::Yellow)))?;
+        write!(shell.stderr, "The difficulty is not estimated.")?;
+        shell.stderr.reset()?;
+        writeln!(shell.stderr)?;
+        shell.stderr.flush()?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
+
+/// Retrieves the problem models from AtCoder Problems, cached for an hour.
+pub(crate) fn problem_models(
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<HashMap<String, ProblemModel>> {
+    static URL: &str = "https://kenkoooo.com/atcoder/resources/problem-models.json";
+    const TTL: Duration = Duration::from_secs(60 * 60);
+
+    let cache_path = env::temp_dir()
+        .join("snowchains")
+        .join("problem-models.json");
+
+    if is_fresh(&cache_path, TTL) {
+        return crate::fs::read_json(&cache_path);
+    }
+
+    let content = crate::web::download(Url::parse(URL).unwrap(), shell)?;
+    let models = serde_json::from_slice(&content)
+        .with_context(|| format!("Could not parse the JSON from {}", URL))?;
+    crate::fs::write(&cache_path, content, true)?;
+    Ok(models)
+}
+
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    let elapsed = path
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    matches!(elapsed, Some(elapsed) if elapsed < ttl)
+}
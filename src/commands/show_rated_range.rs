@@ -0,0 +1,97 @@
+use anyhow::{bail, Context as _};
+use snowchains_core::web::{Atcoder, CookieStorage, PlatformKind, RatedRange, RetrieveRatedRange};
+use std::{
+    cell::RefCell,
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowRatedRange {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    pub contest: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowRatedRange,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowRatedRange {
+        json,
+        config,
+        color: _,
+        service,
+        contest,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    if service != PlatformKind::Atcoder {
+        bail!("`show rated-range` is only supported for AtCoder");
+    }
+
+    let contest = contest
+        .or(detected_target.contest)
+        .with_context(|| "`contest` was not detected. Specify with the positional argument")?;
+
+    let range = {
+        let shell = RefCell::new(&mut shell);
+
+        Atcoder::exec(RetrieveRatedRange {
+            contest,
+            cookie_storage: CookieStorage::with_jsonl(
+                crate::web::credentials::cookie_store_path()?
+            )?,
+            timeout: Some(crate::web::SESSION_TIMEOUT),
+            shell: &shell,
+        })?
+    };
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&range)?)?;
+    } else if let Some(RatedRange { low, high }) = range {
+        writeln!(shell.stdout, "{} - {}", low, high)?;
+    } else {
+        writeln!(shell.stdout, "Unrated")?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anyhow::{bail, Context as _};
+use snowchains_core::web::{PlatformKind, RetrieveRecentProblems, Yukicoder};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowRecentProblems {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Number of problems to show
+    #[structopt(long, value_name("N"), default_value("20"))]
+    pub limit: usize,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+}
+
+pub(crate) fn run(
+    opt: OptShowRecentProblems,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowRecentProblems {
+        json,
+        limit,
+        config,
+        color: _,
+        service,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let service = service.map(Ok).unwrap_or_else(|| {
+        let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+        detected_target.parse_service()?.with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })
+    })?;
+
+    let timeout = Some(crate::web::SESSION_TIMEOUT);
+
+    match service {
+        PlatformKind::Atcoder => bail!("`show recent-problems` is not supported for AtCoder"),
+        PlatformKind::Codeforces => {
+            bail!("`show recent-problems` is not supported for Codeforces")
+        }
+        PlatformKind::Yukicoder => {
+            let outcome = Yukicoder::exec(RetrieveRecentProblems {
+                limit,
+                cookie_storage: (),
+                timeout,
+                shell: &mut shell,
+            })?;
+
+            if json {
+                writeln!(shell.stdout, "{}", outcome.to_json())
+            } else {
+                write!(shell.stdout, "{}", outcome.to_table())
+            }?;
+
+            shell.stdout.flush()?;
+            Ok(())
+        }
+        PlatformKind::Aoj => bail!("`show recent-problems` is not supported for AOJ"),
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{config, messages::Message};
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use snowchains_core::{
+    color_spec,
+    terminal::escape_control_chars,
+    web::{
+        Atcoder, AtcoderRetrieveSubmissionSummariesCredentials, AtcoderSubmittedCode,
+        CookieStorage, PlatformKind, ProblemInContest, RetrieveSubmittedCode, SubmissionVerdict,
+    },
+};
+use std::{
+    cell::RefCell,
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowSubmissionCode {
+    /// Part of the language name on the service (e.g. "Rust")
+    #[structopt(long, value_name("STRING"))]
+    pub lang: Option<String>,
+
+    /// Verdicts of the submission. Only accepted ones by default
+    #[structopt(
+        long,
+        value_name("VERDICT"),
+        possible_values(SubmissionVerdict::VARIANTS)
+    )]
+    pub verdict: Option<Vec<SubmissionVerdict>>,
+
+    /// Writes the code to `src` of the language in `snowchains.dhall` instead of printing it
+    #[structopt(long)]
+    pub save: bool,
+
+    /// Does not write `{src}.submission.yaml` next to the code saved with `--save`
+    #[structopt(long, requires("save"))]
+    pub no_metadata: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(short, long, value_name("SERVICE"), possible_value("atcoder"))]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name in `snowchains.dhall`, used for `--save`
+    #[structopt(short, long, value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowSubmissionCode,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowSubmissionCode {
+        lang,
+        verdict,
+        save,
+        no_metadata,
+        config,
+        color: _,
+        service,
+        contest,
+        language,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let problem = problem
+        .or(detected_target.problem)
+        .with_context(|| "`problem` was not detected. Specify with the positional argument")?;
+
+    let problem_index = config::problem_naming(&cwd, config.as_deref(), service)?
+        .remote_index(contest.as_deref(), &problem);
+
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+    let timeout = Some(crate::web::SESSION_TIMEOUT);
+
+    let submitted = match service {
+        PlatformKind::Atcoder => {
+            let shell = RefCell::new(&mut shell);
+
+            let target = ProblemInContest::Index {
+                contest: contest
+                    .clone()
+                    .with_context(|| "`contest` is required for AtCoder")?,
+                problem: problem_index,
+            };
+
+            let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
+                username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
+                    &shell,
+                ),
+            };
+
+            Atcoder::exec(RetrieveSubmittedCode {
+                target,
+                language: lang,
+                verdicts: verdict,
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })?
+        }
+        PlatformKind::Codeforces | PlatformKind::Yukicoder | PlatformKind::Aoj => {
+            unreachable!("should be filtered by `possible_values`")
+        }
+    };
+
+    if !save {
+        let code = &submitted.code;
+        // Scraped code may contain `\r` or escape sequences that would mess up the terminal.
+        if atty::is(atty::Stream::Stdout) {
+            write!(shell.stdout, "{}", escape_control_chars(code))?;
+        } else {
+            write!(shell.stdout, "{}", code)?;
+        }
+        shell.stdout.flush()?;
+        return Ok(());
+    }
+
+    let (_, config::Language { src, .. }, base_dir) = config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        Some(service),
+        contest.as_deref(),
+        Some(&*problem),
+        language.as_deref(),
+        None,
+        config::Mode::Debug,
+    )?;
+
+    let path = base_dir.join(src);
+
+    if path.exists() {
+        shell.warn(format!("`{}` already exists", path.display()))?;
+        if !shell.confirm(Message::OverwritePrompt.get())? {
+            bail!("Aborted");
+        }
+    }
+
+    crate::fs::write(&path, &submitted.code, true)?;
+
+    let mut saved = vec![path.clone()];
+
+    if !no_metadata {
+        let metadata_path = metadata_path(&path);
+        let metadata = SubmissionMetadata::new(&submitted);
+        crate::fs::write(&metadata_path, serde_yaml::to_string(&metadata)?, true)?;
+        saved.push(metadata_path);
+    }
+
+    for path in saved {
+        write!(shell.stderr, "{} ", Message::SavedTo.get())?;
+        shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+        write!(shell.stderr, "{}", path.display())?;
+        shell.stderr.reset()?;
+        writeln!(shell.stderr)?;
+    }
+    shell.stderr.flush()?;
+    Ok(())
+}
+
+/// Written to `{src}.submission.yaml` by `--save`.
+#[derive(Debug, Serialize)]
+struct SubmissionMetadata<'a> {
+    id: Option<&'a str>,
+    url: &'a Url,
+    submitted_at: DateTime<FixedOffset>,
+    language: &'a str,
+    verdict: &'a str,
+    score: &'a str,
+    exec_time: Option<&'a str>,
+    memory: Option<&'a str>,
+}
+
+impl<'a> SubmissionMetadata<'a> {
+    fn new(submitted: &'a AtcoderSubmittedCode) -> Self {
+        Self {
+            id: submitted.url.path_segments().and_then(Iterator::last),
+            url: &submitted.url,
+            submitted_at: submitted.submission_time,
+            language: &submitted.language,
+            verdict: &submitted.status,
+            score: &submitted.score,
+            exec_time: submitted.exec_time.as_deref(),
+            memory: submitted.memory.as_deref(),
+        }
+    }
+}
+
+fn metadata_path(src: &Path) -> PathBuf {
+    let mut file_name = src.file_name().unwrap_or_default().to_owned();
+    file_name.push(".submission.yaml");
+    src.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn metadata_path() {
+        assert_eq!(
+            Path::new("rs/src/bin/a.rs.submission.yaml"),
+            super::metadata_path(Path::new("rs/src/bin/a.rs")),
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::{bail, Context as _};
+use maplit::btreeset;
+use snowchains_core::web::{
+    Atcoder, AtcoderRetrieveSubmissionSummariesCredentials,
+    AtcoderRetrieveSubmissionSummariesTarget, CookieStorage, PlatformKind,
+    RetrieveSubmissionSummaries,
+};
+use std::{
+    cell::RefCell,
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowSubmissionScore {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c") or screen name (e.g. "abc100_a")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowSubmissionScore,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowSubmissionScore {
+        json,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    if service != PlatformKind::Atcoder {
+        bail!("`show submission-score` is only supported for AtCoder");
+    }
+
+    let contest = contest
+        .or(detected_target.contest)
+        .with_context(|| "`contest` is required for AtCoder")?;
+
+    let problem = problem
+        .or(detected_target.problem)
+        .with_context(|| "`problem` was not detected. Specify with the positional argument")?;
+
+    let outcome = {
+        let shell = RefCell::new(&mut shell);
+
+        let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
+            username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
+                &shell,
+            ),
+        };
+
+        Atcoder::exec(RetrieveSubmissionSummaries {
+            target: AtcoderRetrieveSubmissionSummariesTarget {
+                contest,
+                problems: Some(btreeset!(problem.clone())),
+                max_pages: None,
+            },
+            credentials,
+            cookie_storage: CookieStorage::with_jsonl(
+                crate::web::credentials::cookie_store_path()?
+            )?,
+            timeout: Some(crate::web::SESSION_TIMEOUT),
+            shell: &shell,
+        })?
+    };
+
+    let score = outcome
+        .latest_score(&problem)
+        .with_context(|| format!("No submissions for `{}`", problem))?;
+
+    if json {
+        let score = serde_json::from_str::<serde_json::Number>(score)
+            .with_context(|| format!("Could not parse the score: {:?}", score))?;
+        writeln!(shell.stdout, "{}", serde_json::json!({ "score": score }))?;
+    } else {
+        writeln!(shell.stdout, "{}", score)?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anyhow::Context as _;
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{io::BufRead, path::PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowTestCase {
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: String,
+
+    /// 1-based position of the test case, as shown by `judge`
+    pub nth: usize,
+}
+
+pub(crate) fn run(
+    opt: OptShowTestCase,
+    ctx: crate::Context<impl BufRead, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowTestCase {
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+        nth,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let test_case =
+        crate::judge::nth_test_case(&workspace, service, contest.as_deref(), &problem, nth)?;
+
+    let stdout = &mut shell.stdout;
+
+    let mut write_field = |header: &str, value: &str| -> std::io::Result<()> {
+        stdout.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+        write!(stdout, "{}", header)?;
+        stdout.reset()?;
+        if value.contains('\n') {
+            writeln!(stdout)?;
+            write!(stdout, "{}", value)?;
+            if !value.ends_with('\n') {
+                writeln!(stdout)?;
+            }
+        } else {
+            writeln!(stdout, " {}", value)?;
+        }
+        Ok(())
+    };
+
+    write_field("name:", test_case.name.as_deref().unwrap_or(""))?;
+    write_field("origin:", test_case.origin.as_str())?;
+    if let Some(timelimit) = test_case.timelimit {
+        write_field("timelimit:", &format!("{}ms", timelimit.as_millis()))?;
+    }
+    write_field("in:", &test_case.input)?;
+    if let Some(expected) = test_case.output.expected_stdout() {
+        write_field("out:", expected)?;
+    } else if let Some(example) = test_case.output.example() {
+        write_field("example:", example)?;
+    }
+    if let Some(note) = &test_case.note {
+        write_field("note:", note)?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
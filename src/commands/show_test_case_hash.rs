@@ -0,0 +1,94 @@
+use anyhow::Context as _;
+use sha2::{Digest as _, Sha256};
+use snowchains_core::web::PlatformKind;
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowTestCaseHash {
+    /// Prints the whole hash instead of the first 16 characters
+    #[structopt(long)]
+    pub full: bool,
+
+    /// Omits the newline after the hash
+    #[structopt(long)]
+    pub raw: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: String,
+
+    /// 1-based position of the test case, as shown by `judge`
+    pub nth: usize,
+}
+
+pub(crate) fn run(
+    opt: OptShowTestCaseHash,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowTestCaseHash {
+        full,
+        raw,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+        nth,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let test_case =
+        crate::judge::nth_test_case(&workspace, service, contest.as_deref(), &problem, nth)?;
+
+    let hash = format!("{:x}", Sha256::digest(test_case.input.as_bytes()));
+    let hash = if full { &hash } else { &hash[..16] };
+
+    write!(shell.stdout, "{}", hash)?;
+    if !raw {
+        writeln!(shell.stdout)?;
+    }
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anyhow::Context as _;
+use snowchains_core::web::PlatformKind;
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowTestCaseTimeLimit {
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: String,
+
+    /// 1-based position of the test case, as shown by `judge`
+    pub nth: usize,
+}
+
+pub(crate) fn run(
+    opt: OptShowTestCaseTimeLimit,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowTestCaseTimeLimit {
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+        nth,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let scale = crate::config::judge_options(&cwd, config.as_deref())?.timelimitScale;
+
+    let test_case =
+        crate::judge::nth_test_case(&workspace, service, contest.as_deref(), &problem, nth)?;
+
+    // `timelimit` of the test case itself, or the one of the test suite. `judge` multiplies it by
+    // `judge.timelimitScale`.
+    if let Some(timelimit) = test_case.timelimit {
+        writeln!(
+            shell.stdout,
+            "{}ms ({}ms × {})",
+            timelimit.mul_f64(scale).as_millis(),
+            timelimit.as_millis(),
+            scale,
+        )?;
+    } else {
+        writeln!(shell.stdout, "none")?;
+    }
+    shell.stdout.flush()?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use anyhow::{bail, Context as _};
+use snowchains_core::web::{
+    Atcoder, AtcoderRetrieveSubmissionSummariesCredentials, CookieStorage, PlatformKind,
+    ProblemInContest, RetrieveTopSubmissions,
+};
+use std::{
+    cell::RefCell,
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowTopSubmissions {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Number of submissions to show
+    #[structopt(long, value_name("N"), default_value("10"))]
+    pub limit: usize,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowTopSubmissions,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowTopSubmissions {
+        json,
+        limit,
+        config,
+        color: _,
+        service,
+        contest,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let problem = problem
+        .or(detected_target.problem)
+        .with_context(|| "`problem` was not detected. Specify with the positional argument")?;
+
+    let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
+    let timeout = Some(crate::web::SESSION_TIMEOUT);
+
+    match service {
+        PlatformKind::Atcoder => {
+            let outcome = {
+                let shell = RefCell::new(&mut shell);
+
+                let target = ProblemInContest::Index {
+                    contest: contest.with_context(|| "`contest` is required for AtCoder")?,
+                    problem,
+                };
+
+                let credentials = AtcoderRetrieveSubmissionSummariesCredentials {
+                    username_and_password:
+                        &mut crate::web::credentials::atcoder_username_and_password(&shell),
+                };
+
+                Atcoder::exec(RetrieveTopSubmissions {
+                    target,
+                    credentials,
+                    limit,
+                    cookie_storage,
+                    timeout,
+                    shell: &shell,
+                })?
+            };
+
+            if json {
+                writeln!(shell.stdout, "{}", outcome.to_json())
+            } else {
+                write!(shell.stdout, "{}", outcome.to_table())
+            }?;
+
+            shell.stdout.flush()?;
+            Ok(())
+        }
+        PlatformKind::Codeforces => {
+            bail!("`show top-submissions` is not supported for Codeforces");
+        }
+        PlatformKind::Yukicoder => {
+            bail!("`show top-submissions` is not supported for yukicoder");
+        }
+        PlatformKind::Aoj => bail!("`show top-submissions` is not supported for AOJ"),
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{bail, Context as _};
+use cookie_store::CookieStore;
+use serde::Deserialize;
+use snowchains_core::web::PlatformKind;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+use url::Url;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowTotalAcProblems {
+    /// Also shows the counts per difficulty bracket of AtCoder Problems
+    #[structopt(long)]
+    pub breakdown: bool,
+
+    /// User to count for, instead of the one logged in
+    #[structopt(long, value_name("USERNAME"))]
+    pub user: Option<String>,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+}
+
+pub(crate) fn run(
+    opt: OptShowTotalAcProblems,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowTotalAcProblems {
+        breakdown,
+        user,
+        config,
+        color: _,
+        service,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let service = service.map(Ok).unwrap_or_else(|| {
+        let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+        detected_target.parse_service()?.with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })
+    })?;
+
+    if service != PlatformKind::Atcoder {
+        bail!("`show total-ac-problems` is only supported for AtCoder");
+    }
+
+    let user = match user {
+        Some(user) => user,
+        None => logged_in_atcoder_user()?.with_context(|| {
+            "Could not find the user logged in to AtCoder. Run `login` or specify `--user`"
+        })?,
+    };
+
+    let AcceptedCount { count } = serde_json::from_slice(&crate::web::download(
+        api_url("accepted_count", &[("user", &user)]),
+        &mut shell,
+    )?)
+    .with_context(|| "Could not parse the accepted count from AtCoder Problems")?;
+
+    writeln!(shell.stdout, "Accepted: {} problems", count)?;
+
+    if breakdown {
+        let accepted = accepted_problems(&user, &mut shell)?;
+        let models = crate::commands::show_problem_difficulty::problem_models(&mut shell)?;
+
+        let mut counts = BTreeMap::<_, usize>::new();
+        for problem_id in &accepted {
+            let difficulty = models.get(problem_id).and_then(|m| m.difficulty);
+            *counts.entry(difficulty.map(bracket)).or_default() += 1;
+        }
+
+        for (lower, count) in counts.iter().filter_map(|(b, c)| Some((b.as_ref()?, c))) {
+            writeln!(shell.stdout, "{:>4}-{:<4} {}", lower, lower + 399, count)?;
+        }
+        if let Some(count) = counts.get(&None) {
+            writeln!(shell.stdout, "Unknown   {}", count)?;
+        }
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct AcceptedCount {
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct Submission {
+    problem_id: String,
+    epoch_second: i64,
+    result: String,
+}
+
+fn api_url(endpoint: &str, query: &[(&str, &str)]) -> Url {
+    let mut url = "https://kenkoooo.com/atcoder/atcoder-api/v3/user/"
+        .parse::<Url>()
+        .unwrap()
+        .join(endpoint)
+        .unwrap();
+    url.query_pairs_mut().extend_pairs(query);
+    url
+}
+
+/// Collects the problems accepted by `user`, reading the submissions 500 at a time.
+fn accepted_problems(
+    user: &str,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<HashSet<String>> {
+    const PAGE_LEN: usize = 500;
+
+    let mut accepted = HashSet::new();
+    let mut from_second = 0;
+
+    loop {
+        let url = api_url(
+            "submissions",
+            &[("user", user), ("from_second", &from_second.to_string())],
+        );
+        let submissions =
+            serde_json::from_slice::<Vec<Submission>>(&crate::web::download(url, shell)?)
+                .with_context(|| "Could not parse the submissions from AtCoder Problems")?;
+
+        let last = submissions.iter().map(|s| s.epoch_second).max();
+
+        accepted.extend(
+            submissions
+                .iter()
+                .filter(|s| s.result == "AC")
+                .map(|s| s.problem_id.clone()),
+        );
+
+        match last {
+            Some(last) if submissions.len() >= PAGE_LEN => from_second = last + 1,
+            _ => break,
+        }
+
+        // AtCoder Problems asks for an interval of more than 1 second.
+        thread::sleep(Duration::from_secs(1));
+    }
+
+    Ok(accepted)
+}
+
+/// Lower bound of the 400-wide bracket that `difficulty` belongs to.
+fn bracket(difficulty: f64) -> u32 {
+    (difficulty.max(0.0) as u32) / 400 * 400
+}
+
+fn logged_in_atcoder_user() -> anyhow::Result<Option<String>> {
+    let path = crate::web::credentials::cookie_store_path()?;
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let cookie_store = File::open(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|h| {
+            CookieStore::load_json(BufReader::new(h)).map_err(|e| anyhow::anyhow!("{}", e))
+        })
+        .with_context(|| format!("Could not load cookies from `{}`", path.display()))?;
+
+    Ok(cookie_store
+        .get("atcoder.jp", "/", "REVEL_SESSION")
+        .and_then(|cookie| user_screen_name(cookie.value())))
+}
+
+/// Extracts `UserScreenName` from the value of `REVEL_SESSION`, which is a percent-encoded list
+/// of NUL-separated `key:value` pairs.
+fn user_screen_name(revel_session: &str) -> Option<String> {
+    const KEY: &str = "UserScreenName%3A";
+
+    let start = revel_session.find(KEY)? + KEY.len();
+    let value = &revel_session[start..];
+    let value = &value[..value.find("%00").unwrap_or(value.len())];
+    Some(value.to_owned()).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn user_screen_name() {
+        assert_eq!(
+            super::user_screen_name(
+                "0123abcd-%00a%3Afalse%00%00SessionKey%3Axyz%00%00UserScreenName%3Awariuni%00%00w%3Afalse%00",
+            ),
+            Some("wariuni".to_owned()),
+        );
+        assert_eq!(super::user_screen_name("0123abcd-%00a%3Afalse%00"), None);
+    }
+
+    #[test]
+    fn bracket() {
+        assert_eq!(super::bracket(-1000.0), 0);
+        assert_eq!(super::bracket(399.9), 0);
+        assert_eq!(super::bracket(400.0), 400);
+        assert_eq!(super::bracket(2856.0), 2800);
+    }
+}
@@ -0,0 +1,124 @@
+use anyhow::Context as _;
+use serde::Serialize;
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowUnreachableLanguages {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(&["atcoder", "codeforces", "yukicoder"])
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnreachableLanguage {
+    language: String,
+    language_id: String,
+}
+
+pub(crate) fn run(
+    opt: OptShowUnreachableLanguages,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowUnreachableLanguages {
+        json,
+        config,
+        color: _,
+        service,
+        contest,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (detected_target, _) = crate::config::detect_target(&cwd, config.as_deref())?;
+
+    let service = service
+        .map(Ok)
+        .or_else(|| detected_target.parse_service().transpose())
+        .with_context(|| {
+            "`service` was not detected. To specify it, add `--service` to the arguments"
+        })??;
+
+    let contest = contest.or(detected_target.contest);
+
+    let language_ids =
+        crate::config::language_ids(&cwd, config.as_deref(), service, contest.as_deref())?;
+
+    let available = crate::commands::retrieve_languages::retrieve(
+        service,
+        contest,
+        detected_target.problem,
+        &mut shell,
+    )?
+    .names_by_id;
+
+    let unreachable = language_ids
+        .into_iter()
+        .filter(|(_, language_id)| {
+            !crate::commands::retrieve_languages::is_available(service, &available, language_id)
+        })
+        .map(|(language, language_id)| UnreachableLanguage {
+            language,
+            language_id,
+        })
+        .collect::<Vec<_>>();
+
+    if json {
+        writeln!(shell.stdout, "{}", serde_json::to_string(&unreachable)?)?;
+        shell.stdout.flush()?;
+    } else if unreachable.is_empty() {
+        shell
+            .stderr
+            .set_color(color_spec!(Bold, Fg(Color::Green)))?;
+        write!(shell.stderr, "OK")?;
+        shell.stderr.reset()?;
+        writeln!(
+            shell.stderr,
+            ": every `languageId` is available on {}",
+            service.to_kebab_case_str(),
+        )?;
+        shell.stderr.flush()?;
+    } else {
+        for UnreachableLanguage {
+            language,
+            language_id,
+        } in &unreachable
+        {
+            writeln!(shell.stdout, "{}: {:?}", language, language_id)?;
+        }
+        shell.stdout.flush()?;
+    }
+    Ok(())
+}
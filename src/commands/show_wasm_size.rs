@@ -0,0 +1,118 @@
+use crate::config;
+use anyhow::Context as _;
+use snowchains_core::web::PlatformKind;
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptShowWasmSize {
+    /// Prints the result as JSON
+    #[structopt(long)]
+    pub json: bool,
+
+    /// Looks for the output of `Release` mode
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name
+    #[structopt(short, long, alias("lang"), value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptShowWasmSize,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptShowWasmSize {
+        json,
+        release,
+        config,
+        color: _,
+        service,
+        contest,
+        language,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (_, config::Language { compile, .. }, base_dir) = config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        service,
+        contest.as_deref(),
+        problem.as_deref(),
+        language.as_deref(),
+        None,
+        if release {
+            config::Mode::Release
+        } else {
+            config::Mode::Debug
+        },
+    )?;
+
+    let config::Compile { output, .. } =
+        compile.with_context(|| "The language does not have `compile`")?;
+
+    let path = wasm_path(&base_dir, &output);
+
+    let size = crate::fs::metadata(&path)
+        .with_context(|| "Build it first with `judge`")?
+        .len();
+
+    if json {
+        let json = serde_json::json!({ "path": path, "bytes": size });
+        writeln!(shell.stdout, "{}", json)?;
+    } else {
+        writeln!(
+            shell.stdout,
+            "{} bytes ({:.1} KB)",
+            size,
+            size as f64 / 1024.0,
+        )?;
+    }
+
+    shell.stdout.flush()?;
+    Ok(())
+}
+
+/// `compile.output` with the extension replaced with `.wasm`.
+fn wasm_path(base_dir: &Path, output: &str) -> PathBuf {
+    let output = Path::new(output);
+    base_dir
+        .join(output.strip_prefix(".").unwrap_or(output))
+        .with_extension("wasm")
+}
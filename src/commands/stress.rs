@@ -0,0 +1,180 @@
+use crate::config;
+use anyhow::{ensure, Context as _};
+use human_size::Size;
+use indicatif::ProgressDrawTarget;
+use snowchains_core::web::PlatformKind;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::WriteColor;
+
+#[derive(StructOpt, Debug)]
+pub struct OptStress {
+    /// Language of the brute force solution to compare with. Defaults to the language of the
+    /// solution
+    #[structopt(long, value_name("STRING"))]
+    pub brute: Option<String>,
+
+    /// Variant of the brute force solution. Defaults to "slow" without `--brute`
+    #[structopt(long, value_name("STRING"))]
+    pub brute_variant: Option<String>,
+
+    /// Number of inputs to try
+    #[structopt(long, value_name("N"), default_value("100"))]
+    pub iterations: usize,
+
+    /// Build in `Release` mode
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Display limit
+    #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
+    pub display_limit: Size,
+
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+
+    /// Platform
+    #[structopt(
+        short,
+        long,
+        value_name("SERVICE"),
+        possible_values(PlatformKind::KEBAB_CASE_VARIANTS)
+    )]
+    pub service: Option<PlatformKind>,
+
+    /// Contest ID
+    #[structopt(short, long, value_name("STRING"))]
+    pub contest: Option<String>,
+
+    /// Language name
+    #[structopt(short, long, value_name("STRING"))]
+    pub language: Option<String>,
+
+    /// Problem index (e.g. "a", "b", "c")
+    pub problem: Option<String>,
+}
+
+pub(crate) fn run(
+    opt: OptStress,
+    ctx: crate::Context<impl Sized, impl WriteColor, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptStress {
+        brute,
+        brute_variant,
+        iterations,
+        release,
+        display_limit,
+        config,
+        color: _,
+        service,
+        contest,
+        language,
+        problem,
+    } = opt;
+
+    let crate::Context { cwd, shell } = ctx;
+
+    let mode = if release {
+        config::Mode::Release
+    } else {
+        config::Mode::Debug
+    };
+
+    let (target, solution, base_dir) = config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        service,
+        contest.as_deref(),
+        problem.as_deref(),
+        language.as_deref(),
+        None,
+        mode,
+    )?;
+
+    // `a.cpp` and `a_slow.cpp` by default.
+    let brute_variant = brute_variant.or_else(|| brute.is_none().then(|| "slow".to_owned()));
+
+    let (_, brute, _) = config::target_and_language(
+        &cwd,
+        config.as_deref(),
+        Some(target.service),
+        target.contest.as_deref(),
+        Some(&target.problem),
+        brute.as_deref().or(language.as_deref()),
+        brute_variant.as_deref(),
+        mode,
+    )
+    .with_context(|| {
+        "Could not find the brute force solution. Put `{variant}` in `src` or specify another \
+         language with `--brute`"
+    })?;
+
+    ensure!(
+        brute.src != solution.src,
+        "The brute force solution is `{}` itself. Put `{{variant}}` in `src` or specify another \
+         language with `--brute`",
+        solution.src,
+    );
+
+    if let (Some(solution), Some(brute)) = (&solution.compile, &brute.compile) {
+        ensure!(
+            brute.output != solution.output,
+            "The brute force solution would be compiled to `{}` as well. Put `{{variant}}` in the \
+             `output` of `compile`",
+            solution.output,
+        );
+    }
+
+    let options = config::judge_options(&cwd, config.as_deref())?;
+    let generator = config::stress_generator(&cwd, config.as_deref(), &target)?;
+
+    let config::Target {
+        service,
+        contest,
+        problem,
+        ..
+    } = target;
+
+    let crate::shell::Shell {
+        stdout,
+        stderr,
+        stderr_tty,
+        stdin_process_redirection,
+        stdout_process_redirection,
+        stderr_process_redirection,
+        ..
+    } = shell;
+
+    crate::judge::stress(crate::judge::StressArgs {
+        stdout,
+        stderr,
+        stdin_process_redirection,
+        stdout_process_redirection,
+        stderr_process_redirection,
+        progress_draw_target: if stderr_tty {
+            ProgressDrawTarget::stderr()
+        } else {
+            ProgressDrawTarget::hidden()
+        },
+        base_dir,
+        service,
+        contest,
+        problem,
+        solution,
+        brute,
+        options,
+        generator,
+        iterations,
+        display_limit,
+    })
+}
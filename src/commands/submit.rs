@@ -1,12 +1,29 @@
-use crate::config;
+use crate::{
+    config::{self, WebhookEvent},
+    outbox::QueuedSubmission,
+};
 use anyhow::{bail, Context as _};
+use chrono::{DateTime, Utc};
+use fs2::FileExt as _;
 use human_size::Size;
+use itertools::Itertools as _;
+use maplit::btreeset;
 use snowchains_core::web::{
-    Atcoder, AtcoderSubmitCredentials, Codeforces, CodeforcesSubmitCredentials, CookieStorage,
-    PlatformKind, ProblemInContest, Submit, Yukicoder, YukicoderSubmitCredentials,
+    Atcoder, AtcoderLatestSubmission, AtcoderRetrieveSubmissionSummariesCredentials,
+    AtcoderRetrieveSubmissionSummariesTarget, AtcoderSubmitCredentials, Codeforces,
+    CodeforcesSubmitCredentials, CookieStorage, PlatformKind, ProblemInContest,
+    RetrieveSubmissionSummaries, Submit, SubmitOutcome, Yukicoder, YukicoderSubmitCredentials,
     YukicoderSubmitTarget,
 };
-use std::{cell::RefCell, env, io::BufRead, iter, path::PathBuf};
+use std::{
+    cell::RefCell,
+    env,
+    fs::{File, OpenOptions},
+    io::{BufRead, Write as _},
+    iter,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use termcolor::WriteColor;
@@ -21,14 +38,48 @@ pub struct OptSubmit {
     #[structopt(long)]
     pub no_judge: bool,
 
+    /// Judges the code with the existing binary instead of compiling it again
+    #[structopt(long, conflicts_with("no-judge"))]
+    pub skip_compile: bool,
+
+    /// Does not show the diffs of the expected and actual outputs for `Wrong Answer`
+    #[structopt(long, conflicts_with("no-judge"))]
+    pub no_diff: bool,
+
     /// Tests code in `Debug` mode
     #[structopt(long)]
     pub debug: bool,
 
-    /// Prints JSON data
+    /// Submits even if another `submit` for the problem is in progress
     #[structopt(long)]
+    pub force: bool,
+
+    /// Judges the code and queues it in `.snowchains/outbox/` instead of submitting it
+    #[structopt(long)]
+    pub queue: bool,
+
+    /// End of the contest (e.g. "2024-06-22T22:40+09:00") after which the queued code is not
+    /// submitted. Required for `--queue` when it cannot be retrieved
+    #[structopt(
+        long,
+        value_name("DATETIME"),
+        requires("queue"),
+        parse(try_from_str = crate::commands::retrieve_testcases::parse_datetime)
+    )]
+    pub contest_end: Option<DateTime<Utc>>,
+
+    /// Prints JSON data
+    #[structopt(long, conflicts_with("queue"))]
     pub json: bool,
 
+    /// Does not post to `notifications.webhook`
+    #[structopt(long)]
+    pub no_notify: bool,
+
+    /// Checks that `languageId` is available on the service before judging and submitting
+    #[structopt(long)]
+    pub check_lang_id: bool,
+
     /// Test for only the test cases
     #[structopt(long, value_name("NAME"))]
     pub testcases: Option<Vec<String>>,
@@ -66,6 +117,10 @@ pub struct OptSubmit {
     #[structopt(short, long, value_name("STRING"))]
     pub language: Option<String>,
 
+    /// Alternative solution (e.g. "brute")
+    #[structopt(long, value_name("STRING"))]
+    pub variant: Option<String>,
+
     /// Problem index (e.g. "a", "b", "c")
     pub problem: Option<String>,
 }
@@ -77,8 +132,15 @@ pub(crate) fn run(
     let OptSubmit {
         no_watch,
         no_judge,
+        skip_compile,
+        no_diff,
         debug,
+        force,
+        queue,
+        contest_end,
         json,
+        no_notify,
+        check_lang_id,
         testcases,
         display_limit,
         config,
@@ -86,6 +148,7 @@ pub(crate) fn run(
         service,
         contest,
         language,
+        variant,
         problem,
     } = opt;
 
@@ -96,15 +159,10 @@ pub(crate) fn run(
             service,
             contest,
             problem,
+            variant,
             mode: _,
         },
-        config::Language {
-            src,
-            transpile,
-            compile: _,
-            run: _,
-            languageId: language_id,
-        },
+        language_config,
         base_dir,
     ) = config::target_and_language(
         &cwd,
@@ -113,6 +171,7 @@ pub(crate) fn run(
         contest.as_deref(),
         problem.as_deref(),
         language.as_deref(),
+        variant.as_deref(),
         if debug {
             config::Mode::Debug
         } else {
@@ -120,10 +179,79 @@ pub(crate) fn run(
         },
     )?;
 
+    for warning in language_config.path_style_warnings(config::is_wsl()) {
+        shell.warn(warning)?;
+    }
+
+    // `problem` is the local name. The services take the original index.
+    let problem_index = config::problem_naming(&cwd, config.as_deref(), service)?
+        .remote_index(contest.as_deref(), &problem);
+
+    let config::Language {
+        src,
+        transpile,
+        compile: _,
+        run: _,
+        languageId: language_id,
+    } = language_config;
+
+    // Held until this function returns. The OS releases it even if the process is killed.
+    let _lock = if force {
+        None
+    } else {
+        Some(SubmitLock::acquire(
+            &base_dir,
+            service,
+            contest.as_deref(),
+            &problem,
+        )?)
+    };
+
     let code = crate::fs::read_to_string(base_dir.join(&src))?;
     let language_id = language_id.with_context(|| "Missing `languageId`")?;
 
+    if check_lang_id {
+        let available = crate::commands::retrieve_languages::retrieve(
+            service,
+            contest.clone(),
+            Some(problem_index.clone()),
+            &mut shell,
+        )?
+        .names_by_id;
+
+        if !crate::commands::retrieve_languages::is_available(service, &available, &language_id) {
+            bail!(
+                "Language ID {} not found. Available: {}",
+                language_id,
+                available
+                    .iter()
+                    .map(|(id, name)| format!("{} ({})", id, name))
+                    .join(", "),
+            );
+        }
+    }
+
     if no_judge {
+        // Hashed in the same way as `judge`, which reads the raw bytes.
+        let sha256 = crate::judge::sha256_hex(crate::fs::read(base_dir.join(&src))?);
+
+        let warning = match crate::judge::JudgeHistory::load(&base_dir)?.get(&src) {
+            None => Some(format!("`{}` has never passed `judge`", src)),
+            Some(passed) if passed.sha256 != sha256 => Some(format!(
+                "`{}` changed since the last passing judge {} ago",
+                src,
+                crate::judge::format_elapsed(passed.elapsed()),
+            )),
+            Some(_) => None,
+        };
+
+        if let Some(warning) = warning {
+            shell.warn(warning)?;
+            if !shell.confirm(crate::messages::Message::SubmitAnywayPrompt.get())? {
+                bail!("Aborted");
+            }
+        }
+
         if let Some(transpile) = &transpile {
             crate::judge::transpile(
                 &mut shell.stderr,
@@ -145,6 +273,13 @@ pub(crate) fn run(
                 vec![]
             })
             .args(&["--display-limit", &display_limit.to_string()])
+            .args(if no_notify { &["--no-notify"][..] } else { &[] })
+            .args(if skip_compile {
+                &["--skip-compile"][..]
+            } else {
+                &[]
+            })
+            .args(if no_diff { &["--no-diff"][..] } else { &[] })
             .arg("--config")
             .arg(base_dir.join("snowchains.dhall"))
             .args(&["--color", &color.to_string()])
@@ -159,6 +294,11 @@ pub(crate) fn run(
             } else {
                 vec![]
             })
+            .args(if variant.is_empty() {
+                vec![]
+            } else {
+                vec!["--variant".to_owned(), variant.clone()]
+            })
             .arg(&problem)
             .status()?;
 
@@ -167,15 +307,154 @@ pub(crate) fn run(
         }
     }
 
-    let watch_submission = !no_watch;
+    if queue {
+        let contest_end = match (contest_end, service, &contest) {
+            (Some(end), _, _) => Some(end),
+            (None, PlatformKind::Atcoder, Some(contest)) => Some(
+                crate::commands::retrieve_testcases::contest_duration(contest, &mut shell)
+                    .with_context(|| {
+                        "Could not retrieve the end of the contest. Specify it with `--contest-end`"
+                    })?
+                    .local_end(),
+            ),
+            (None, _, _) => {
+                shell.warn(format!(
+                    "The end of the contest is unknown for {}. The code will be submitted \
+                     whenever the outbox is flushed. Specify it with `--contest-end` to prevent it",
+                    service.to_kebab_case_str(),
+                ))?;
+                None
+            }
+        };
+
+        let path = QueuedSubmission {
+            service,
+            contest,
+            problem: problem_index,
+            src,
+            language_id,
+            code,
+            queued_at: Utc::now(),
+            contest_end,
+            submission_url: None,
+        }
+        .save(&base_dir)?;
+
+        writeln!(
+            shell.stderr,
+            "Queued to `{}`. Run `snowchains flush-outbox` to submit it",
+            path.display(),
+        )?;
+        shell.stderr.flush()?;
+        return Ok(());
+    }
+
+    crate::outbox::flush_if_any(&base_dir, &mut shell)?;
+
+    let webhook = if no_notify {
+        None
+    } else {
+        config::webhook(&cwd, config.as_deref())?
+    };
+
+    let outcome = submit(
+        service,
+        contest.clone(),
+        problem_index.clone(),
+        language_id,
+        code,
+        !no_watch,
+        &mut shell,
+    )?;
+
+    if json {
+        write!(shell.stdout, "{}", outcome.to_json())?;
+        shell.stdout.flush()?;
+    }
+
+    if let Some(webhook) = webhook.filter(|w| w.events.contains(&WebhookEvent::Submit)) {
+        // The verdict is known only if the submission has been watched until it is judged.
+        let latest = match (service, &contest) {
+            (PlatformKind::Atcoder, Some(contest)) if !no_watch => {
+                latest_atcoder_submission(contest, &problem_index, &mut shell)?
+            }
+            _ => None,
+        };
+
+        let notification = crate::notify::Notification {
+            event: WebhookEvent::Submit,
+            service,
+            contest: contest.as_deref(),
+            problem: &problem,
+            verdict: latest
+                .as_ref()
+                .map(|l| l.verdict.clone())
+                .unwrap_or_else(|| "Submitted".to_owned()),
+            score: latest.as_ref().map(|l| l.score.clone()),
+            time: latest
+                .as_ref()
+                .and_then(|l| l.exec_time.as_deref())
+                .and_then(|t| t.trim_end_matches("ms").trim().parse().ok())
+                .map(Duration::from_millis),
+            url: Some(
+                latest
+                    .as_ref()
+                    .map(|l| &l.url)
+                    .unwrap_or(&outcome.submission_url),
+            ),
+        };
+
+        if let Err(err) = crate::notify::post(&webhook, &notification) {
+            shell.warn(format!("{:#}", err))?;
+        }
+    }
+
+    Ok(())
+}
 
+fn latest_atcoder_submission(
+    contest: &str,
+    problem: &str,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Sized, impl WriteColor>,
+) -> anyhow::Result<Option<AtcoderLatestSubmission>> {
+    let shell = RefCell::new(shell);
+
+    let outcome = Atcoder::exec(RetrieveSubmissionSummaries {
+        target: AtcoderRetrieveSubmissionSummariesTarget {
+            contest: contest.to_owned(),
+            problems: Some(btreeset!(problem.to_owned())),
+            max_pages: None,
+        },
+        credentials: AtcoderRetrieveSubmissionSummariesCredentials {
+            username_and_password: &mut crate::web::credentials::atcoder_username_and_password(
+                &shell,
+            ),
+        },
+        cookie_storage: CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?,
+        timeout: Some(crate::web::SESSION_TIMEOUT),
+        shell: &shell,
+    })?;
+
+    Ok(outcome.latest_submission(problem))
+}
+
+/// Submits `code` without judging it.
+pub(crate) fn submit(
+    service: PlatformKind,
+    contest: Option<String>,
+    problem: String,
+    language_id: String,
+    code: String,
+    watch_submission: bool,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Sized, impl WriteColor>,
+) -> anyhow::Result<SubmitOutcome> {
     let cookie_storage = CookieStorage::with_jsonl(crate::web::credentials::cookie_store_path()?)?;
 
     let timeout = Some(crate::web::SESSION_TIMEOUT);
 
-    let outcome = match service {
+    match service {
         PlatformKind::Atcoder => {
-            let shell = RefCell::new(&mut shell);
+            let shell = RefCell::new(shell);
 
             let target = ProblemInContest::Index {
                 contest: contest.with_context(|| "`contest` is required for AtCoder")?,
@@ -206,9 +485,9 @@ pub(crate) fn run(
             };
 
             let (api_key, api_secret) =
-                crate::web::credentials::codeforces_api_key_and_secret(&mut shell)?;
+                crate::web::credentials::codeforces_api_key_and_secret(shell)?;
 
-            let shell = RefCell::new(&mut shell);
+            let shell = RefCell::new(shell);
 
             let credentials = CodeforcesSubmitCredentials {
                 username_and_password:
@@ -236,10 +515,10 @@ pub(crate) fn run(
             };
 
             let credentials = YukicoderSubmitCredentials {
-                api_key: crate::web::credentials::yukicoder_api_key(&mut shell)?,
+                api_key: crate::web::credentials::yukicoder_api_key(shell)?,
             };
 
-            let shell = RefCell::new(&mut shell);
+            let shell = RefCell::new(shell);
 
             Yukicoder::exec(Submit {
                 target,
@@ -252,12 +531,71 @@ pub(crate) fn run(
                 shell,
             })
         }
-    }?;
+        PlatformKind::Aoj => bail!("`submit` is not supported for AOJ"),
+    }
+}
 
-    if json {
-        write!(shell.stdout, "{}", outcome.to_json())?;
-        shell.stdout.flush()?;
+#[derive(Debug)]
+struct SubmitLock(File);
+
+impl SubmitLock {
+    fn acquire(
+        base_dir: &Path,
+        service: PlatformKind,
+        contest: Option<&str>,
+        problem: &str,
+    ) -> anyhow::Result<Self> {
+        let path = base_dir
+            .join(".snowchains")
+            .join("locks")
+            .join("submit")
+            .join(service.to_kebab_case_str())
+            .join(contest.unwrap_or(""))
+            .join(problem)
+            .with_extension("lock");
+
+        if let Some(parent) = path.parent() {
+            crate::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Could not open `{}`", path.display()))?;
+
+        if let Err(err) = file.try_lock_exclusive() {
+            if err.kind() != fs2::lock_contended_error().kind() {
+                return Err(err).with_context(|| format!("Could not lock `{}`", path.display()));
+            }
+
+            let elapsed = crate::fs::metadata(&path)?
+                .modified()
+                .ok()
+                .and_then(|t| SystemTime::now().duration_since(t).ok())
+                .map(|d| format!(" (started {}s ago)", d.as_secs()))
+                .unwrap_or_default();
+
+            bail!(
+                "a submit for {}{} is already in progress{}. To submit anyway, add `--force`",
+                contest.map(|c| format!("{}/", c)).unwrap_or_default(),
+                problem,
+                elapsed,
+            );
+        }
+
+        // Updates the modification time, which is used for the message above.
+        file.set_len(0)
+            .and_then(|()| writeln!(file, "{}", std::process::id()))
+            .with_context(|| format!("Could not write `{}`", path.display()))?;
+
+        Ok(Self(file))
     }
+}
 
-    Ok(())
+impl Drop for SubmitLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
 }
@@ -0,0 +1,87 @@
+use anyhow::bail;
+use snowchains_core::color_spec;
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+use termcolor::{Color, WriteColor};
+
+#[derive(StructOpt, Debug)]
+pub struct OptValidate {
+    /// Path to `snowchains.dhall`
+    #[structopt(long)]
+    pub config: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        possible_values(crate::ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: crate::ColorChoice,
+}
+
+pub(crate) fn run(
+    opt: OptValidate,
+    ctx: crate::Context<impl BufRead, impl Write, impl WriteColor>,
+) -> anyhow::Result<()> {
+    let OptValidate { config, color: _ } = opt;
+
+    let crate::Context { cwd, mut shell } = ctx;
+
+    let (path, errors) = crate::config::validate(&cwd, config.as_deref())?;
+
+    for (target, err) in &errors {
+        shell.stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+        write!(shell.stderr, "error:")?;
+        shell.stderr.reset()?;
+        writeln!(
+            shell.stderr,
+            " `languages` is invalid for {} ({:?})",
+            target.service, target.mode,
+        )?;
+        for cause in err.chain() {
+            writeln!(shell.stderr, "  {}", cause)?;
+        }
+    }
+    shell.stderr.flush()?;
+
+    if !errors.is_empty() {
+        bail!("{} error(s) in `{}`", errors.len(), path);
+    }
+
+    let (_, workspace) = crate::config::detect_target(&cwd, config.as_deref())?;
+    let links = crate::suite_link::find_links(&workspace.join(".snowchains").join("tests"))?;
+
+    let mut num_broken_links = 0;
+
+    for (link, target) in &links {
+        write!(shell.stderr, "{} -> ", link.display())?;
+        shell.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+        write!(shell.stderr, "{}", target.display())?;
+        shell.stderr.reset()?;
+        if !target.exists() {
+            num_broken_links += 1;
+            shell.stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+            write!(shell.stderr, " (broken)")?;
+            shell.stderr.reset()?;
+        }
+        writeln!(shell.stderr)?;
+    }
+    shell.stderr.flush()?;
+
+    if num_broken_links > 0 {
+        bail!("{} broken link(s) in the test suites", num_broken_links);
+    }
+
+    shell
+        .stderr
+        .set_color(color_spec!(Bold, Fg(Color::Green)))?;
+    write!(shell.stderr, "OK")?;
+    shell.stderr.reset()?;
+    writeln!(shell.stderr, ": `{}`", path)?;
+    shell.stderr.flush()?;
+    Ok(())
+}
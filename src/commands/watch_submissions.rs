@@ -1,4 +1,4 @@
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
 use snowchains_core::web::{
     Atcoder, AtcoderWatchSubmissionsCredentials, AtcoderWatchSubmissionsTarget, CookieStorage,
     PlatformKind, WatchSubmissions,
@@ -81,5 +81,6 @@ pub(crate) fn run(
         }
         PlatformKind::Codeforces => todo!(),
         PlatformKind::Yukicoder => todo!(),
+        PlatformKind::Aoj => bail!("`watch submissions` is not supported for AOJ"),
     }
 }
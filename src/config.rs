@@ -7,13 +7,17 @@ use heck::{CamelCase as _, KebabCase as _, MixedCase as _, SnakeCase as _};
 use indexmap::IndexMap;
 use itertools::Itertools as _;
 use maplit::hashmap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_dhall::{SimpleType, StaticType};
 use snowchains_core::web::PlatformKind;
 use std::{
-    collections::BTreeMap,
+    cmp,
+    collections::{BTreeMap, HashSet},
     convert::Infallible,
-    fmt,
+    env,
+    ffi::OsStr,
+    fmt, iter, mem,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
 };
 
@@ -30,6 +34,7 @@ pub(crate) fn detect_target(
     Ok((detected, dir))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn target_and_language(
     cwd: &Path,
     rel_path: Option<&Path>,
@@ -37,35 +42,50 @@ pub(crate) fn target_and_language(
     cli_opt_contest: Option<&str>,
     cli_opt_problem: Option<&str>,
     cli_opt_language: Option<&str>,
+    cli_opt_variant: Option<&str>,
     cli_opt_mode: Mode,
 ) -> anyhow::Result<(Target, Language, PathBuf)> {
     let path = find_snowchains_dhall(cwd, rel_path)?;
 
+    let workspace = Path::new(&path).parent().unwrap_or_else(|| path.as_ref());
+    let pinned_languages = PinnedLanguages::load(workspace)?;
+
     let (target, language_name) = Detected::load_and_eval(cwd, &path)?.merge_with_cli_options(
         cli_opt_service,
         cli_opt_contest,
         cli_opt_problem,
         cli_opt_language,
+        cli_opt_variant,
         cli_opt_mode,
+        &pinned_languages,
     )?;
 
-    let mut languages = serde_dhall::from_str(&format!(
-        "let target = {} let config = {} in config.languages target",
-        target.to_dhall_expr(),
-        path,
-    ))
-    .parse::<BTreeMap<String, Language>>()
-    .with_context(|| format!("Could not evaluate `{}`", path))?;
+    let mut languages = eval_languages(&path, &target)?;
 
     let expected_names = languages.keys().join(", ");
 
-    let language = languages.remove(&language_name).with_context(|| {
+    let mut language = languages.remove(&language_name).with_context(|| {
         format!(
             "The language `{}` not found. Expected one of [{}]",
             language_name, expected_names,
         )
     })?;
 
+    // Otherwise the main solution would be judged or submitted as the variant.
+    ensure!(
+        target.variant.is_empty() || language.src.contains("{variant}"),
+        "The variant `{}` was given, but `src` of `{}` (`{}`) has no `{{variant}}`",
+        target.variant,
+        language_name,
+        language.src,
+    );
+
+    language.expand_variant(&target.variant);
+
+    if is_wsl() && auto_wslpath(&path)? {
+        language.convert_windows_paths();
+    }
+
     let dir = Path::new(&path)
         .parent()
         .unwrap_or_else(|| path.as_ref())
@@ -74,6 +94,136 @@ pub(crate) fn target_and_language(
     Ok((target, language, dir))
 }
 
+/// Evaluates `languages` for every service and mode with a dummy problem, returning the failures.
+///
+/// The programs of the commands are also looked up in `$PATH`. Ones given as paths are not, since
+/// they may be outputs of `compile`.
+pub(crate) fn validate(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+) -> anyhow::Result<(String, Vec<(Target, anyhow::Error)>)> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+    let env_path = env::var_os("PATH").unwrap_or_default();
+
+    let mut errors = vec![];
+    let mut reported = HashSet::new();
+
+    for &service in &[
+        PlatformKind::Atcoder,
+        PlatformKind::Codeforces,
+        PlatformKind::Yukicoder,
+    ] {
+        for &mode in &[Mode::Debug, Mode::Release] {
+            let target = Target {
+                service,
+                contest: Some("dummy".to_owned()),
+                problem: "a".to_owned(),
+                variant: "".to_owned(),
+                mode,
+            };
+
+            let languages = match eval_languages(&path, &target) {
+                Ok(languages) => languages,
+                Err(err) => {
+                    errors.push((target, err));
+                    continue;
+                }
+            };
+
+            for (name, language) in &languages {
+                for (field, program) in language.programs() {
+                    if program.contains(std::path::is_separator)
+                        || is_in_path(program, &env_path)
+                        || !reported.insert((name.clone(), field.clone(), program.to_owned()))
+                    {
+                        continue;
+                    }
+                    errors.push((
+                        target.clone(),
+                        anyhow!(
+                            "`{}`: `{}` (`{}`) was not found in `$PATH`",
+                            name,
+                            field,
+                            program,
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok((path, errors))
+}
+
+/// Evaluates `languages` for `service` with a dummy problem, returning `languageId` of each
+/// language that has one.
+pub(crate) fn language_ids(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+    service: PlatformKind,
+    contest: Option<&str>,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    let target = Target {
+        service,
+        contest: Some(contest.unwrap_or("dummy").to_owned()),
+        problem: "a".to_owned(),
+        variant: "".to_owned(),
+        mode: Mode::Debug,
+    };
+
+    Ok(eval_languages(&path, &target)?
+        .into_iter()
+        .flat_map(|(name, Language { languageId, .. })| Some((name, languageId?)))
+        .collect())
+}
+
+/// Whether `program` is an executable file in one of `env_path`, trying each of `$PATHEXT` on
+/// Windows.
+fn is_in_path(program: &str, env_path: &OsStr) -> bool {
+    let exts = if cfg!(windows) {
+        env::var("PATHEXT").unwrap_or_default()
+    } else {
+        "".to_owned()
+    };
+
+    env::split_paths(env_path).any(|dir| {
+        is_executable(&dir.join(program))
+            || exts
+                .split(';')
+                .filter(|ext| !ext.is_empty())
+                .any(|ext| is_executable(&dir.join(format!("{}{}", program, ext))))
+    })
+}
+
+/// Whether `path` is a file that has any of the execute bits on Unix, or just a file on the other
+/// platforms.
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn eval_languages(path: &str, target: &Target) -> anyhow::Result<BTreeMap<String, Language>> {
+    serde_dhall::from_str(&format!(
+        "let target = {} let config = {} in config.languages target",
+        target.to_dhall_expr()?,
+        path,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `{}`", path))
+}
+
 pub(crate) fn xtask(cwd: &Path, rel_path: Option<&Path>, name: &str) -> anyhow::Result<Script> {
     let path = find_snowchains_dhall(cwd, rel_path)?;
 
@@ -93,6 +243,354 @@ pub(crate) fn xtask(cwd: &Path, rel_path: Option<&Path>, name: &str) -> anyhow::
     })
 }
 
+/// Evaluates `notifications.webhook`, which is not in the schema.
+///
+/// `None` if the config has no `notifications` field or no `webhook` in it.
+pub(crate) fn webhook(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<Option<Webhook>> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    let Notifications { webhook } = serde_dhall::from_str(&format!(
+        "let config = {} in ({{ notifications = {{=}} }} // config).notifications",
+        path,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `notifications` in `{}`", path))?;
+
+    return Ok(webhook);
+
+    #[derive(Deserialize)]
+    struct Notifications {
+        webhook: Option<Webhook>,
+    }
+}
+
+/// Evaluates `judge.extraTestDirs : Target → List Text`, which defaults to
+/// `Judge.default.extraTestDirs` in the schema.
+///
+/// The directories are relative to the workspace.
+pub(crate) fn extra_test_dirs(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+    target: &Target,
+) -> anyhow::Result<Vec<String>> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    serde_dhall::from_str(&format!(
+        "let target = {} \
+         let config = {} \
+         let Snowchains = ({}) \
+         in (Snowchains.Judge.default // ({{ judge = {{=}} }} // config).judge).extraTestDirs \
+              target",
+        target.to_dhall_expr()?,
+        path,
+        SCHEMA,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `judge.extraTestDirs` in `{}`", path))
+}
+
+/// Evaluates `judge` except `extraTestDirs`, completed with `Judge.default` in the schema.
+pub(crate) fn judge_options(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<JudgeOptions> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    let options = serde_dhall::from_str(&format!(
+        "let config = {} \
+         let Snowchains = ({}) \
+         let judge = Snowchains.Judge.default // ({{ judge = {{=}} }} // config).judge \
+         in judge.{{ timelimitScale, stdinEcho, exportEnv, utf8Env }}",
+        path, SCHEMA,
+    ))
+    .static_type_annotation()
+    .parse::<JudgeOptions>()
+    .with_context(|| format!("Could not evaluate `judge` in `{}`", path))?;
+
+    ensure!(
+        options.timelimitScale.is_finite() && options.timelimitScale > 0.0,
+        "`judge.timelimitScale` in `{}` must be a positive finite number",
+        path,
+    );
+    Ok(options)
+}
+
+/// Evaluates `paths.autoWslpath`, completed with `Paths.default` in the schema.
+fn auto_wslpath(path: &str) -> anyhow::Result<bool> {
+    serde_dhall::from_str(&format!(
+        "let config = {} \
+         let Snowchains = ({}) \
+         in (Snowchains.Paths.default // ({{ paths = {{=}} }} // config).paths).autoWslpath",
+        path, SCHEMA,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `paths.autoWslpath` in `{}`", path))
+}
+
+/// Evaluates `stress.generator : Target → List Text`, which is not in the schema.
+///
+/// The command that `stress` runs with the iteration index appended. Empty if the config has no
+/// such field.
+pub(crate) fn stress_generator(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+    target: &Target,
+) -> anyhow::Result<Vec<String>> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    serde_dhall::from_str(&format!(
+        "let target = {} \
+         let config = {} \
+         let fallback = {{ generator = \\(_ : {}) -> [] : List Text }} \
+         in (fallback // ({{ stress = {{=}} }} // config).stress).generator target",
+        target.to_dhall_expr()?,
+        path,
+        TARGET_TYPE,
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `stress.generator` in `{}`", path))
+}
+
+/// Evaluates `session.download.parallelism : Natural`, which is not in the schema.
+///
+/// The maximum number of files downloaded at once for `--full`. `4` if the config has no such
+/// field.
+pub(crate) fn download_parallelism(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+) -> anyhow::Result<NonZeroUsize> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    serde_dhall::from_str(&format!(
+        "let config = {} \
+         let session = ({{ session = {{=}} }} // config).session \
+         in ({{ parallelism = 4 }} // ({{ download = {{=}} }} // session).download).parallelism",
+        path,
+    ))
+    .parse()
+    .with_context(|| {
+        format!(
+            "Could not evaluate `session.download.parallelism` in `{}`",
+            path
+        )
+    })
+}
+
+/// Evaluates `problemNaming.{service}`, which is not in the schema.
+///
+/// `letters` if the config has no such field.
+pub(crate) fn problem_naming(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+    service: PlatformKind,
+) -> anyhow::Result<ProblemNaming> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    serde_dhall::from_str(&format!(
+        "let ProblemNaming = < letters | numbers | slug > \
+         let config = {} \
+         let fallback = \
+           {{ atcoder = ProblemNaming.letters \
+           , codeforces = ProblemNaming.letters \
+           , yukicoder = ProblemNaming.letters \
+           , aoj = ProblemNaming.letters \
+           }} \
+         in (fallback // ({{ problemNaming = {{=}} }} // config).problemNaming).{}",
+        path,
+        service.to_kebab_case_str(),
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `problemNaming` in `{}`", path))
+}
+
+/// Evaluates `defaultProblems.{service}`, which is not in the schema.
+///
+/// The problems to retrieve when none are given. `None` if the config has no such field.
+pub(crate) fn default_problems(
+    cwd: &Path,
+    rel_path: Option<&Path>,
+    service: PlatformKind,
+    contest: Option<&str>,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let path = find_snowchains_dhall(cwd, rel_path)?;
+
+    let default_problems: DefaultProblems = serde_dhall::from_str(&format!(
+        "let config = {} \
+         let fallback = \
+           {{ default = [] : List Text \
+           , contests = [] : List {{ mapKey : Text, mapValue : List Text }} \
+           }} \
+         let defaultProblems = ({{ defaultProblems = {{=}} }} // config).defaultProblems \
+         in fallback // ({{ {service} = {{=}} }} // defaultProblems).{service}",
+        path,
+        service = service.to_kebab_case_str(),
+    ))
+    .parse()
+    .with_context(|| format!("Could not evaluate `defaultProblems` in `{}`", path))?;
+
+    Ok(default_problems.for_contest(contest))
+}
+
+/// `{ default : List Text, contests : List { mapKey : Text, mapValue : List Text } }`
+///
+/// `contests` is usually written with `toMap`.
+#[derive(Deserialize, Debug)]
+struct DefaultProblems {
+    default: Vec<String>,
+    contests: Vec<DefaultProblemsEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct DefaultProblemsEntry {
+    map_key: String,
+    map_value: Vec<String>,
+}
+
+impl DefaultProblems {
+    /// An entry in `contests` takes precedence over `default`. An empty list means all problems.
+    fn for_contest(self, contest: Option<&str>) -> Option<Vec<String>> {
+        let Self { default, contests } = self;
+
+        let problems = contest
+            .and_then(|contest| {
+                contests
+                    .into_iter()
+                    .find(|DefaultProblemsEntry { map_key, .. }| {
+                        map_key.eq_ignore_ascii_case(contest)
+                    })
+            })
+            .map(|DefaultProblemsEntry { map_value, .. }| map_value)
+            .unwrap_or(default);
+
+        Some(problems).filter(|ps| !ps.is_empty())
+    }
+}
+
+/// A key read from `snowchains.dhall`.
+#[derive(Serialize, Debug)]
+pub(crate) struct ConfigKey {
+    pub(crate) key: &'static str,
+    pub(crate) r#type: &'static str,
+    /// `None` if the key is in `Config` of the schema, which makes it required.
+    pub(crate) default: Option<&'static str>,
+}
+
+/// Every key read from `snowchains.dhall`. `<service>` is `atcoder`, `codeforces`, `yukicoder`, or
+/// `aoj`.
+///
+/// Keep this in sync with the schema and the functions above that evaluate the other keys.
+pub(crate) static ALL_KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        key: "detectServiceFromRelativePathSegments",
+        r#type: "List Text → Optional Text",
+        default: None,
+    },
+    ConfigKey {
+        key: "detectContestFromRelativePathSegments",
+        r#type: "List Text → Optional Text",
+        default: None,
+    },
+    ConfigKey {
+        key: "detectProblemFromRelativePathSegments",
+        r#type: "List Text → Optional Text",
+        default: None,
+    },
+    ConfigKey {
+        key: "detectLanguageFromRelativePathSegments",
+        r#type: "List Text → Optional Text",
+        default: None,
+    },
+    ConfigKey {
+        key: "languages",
+        r#type: "Target → Map Text Language",
+        default: None,
+    },
+    ConfigKey {
+        key: "xtask",
+        r#type: "Map Text Script",
+        default: None,
+    },
+    ConfigKey {
+        key: "notifications.webhook",
+        r#type:
+            "{ url : Text, events : List < submit | judge >, format : < discord | slack | json > }",
+        default: Some("no webhook"),
+    },
+    ConfigKey {
+        key: "judge.timelimitScale",
+        r#type: "Double",
+        default: Some("1.0"),
+    },
+    ConfigKey {
+        key: "judge.stdinEcho",
+        r#type: "Bool",
+        default: Some("False"),
+    },
+    ConfigKey {
+        key: "judge.exportEnv",
+        r#type: "Bool",
+        default: Some("True"),
+    },
+    ConfigKey {
+        key: "judge.utf8Env",
+        r#type: "Bool",
+        default: Some("True"),
+    },
+    ConfigKey {
+        key: "judge.extraTestDirs",
+        r#type: "Target → List Text",
+        default: Some("λ(_ : Target) → [] : List Text"),
+    },
+    ConfigKey {
+        key: "paths.autoWslpath",
+        r#type: "Bool",
+        default: Some("False"),
+    },
+    ConfigKey {
+        key: "stress.generator",
+        r#type: "Target → List Text",
+        default: Some("λ(_ : Target) → [] : List Text"),
+    },
+    ConfigKey {
+        key: "session.download.parallelism",
+        r#type: "Natural",
+        default: Some("4"),
+    },
+    ConfigKey {
+        key: "problemNaming.<service>",
+        r#type: "< letters | numbers | slug >",
+        default: Some("letters"),
+    },
+    ConfigKey {
+        key: "defaultProblems.<service>",
+        r#type: "{ default : List Text, contests : Map Text (List Text) }",
+        default: Some("all of the problems"),
+    },
+];
+
+/// The schema, which also has the defaults of `judge` and `paths`.
+const SCHEMA: &str = include_str!("../resources/config/schema/Snowchains/package.dhall");
+
+/// `Target` in the schema.
+const TARGET_TYPE: &str = r"{ service : < Atcoder | Codeforces | Yukicoder >
+, contest :
+    Optional
+      { lowercase : Text
+      , uppercase : Text
+      , snakeCase : Text
+      , kebabCase : Text
+      , mixedCase : Text
+      , pascalCase : Text
+      }
+, problem :
+    { lowercase : Text
+    , uppercase : Text
+    , snakeCase : Text
+    , kebabCase : Text
+    , mixedCase : Text
+    , pascalCase : Text
+    }
+, mode : < Debug | Release >
+}";
+
 fn find_snowchains_dhall(cwd: &Path, rel_path: Option<&Path>) -> anyhow::Result<String> {
     let path = if let Some(rel_path) = rel_path {
         let rel_path = rel_path.strip_prefix(".").unwrap_or(rel_path);
@@ -188,13 +686,16 @@ in  {{ service = config.detectServiceFromRelativePathSegments relativePathSegmen
         .with_context(|| format!("Could not evalute `{}`", path))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn merge_with_cli_options(
         &self,
         service: Option<PlatformKind>,
         contest: Option<&str>,
         problem: Option<&str>,
         language: Option<&str>,
+        variant: Option<&str>,
         mode: Mode,
+        pinned_languages: &PinnedLanguages,
     ) -> anyhow::Result<(Target, String)> {
         let service = service.map(Ok).unwrap_or_else(|| {
             self.service
@@ -221,18 +722,18 @@ in  {{ service = config.detectServiceFromRelativePathSegments relativePathSegmen
             .to_owned();
 
         let language = language
-            .map(Ok)
-            .unwrap_or_else(|| {
-                self.language
-                    .as_deref()
-                    .with_context(|| "`language` was not detected. Specify with `--language`")
-            })?
+            .or_else(|| pinned_languages.get(service, contest.as_deref(), &problem))
+            .or(self.language.as_deref())
+            .with_context(|| "`language` was not detected. Specify with `--language`")?
             .to_owned();
 
+        let variant = variant.unwrap_or_default().to_owned();
+
         let target = Target {
             service,
             contest,
             problem,
+            variant,
             mode,
         };
 
@@ -250,12 +751,148 @@ in  {{ service = config.detectServiceFromRelativePathSegments relativePathSegmen
     }
 }
 
+/// Languages pinned to problems with `lang set`.
+///
+/// Stored in `.snowchains/languages.yml` as `{service: {contest: {problem: language}}}`. They take
+/// precedence over `detectLanguageFromRelativePathSegments` but not over `--language`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub(crate) struct PinnedLanguages(BTreeMap<String, BTreeMap<String, BTreeMap<String, String>>>);
+
+impl PinnedLanguages {
+    pub(crate) fn load(workspace: &Path) -> anyhow::Result<Self> {
+        let path = Self::path(workspace);
+        if path.exists() {
+            crate::fs::read_yaml(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub(crate) fn save(&self, workspace: &Path) -> anyhow::Result<PathBuf> {
+        let path = Self::path(workspace);
+        crate::fs::write(&path, serde_yaml::to_string(self)?, true)?;
+        Ok(path)
+    }
+
+    pub(crate) fn set(
+        &mut self,
+        service: PlatformKind,
+        contest: Option<&str>,
+        problem: &str,
+        language: &str,
+    ) {
+        self.0
+            .entry(service.to_kebab_case_str().to_owned())
+            .or_default()
+            .entry(contest.unwrap_or("").to_owned())
+            .or_default()
+            .insert(problem.to_owned(), language.to_owned());
+    }
+
+    fn get(&self, service: PlatformKind, contest: Option<&str>, problem: &str) -> Option<&str> {
+        self.0
+            .get(service.to_kebab_case_str())?
+            .get(contest.unwrap_or(""))?
+            .get(problem)
+            .map(|s| &**s)
+    }
+
+    fn path(workspace: &Path) -> PathBuf {
+        workspace.join(".snowchains").join("languages.yml")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PathStyle {
+    /// `C:\...`, `C:/...`, or `\\server\share`
+    Windows,
+    /// `/...`
+    Posix,
+}
+
+fn path_style(s: &str) -> Option<PathStyle> {
+    let bs = s.as_bytes();
+    if bs.len() >= 3
+        && bs[0].is_ascii_alphabetic()
+        && bs[1] == b':'
+        && matches!(bs[2], b'\\' | b'/')
+        || s.starts_with(r"\\")
+    {
+        Some(PathStyle::Windows)
+    } else if s.starts_with('/') {
+        Some(PathStyle::Posix)
+    } else {
+        None
+    }
+}
+
+/// Converts `C:\foo\bar` into `/mnt/c/foo/bar` assuming the default mount point.
+fn wslpath_u(s: &str) -> Option<String> {
+    if path_style(s) != Some(PathStyle::Windows) || s.starts_with(r"\\") {
+        return None;
+    }
+    let drive = s[..1].to_ascii_lowercase();
+    Some(format!("/mnt/{}{}", drive, s[2..].replace('\\', "/")))
+}
+
+/// Whether this process runs on WSL.
+pub(crate) fn is_wsl() -> bool {
+    cfg!(target_os = "linux")
+        && (std::env::var_os("WSL_DISTRO_NAME").is_some()
+            || matches!(
+                std::fs::read_to_string("/proc/sys/kernel/osrelease"),
+                Ok(release) if release.to_lowercase().contains("microsoft")
+            ))
+}
+
 #[derive(Debug, Deserialize, StaticType, Clone)]
 pub(crate) enum Command {
     Args(Vec<String>),
     Script(Script),
 }
 
+impl Command {
+    /// Arguments and the program of a script, named like `run[0]` or `run.program`.
+    fn paths(&self, name: &str) -> Vec<(String, &str)> {
+        match self {
+            Self::Args(args) => args
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| (format!("{}[{}]", name, i), &**arg))
+                .collect(),
+            Self::Script(Script { program, .. }) => vec![(format!("{}.program", name), program)],
+        }
+    }
+
+    /// The program to execute, named like `run[0]` or `run.program`.
+    fn program(&self, name: &str) -> Option<(String, &str)> {
+        self.paths(name).into_iter().next()
+    }
+
+    /// Arguments, or the program and the content of a script.
+    fn texts_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        let (args, script) = match self {
+            Self::Args(args) => (&mut **args, None),
+            Self::Script(Script {
+                program, content, ..
+            }) => (
+                &mut [][..],
+                Some(iter::once(program).chain(iter::once(content))),
+            ),
+        };
+        args.iter_mut().chain(script.into_iter().flatten())
+    }
+
+    fn paths_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        let (args, program) = match self {
+            Self::Args(args) => (&mut **args, None),
+            Self::Script(Script { program, .. }) => (&mut [][..], Some(program)),
+        };
+        args.iter_mut().chain(program)
+    }
+}
+
 #[derive(Debug, Deserialize, StaticType, Clone)]
 pub(crate) struct Script {
     pub(crate) program: String,
@@ -273,23 +910,253 @@ pub(crate) struct Language {
     pub(crate) languageId: Option<String>,
 }
 
-#[derive(Debug, Deserialize, StaticType)]
+/// `judge` in the config, except `extraTestDirs`.
+#[allow(non_snake_case)] // for `StaticType`
+#[derive(Debug, Deserialize, StaticType, Clone, Copy)]
+pub(crate) struct JudgeOptions {
+    /// Multiplies the timelimits of the test suites.
+    pub(crate) timelimitScale: f64,
+    /// Prints each input to stderr before running.
+    pub(crate) stdinEcho: bool,
+    /// Sets the `SNOWCHAINS_*` variables for the solver.
+    pub(crate) exportEnv: bool,
+    /// Sets the UTF-8 locale variables for the solver.
+    pub(crate) utf8Env: bool,
+}
+
+impl Language {
+    /// Warnings about paths that cannot work on this machine.
+    ///
+    /// Mixing drive-letter paths and POSIX absolute paths is always suspicious. On WSL, a
+    /// drive-letter path alone is also reported since Linux processes cannot open it.
+    pub(crate) fn path_style_warnings(&self, wsl: bool) -> Vec<String> {
+        let paths = self.paths();
+
+        let first = |style| {
+            paths
+                .iter()
+                .find(|(_, path)| path_style(path) == Some(style))
+        };
+
+        match (first(PathStyle::Windows), first(PathStyle::Posix)) {
+            (Some((win_field, win_path)), Some((posix_field, posix_path))) => vec![format!(
+                "`{}` (`{}`) is a Windows path but `{}` (`{}`) is a POSIX path. Convert one of \
+                 them with `wslpath`{}",
+                win_field,
+                win_path,
+                posix_field,
+                posix_path,
+                if wsl {
+                    " or set `paths.autoWslpath = True`"
+                } else {
+                    ""
+                },
+            )],
+            (Some((field, path)), None) if wsl => vec![format!(
+                "`{}` (`{}`) is a Windows path. Use `{}` (`wslpath -u`) or set \
+                 `paths.autoWslpath = True`",
+                field,
+                path,
+                wslpath_u(path).as_deref().unwrap_or("/mnt/..."),
+            )],
+            _ => vec![],
+        }
+    }
+
+    /// Replaces `{variant}` in `src` and the `output`s with `_{variant}`, or with nothing for the
+    /// empty variant.
+    ///
+    /// The commands usually contain these paths, so the occurrences of them are replaced too. Other
+    /// `{variant}`s in the commands are left as they are.
+    pub(crate) fn expand_variant(&mut self, variant: &str) {
+        let expanded = |path: &str| {
+            let variant = if variant.is_empty() {
+                "".to_owned()
+            } else {
+                format!("_{}", variant)
+            };
+            path.replace("{variant}", &variant)
+        };
+
+        let mut replacements = vec![];
+        let outputs = self
+            .transpile
+            .iter_mut()
+            .chain(&mut self.compile)
+            .map(|Compile { output, .. }| output);
+        for path in iter::once(&mut self.src).chain(outputs) {
+            if path.contains("{variant}") {
+                let new = expanded(path);
+                replacements.push((mem::replace(path, new.clone()), new));
+            }
+        }
+
+        // `a{variant}` may be a part of `target/a{variant}`.
+        replacements.sort_by_key(|(old, _)| cmp::Reverse(old.len()));
+
+        let commands = self
+            .transpile
+            .iter_mut()
+            .chain(&mut self.compile)
+            .map(|Compile { command, .. }| command)
+            .chain(iter::once(&mut self.run));
+        for command in commands {
+            for s in command.texts_mut() {
+                for (old, new) in &replacements {
+                    *s = s.replace(&**old, new);
+                }
+            }
+        }
+    }
+
+    /// Rewrites the drive-letter paths as `wslpath -u` does.
+    pub(crate) fn convert_windows_paths(&mut self) {
+        let convert = |path: &mut String| {
+            if let Some(converted) = wslpath_u(path) {
+                *path = converted;
+            }
+        };
+
+        convert(&mut self.src);
+        for compile in self.transpile.iter_mut().chain(&mut self.compile) {
+            convert(&mut compile.output);
+            compile.command.paths_mut().for_each(convert);
+        }
+        self.run.paths_mut().for_each(convert);
+    }
+
+    /// The programs of `transpile`, `compile`, and `run`.
+    fn programs(&self) -> Vec<(String, &str)> {
+        let mut programs = vec![];
+        for (name, compile) in &[("transpile", &self.transpile), ("compile", &self.compile)] {
+            if let Some(Compile { command, .. }) = compile {
+                programs.extend(command.program(&format!("{}.command", name)));
+            }
+        }
+        programs.extend(self.run.program("run"));
+        programs
+    }
+
+    fn paths(&self) -> Vec<(String, &str)> {
+        let mut paths = vec![("src".to_owned(), &*self.src)];
+        for (name, compile) in &[("transpile", &self.transpile), ("compile", &self.compile)] {
+            if let Some(Compile { command, output }) = compile {
+                paths.push((format!("{}.output", name), output));
+                paths.extend(command.paths(&format!("{}.command", name)));
+            }
+        }
+        paths.extend(self.run.paths("run"));
+        paths
+    }
+}
+
+#[derive(Debug, Deserialize, StaticType, Clone)]
 pub(crate) struct Compile {
     pub(crate) command: Command,
     pub(crate) output: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct Webhook {
+    pub(crate) url: String,
+    pub(crate) events: Vec<WebhookEvent>,
+    pub(crate) format: WebhookFormat,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum WebhookEvent {
+    Submit,
+    Judge,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum WebhookFormat {
+    Discord,
+    Slack,
+    Json,
+}
+
+/// How problems are named locally: test files, source files, and problem arguments.
+#[derive(Debug, Deserialize, strum::Display, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ProblemNaming {
+    /// `a`, `b`, `c`, ...
+    Letters,
+    /// `p01`, `p02`, `p03`, ... for letter indexes in a contest.
+    Numbers,
+    /// Sanitized indexes such as `1-a` for `1_A`.
+    Slug,
+}
+
+impl ProblemNaming {
+    pub(crate) const ALL: &'static [Self] = &[Self::Letters, Self::Numbers, Self::Slug];
+
+    pub(crate) fn local_name(self, contest: Option<&str>, index: &str) -> String {
+        match self {
+            Self::Letters => index.to_kebab_case(),
+            Self::Numbers => match (contest, letter_position(index)) {
+                (Some(_), Some(pos)) => format!("p{:02}", pos),
+                _ => Self::Slug.local_name(contest, index),
+            },
+            Self::Slug => index
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|s| !s.is_empty())
+                .map(str::to_lowercase)
+                .join("-"),
+        }
+    }
+
+    /// Inverse of [`local_name`]. The services compare indexes case-insensitively.
+    ///
+    /// [`local_name`]: #method.local_name
+    pub(crate) fn remote_index(self, contest: Option<&str>, local_name: &str) -> String {
+        let pos = local_name
+            .strip_prefix('p')
+            .filter(|s| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|s| s.parse::<usize>().ok());
+
+        match (self, contest, pos) {
+            (Self::Numbers, Some(_), Some(pos)) => {
+                match pos.checked_sub(1).and_then(|i| (b'A'..=b'Z').nth(i)) {
+                    Some(letter) => char::from(letter).to_string(),
+                    None => local_name.to_owned(),
+                }
+            }
+            _ => local_name.to_owned(),
+        }
+    }
+}
+
+/// 1-based position of a single-letter index.
+fn letter_position(index: &str) -> Option<usize> {
+    match *index.as_bytes() {
+        [c] if c.is_ascii_alphabetic() => Some(usize::from(c.to_ascii_uppercase() - b'A') + 1),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct Target {
     pub(crate) service: PlatformKind,
     pub(crate) contest: Option<String>,
     pub(crate) problem: String,
+    /// Expanded into `{variant}` in `src` and the `output`s. Empty by default.
+    pub(crate) variant: String,
     pub(crate) mode: Mode,
 }
 
 impl Target {
-    fn to_dhall_expr(&self) -> String {
-        format!(
+    fn to_dhall_expr(&self) -> anyhow::Result<String> {
+        if self.service == PlatformKind::Aoj {
+            bail!(
+                "AOJ is not a `Service` in the config schema. Only `retrieve testcases` supports it",
+            );
+        }
+
+        Ok(format!(
             r"let Service = < Atcoder | Codeforces | Yukicoder >
 
 let CaseConvertedText =
@@ -340,7 +1207,7 @@ in  {{ service = Service.{}
                 Mode::Debug => "Debug",
                 Mode::Release => "Release",
             },
-        )
+        ))
     }
 }
 
@@ -349,3 +1216,368 @@ pub(crate) enum Mode {
     Debug,
     Release,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Command, Compile, ConfigKey, DefaultProblems, DefaultProblemsEntry, Detected, JudgeOptions,
+        Language, Mode, PathStyle, PinnedLanguages, ProblemNaming, Script,
+    };
+    use snowchains_core::web::PlatformKind;
+    use std::{env, fs};
+
+    #[test]
+    fn all_keys() {
+        let schema = super::SCHEMA;
+        let config = &schema[schema.find("let Config =").unwrap()..];
+        let config = &config[..config.find("\n\n").unwrap()];
+
+        let in_schema = config
+            .lines()
+            .flat_map(|line| {
+                let line = line.trim_start();
+                let line = line
+                    .strip_prefix("{ ")
+                    .or_else(|| line.strip_prefix(", "))?;
+                line.split(" : ").next()
+            })
+            .collect::<Vec<_>>();
+
+        let required = super::ALL_KEYS
+            .iter()
+            .filter(|ConfigKey { default, .. }| default.is_none())
+            .map(|ConfigKey { key, .. }| *key)
+            .collect::<Vec<_>>();
+
+        assert_eq!(in_schema, required);
+    }
+
+    #[test]
+    fn pinned_languages() -> anyhow::Result<()> {
+        let detected = Detected {
+            service: Some("atcoder".to_owned()),
+            contest: Some("abc100".to_owned()),
+            problem: Some("a".to_owned()),
+            language: Some("rust".to_owned()),
+        };
+
+        let mut pinned = PinnedLanguages::default();
+        pinned.set(PlatformKind::Atcoder, Some("abc100"), "a", "python");
+
+        let language = |cli_opt_language, problem| -> anyhow::Result<_> {
+            let merged = detected.merge_with_cli_options(
+                None,
+                None,
+                Some(problem),
+                cli_opt_language,
+                None,
+                Mode::Debug,
+                &pinned,
+            )?;
+            Ok(merged.1)
+        };
+
+        assert_eq!("python", language(None, "a")?);
+        assert_eq!("cpp", language(Some("cpp"), "a")?);
+        assert_eq!("rust", language(None, "b")?);
+        Ok(())
+    }
+
+    #[test]
+    fn path_style() {
+        assert_eq!(
+            Some(PathStyle::Windows),
+            super::path_style(r"C:\Users\a\a.exe")
+        );
+        assert_eq!(Some(PathStyle::Windows), super::path_style("d:/a.exe"));
+        assert_eq!(
+            Some(PathStyle::Windows),
+            super::path_style(r"\\server\share")
+        );
+        assert_eq!(Some(PathStyle::Posix), super::path_style("/mnt/c/Users/a"));
+        assert_eq!(None, super::path_style("C:a.exe"));
+        assert_eq!(None, super::path_style("-o"));
+        assert_eq!(None, super::path_style("./a.out"));
+    }
+
+    #[test]
+    fn wslpath_u() {
+        assert_eq!(
+            Some("/mnt/c/Users/a b/a.exe"),
+            super::wslpath_u(r"C:\Users\a b\a.exe").as_deref(),
+        );
+        assert_eq!(
+            Some("/mnt/d/a.exe"),
+            super::wslpath_u("D:/a.exe").as_deref()
+        );
+        assert_eq!(None, super::wslpath_u(r"\\server\share"));
+        assert_eq!(None, super::wslpath_u("/usr/bin/g++"));
+    }
+
+    #[test]
+    fn default_problems() {
+        let default_problems = || DefaultProblems {
+            default: vec!["a".to_owned(), "b".to_owned()],
+            contests: vec![
+                DefaultProblemsEntry {
+                    map_key: "abc042".to_owned(),
+                    map_value: vec!["c".to_owned()],
+                },
+                DefaultProblemsEntry {
+                    map_key: "abc043".to_owned(),
+                    map_value: vec![],
+                },
+            ],
+        };
+
+        let for_contest = |contest| default_problems().for_contest(contest);
+
+        assert_eq!(
+            Some(vec!["a".to_owned(), "b".to_owned()]),
+            for_contest(None)
+        );
+        assert_eq!(
+            Some(vec!["a".to_owned(), "b".to_owned()]),
+            for_contest(Some("abc041")),
+        );
+        assert_eq!(Some(vec!["c".to_owned()]), for_contest(Some("ABC042")));
+        assert_eq!(None, for_contest(Some("abc043")));
+    }
+
+    #[test]
+    fn problem_naming() {
+        let contest = Some("no-1234");
+
+        assert_eq!("a", ProblemNaming::Letters.local_name(contest, "A"));
+        assert_eq!("p01", ProblemNaming::Numbers.local_name(contest, "A"));
+        assert_eq!("p12", ProblemNaming::Numbers.local_name(contest, "l"));
+        assert_eq!("1-a", ProblemNaming::Numbers.local_name(contest, "1_A"));
+        assert_eq!("1234", ProblemNaming::Numbers.local_name(None, "1234"));
+        assert_eq!("1-a", ProblemNaming::Slug.local_name(contest, "1_A"));
+        assert_eq!("ex", ProblemNaming::Slug.local_name(contest, "Ex"));
+
+        assert_eq!("A", ProblemNaming::Numbers.remote_index(contest, "p01"));
+        assert_eq!("L", ProblemNaming::Numbers.remote_index(contest, "p12"));
+        assert_eq!("p00", ProblemNaming::Numbers.remote_index(contest, "p00"));
+        assert_eq!("p27", ProblemNaming::Numbers.remote_index(contest, "p27"));
+        assert_eq!("p01", ProblemNaming::Numbers.remote_index(None, "p01"));
+        assert_eq!("p01", ProblemNaming::Letters.remote_index(contest, "p01"));
+    }
+
+    #[test]
+    fn path_style_warnings() {
+        let language = |output: &str, run: &str| Language {
+            src: "a.cpp".to_owned(),
+            transpile: None,
+            compile: Some(Compile {
+                command: Command::Args(vec!["g++".to_owned(), "-o".to_owned(), output.to_owned()]),
+                output: output.to_owned(),
+            }),
+            run: Command::Args(vec![run.to_owned()]),
+            languageId: None,
+        };
+
+        let mixed = language(r"C:\a\a.exe", "/mnt/c/a/a.exe");
+        let warnings = mixed.path_style_warnings(false);
+        assert_eq!(1, warnings.len());
+        assert!(warnings[0].contains(r"`compile.output` (`C:\a\a.exe`)"));
+        assert!(warnings[0].contains("`run[0]` (`/mnt/c/a/a.exe`)"));
+
+        let posix = language("/tmp/a.out", "/tmp/a.out");
+        assert!(posix.path_style_warnings(false).is_empty());
+        assert!(posix.path_style_warnings(true).is_empty());
+
+        let windows = language(r"C:\a\a.exe", r"C:\a\a.exe");
+        assert!(windows.path_style_warnings(false).is_empty());
+        assert!(windows.path_style_warnings(true)[0].contains("`/mnt/c/a/a.exe`"));
+
+        let mut converted = mixed;
+        converted.convert_windows_paths();
+        assert!(converted.path_style_warnings(true).is_empty());
+        assert_eq!("/mnt/c/a/a.exe", converted.compile.unwrap().output);
+    }
+
+    #[test]
+    fn expand_variant() {
+        let language = || Language {
+            src: "cpp/a{variant}.cpp".to_owned(),
+            transpile: None,
+            compile: Some(Compile {
+                command: Command::Args(vec![
+                    "g++".to_owned(),
+                    "cpp/a{variant}.cpp".to_owned(),
+                    "-o".to_owned(),
+                    "cpp/target/a{variant}".to_owned(),
+                    "-D{variant}".to_owned(),
+                ]),
+                output: "cpp/target/a{variant}".to_owned(),
+            }),
+            run: Command::Script(Script {
+                program: "bash".to_owned(),
+                extension: "bash".to_owned(),
+                content: "exec ./cpp/target/a{variant}\n".to_owned(),
+            }),
+            languageId: None,
+        };
+
+        let args = |language: &Language| match &language.compile.as_ref().unwrap().command {
+            Command::Args(args) => args.clone(),
+            Command::Script(_) => unreachable!(),
+        };
+        let content = |language: &Language| match &language.run {
+            Command::Script(Script { content, .. }) => content.clone(),
+            Command::Args(_) => unreachable!(),
+        };
+
+        let mut main = language();
+        main.expand_variant("");
+        assert_eq!("cpp/a.cpp", main.src);
+        assert_eq!("cpp/target/a", main.compile.as_ref().unwrap().output);
+        assert_eq!(
+            ["g++", "cpp/a.cpp", "-o", "cpp/target/a", "-D{variant}"],
+            *args(&main),
+        );
+        assert_eq!("exec ./cpp/target/a\n", content(&main));
+
+        let mut slow = language();
+        slow.expand_variant("slow");
+        assert_eq!("cpp/a_slow.cpp", slow.src);
+        assert_eq!("cpp/target/a_slow", slow.compile.as_ref().unwrap().output);
+        assert_eq!(
+            [
+                "g++",
+                "cpp/a_slow.cpp",
+                "-o",
+                "cpp/target/a_slow",
+                "-D{variant}"
+            ],
+            *args(&slow),
+        );
+        assert_eq!("exec ./cpp/target/a_slow\n", content(&slow));
+
+        let mut without_placeholder = language();
+        without_placeholder.src = "cpp/a.cpp".to_owned();
+        without_placeholder.expand_variant("slow");
+        assert_eq!("cpp/a.cpp", without_placeholder.src);
+    }
+
+    #[test]
+    fn variant_without_placeholder() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let src = |src: &str| -> anyhow::Result<_> {
+            fs::write(
+                dir.path().join("snowchains.dhall"),
+                format!(
+                    r#"{{ detectServiceFromRelativePathSegments = λ(_ : List Text) → Some "atcoder"
+, detectContestFromRelativePathSegments = λ(_ : List Text) → Some "abc100"
+, detectProblemFromRelativePathSegments = λ(_ : List Text) → Some "a"
+, detectLanguageFromRelativePathSegments = λ(_ : List Text) → Some "python"
+, languages =
+    λ(_ : {})
+    → toMap
+        {{ python =
+          {{ src = "{}"
+          , run = < Args : List Text | Script : {{ program : Text, extension : Text, content : Text }} >.Args
+              [ "python3" ]
+          }}
+        }}
+}}"#,
+                    super::TARGET_TYPE,
+                    src,
+                ),
+            )?;
+
+            let (_, language, _) = super::target_and_language(
+                dir.path(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("slow"),
+                Mode::Debug,
+            )?;
+            Ok(language.src)
+        };
+
+        assert_eq!("a_slow.py", src("a{variant}.py")?);
+        let err = src("a.py").unwrap_err().to_string();
+        assert!(err.contains("has no `{variant}`"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn judge_options() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let judge_options = |config: &str| -> anyhow::Result<_> {
+            fs::write(dir.path().join("snowchains.dhall"), config)?;
+            super::judge_options(dir.path(), None)
+        };
+
+        let JudgeOptions {
+            timelimitScale,
+            stdinEcho,
+            exportEnv,
+            utf8Env,
+        } = judge_options("{=}")?;
+        assert_eq!(
+            (1.0, false, true, true),
+            (timelimitScale, stdinEcho, exportEnv, utf8Env)
+        );
+
+        let JudgeOptions {
+            timelimitScale,
+            stdinEcho,
+            exportEnv,
+            utf8Env,
+        } = judge_options("{ judge = { timelimitScale = 1.5, utf8Env = False } }")?;
+        assert_eq!(
+            (1.5, false, true, false),
+            (timelimitScale, stdinEcho, exportEnv, utf8Env)
+        );
+
+        assert!(judge_options("{ judge.timelimitScale = 0.0 }").is_err());
+        assert!(judge_options("{ judge.timelimitScale = 2 }").is_err());
+
+        let auto_wslpath = |config: &str| -> anyhow::Result<_> {
+            let path = dir.path().join("snowchains.dhall");
+            fs::write(&path, config)?;
+            super::auto_wslpath(path.to_str().unwrap())
+        };
+
+        assert!(!auto_wslpath("{=}")?);
+        assert!(auto_wslpath("{ paths.autoWslpath = True }")?);
+        Ok(())
+    }
+
+    #[test]
+    fn is_in_path() -> anyhow::Result<()> {
+        let (dir1, dir2) = (tempfile::tempdir()?, tempfile::tempdir()?);
+        fs::write(dir2.path().join("solver"), "")?;
+        fs::create_dir(dir1.path().join("dir"))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+
+            fs::set_permissions(
+                dir2.path().join("solver"),
+                fs::Permissions::from_mode(0o755),
+            )?;
+            fs::write(dir2.path().join("data"), "")?;
+            fs::set_permissions(dir2.path().join("data"), fs::Permissions::from_mode(0o644))?;
+        }
+
+        let env_path = env::join_paths([dir1.path(), dir2.path()])?;
+
+        assert!(super::is_in_path("solver", &env_path));
+        assert!(!super::is_in_path("dir", &env_path));
+        assert!(!super::is_in_path("missing", &env_path));
+        assert!(!super::is_in_path("solver", "".as_ref()));
+        #[cfg(unix)]
+        assert!(!super::is_in_path("data", &env_path));
+        Ok(())
+    }
+}
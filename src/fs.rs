@@ -1,16 +1,39 @@
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
 use serde::{de::DeserializeOwned, Serialize};
-use std::{fs::Metadata, path::Path};
+use std::{
+    borrow::Cow,
+    fs::{Metadata, OpenOptions},
+    io::{self, Write as _},
+    path::{Component, Path},
+};
+
+/// Maximum length of a file name on common file systems.
+const MAX_FILE_NAME_LEN: usize = 255;
+
+/// `MAX_PATH` on Windows, which applies to paths without the `\\?\` prefix.
+const WINDOWS_MAX_PATH: usize = 260;
+
+#[cfg(windows)]
+const MAX_PATH_LEN: usize = 32_767;
+
+#[cfg(not(windows))]
+const MAX_PATH_LEN: usize = 4095;
 
 pub(crate) fn metadata(path: impl AsRef<Path>) -> anyhow::Result<Metadata> {
     let path = path.as_ref();
-    std::fs::metadata(path)
+    std::fs::metadata(extended(path))
         .with_context(|| format!("Could not get the metadata of `{}`", path.display()))
 }
 
+pub(crate) fn read(path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
+    let path = path.as_ref();
+    std::fs::read(extended(path)).with_context(|| format!("Could not read `{}`", path.display()))
+}
+
 pub(crate) fn read_to_string(path: impl AsRef<Path>) -> anyhow::Result<String> {
     let path = path.as_ref();
-    std::fs::read_to_string(path).with_context(|| format!("Could not read `{}`", path.display()))
+    std::fs::read_to_string(extended(path))
+        .with_context(|| format!("Could not read `{}`", path.display()))
 }
 
 pub(crate) fn read_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow::Result<T> {
@@ -38,7 +61,7 @@ pub(crate) fn write(
         }
     }
 
-    std::fs::write(&path, contents)
+    std::fs::write(extended(path.as_ref()), contents)
         .with_context(|| format!("Could not write `{}`", path.as_ref().display()))
 }
 
@@ -50,7 +73,210 @@ pub(crate) fn write_json(
     write(path, serde_json::to_string(&value)?, create_dir_all)
 }
 
+/// Writes a file that contains secrets such as tokens, so that only the owner can read it.
+pub(crate) fn write_secret(
+    path: impl AsRef<Path>,
+    contents: impl AsRef<[u8]>,
+    create_dir_all: bool,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+
+    if create_dir_all {
+        if let Some(parent) = path.parent() {
+            self::create_dir_all(parent)?;
+        }
+    }
+
+    (|| -> io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(extended(path))?;
+        file.write_all(contents.as_ref())
+    })()
+    .with_context(|| format!("Could not write `{}`", path.display()))?;
+
+    restrict_permissions(path)
+}
+
+pub(crate) fn write_json_secret(
+    path: impl AsRef<Path>,
+    value: impl Serialize,
+    create_dir_all: bool,
+) -> anyhow::Result<()> {
+    write_secret(path, serde_json::to_string(&value)?, create_dir_all)
+}
+
+/// Makes `path` readable and writable only by the owner.
+///
+/// On Windows, this is a best-effort attempt with `icacls` and its failure is ignored.
+pub(crate) fn restrict_permissions(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let path = path.as_ref();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Could not restrict the permissions of `{}`", path.display()))
+    }
+
+    #[cfg(windows)]
+    {
+        if let Some(username) = std::env::var_os("USERNAME") {
+            let mut grant = username;
+            grant.push(":F");
+            let _ = std::process::Command::new("icacls")
+                .arg(extended(path).as_os_str())
+                .args(&["/inheritance:r", "/grant:r"])
+                .arg(grant)
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status();
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = path;
+        Ok(())
+    }
+}
+
 pub(crate) fn create_dir_all(path: impl AsRef<Path>) -> anyhow::Result<()> {
-    std::fs::create_dir_all(&path)
+    std::fs::create_dir_all(extended(path.as_ref()))
         .with_context(|| format!("Could not create `{}`", path.as_ref().display()))
 }
+
+pub(crate) fn remove_file(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    std::fs::remove_file(extended(path.as_ref()))
+        .with_context(|| format!("Could not remove `{}`", path.as_ref().display()))
+}
+
+pub(crate) fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> anyhow::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    std::fs::rename(extended(from), extended(to))
+        .with_context(|| format!("Could not move `{}` to `{}`", from.display(), to.display(),))
+}
+
+/// Returns the free space of the file system that `path` is (or would be) on.
+pub(crate) fn available_space(path: impl AsRef<Path>) -> anyhow::Result<u64> {
+    let path = path.as_ref();
+    let existing = path.ancestors().find(|p| p.exists()).with_context(|| {
+        format!(
+            "Could not find any existing ancestor of `{}`",
+            path.display()
+        )
+    })?;
+    fs2::available_space(existing).with_context(|| {
+        format!(
+            "Could not get the available space for `{}`",
+            existing.display(),
+        )
+    })
+}
+
+/// Fails if `path` can not be created on this platform.
+pub(crate) fn ensure_path_len(path: &Path) -> anyhow::Result<()> {
+    for component in path.components() {
+        if let Component::Normal(name) = component {
+            if name.len() > MAX_FILE_NAME_LEN {
+                bail!(
+                    "The file name `{}` is longer than {} bytes",
+                    name.to_string_lossy(),
+                    MAX_FILE_NAME_LEN,
+                );
+            }
+        }
+    }
+
+    let extended = extended(path);
+    let len = extended.as_os_str().len();
+
+    if len > MAX_PATH_LEN {
+        bail!(
+            "`{}` is longer than {} bytes. Move the workspace to a shorter path",
+            path.display(),
+            MAX_PATH_LEN,
+        );
+    }
+    if cfg!(windows) && matches!(extended, Cow::Borrowed(_)) && len >= WINDOWS_MAX_PATH {
+        bail!(
+            "`{}` is longer than `MAX_PATH` ({}) and could not be prefixed with `\\\\?\\`. \
+             Use an absolute path on a local drive",
+            path.display(),
+            WINDOWS_MAX_PATH,
+        );
+    }
+    Ok(())
+}
+
+/// On Windows, prefixes a long absolute path with `\\?\` so that it is not limited by `MAX_PATH`.
+#[cfg(windows)]
+fn extended(path: &Path) -> Cow<'_, Path> {
+    use std::{ffi::OsString, path::Prefix};
+
+    if path.as_os_str().len() < WINDOWS_MAX_PATH {
+        return Cow::Borrowed(path);
+    }
+
+    let mut components = path.components();
+
+    let prefix = match components.next() {
+        Some(Component::Prefix(prefix)) if matches!(prefix.kind(), Prefix::Disk(_)) => prefix,
+        _ => return Cow::Borrowed(path),
+    };
+
+    let mut extended = OsString::from(r"\\?\");
+    extended.push(prefix.as_os_str());
+
+    for component in components {
+        match component {
+            Component::RootDir => {}
+            Component::CurDir => continue,
+            Component::Normal(name) => {
+                extended.push(r"\");
+                extended.push(name);
+            }
+            // `\\?\` disables the normalization.
+            Component::Prefix(_) | Component::ParentDir => return Cow::Borrowed(path),
+        }
+    }
+
+    Cow::Owned(extended.into())
+}
+
+#[cfg(not(windows))]
+fn extended(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    #[test]
+    fn write_secret() -> anyhow::Result<()> {
+        let dir = tempfile::Builder::new()
+            .prefix("snowchains-fs-write-secret-")
+            .tempdir()?;
+        let path = dir.path().join("tokens").join("yukicoder.json");
+
+        super::write_secret(&path, "\"key\"", true)?;
+        assert_eq!(
+            std::fs::metadata(&path)?.permissions().mode() & 0o777,
+            0o600
+        );
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+        super::write_json_secret(&path, "key2", false)?;
+        assert_eq!(
+            std::fs::metadata(&path)?.permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(std::fs::read_to_string(&path)?, "\"key2\"");
+        Ok(())
+    }
+}
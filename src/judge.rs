@@ -1,22 +1,35 @@
 use crate::config;
-use anyhow::bail;
+use anyhow::{bail, ensure, Context as _};
 use az::SaturatingAs as _;
 use human_size::{Byte, Size};
-use indicatif::ProgressDrawTarget;
+use indicatif::{ProgressBar, ProgressDrawTarget};
 use itertools::Itertools as _;
 use maplit::btreemap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest as _, Sha256};
 use snowchains_core::{
-    color_spec, judge::CommandExpression, testsuite::TestSuite, web::PlatformKind,
+    color_spec,
+    judge::{CommandExpression, JudgeSummary, Verdict},
+    testsuite::{
+        Additional, BatchTestCase, BatchTestSuite, PartialBatchTestCase, ScoringTestSuite,
+        TestCaseOrigin, TestCaseSource, TestSuite, TextSource,
+    },
+    web::PlatformKind,
 };
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, BTreeSet, HashSet},
     ffi::OsStr,
-    io::Write as _,
+    fs::File,
+    io::{self, Write as _},
     iter, mem,
     ops::Deref,
     path::{Path, PathBuf},
-    process::Stdio,
+    process::{ExitStatus, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use tempfile::NamedTempFile;
 use termcolor::{Color, WriteColor};
 
 pub(crate) struct Args<W1, W2> {
@@ -30,17 +43,34 @@ pub(crate) struct Args<W1, W2> {
     pub(crate) service: PlatformKind,
     pub(crate) contest: Option<String>,
     pub(crate) problem: String,
+    pub(crate) variant: String,
     pub(crate) src: String,
     pub(crate) transpile: Option<config::Compile>,
     pub(crate) compile: Option<config::Compile>,
     pub(crate) run: config::Command,
     pub(crate) test_case_names: Option<HashSet<String>>,
+    pub(crate) origin: Option<TestCaseOrigin>,
+    pub(crate) ignore_trailing_whitespace: bool,
+    /// `judge.timelimitScale`, which is already validated.
+    pub(crate) timelimit_scale: f64,
+    pub(crate) stdin_echo: bool,
+    pub(crate) export_env: bool,
+    pub(crate) utf8_env: bool,
     pub(crate) display_limit: Size,
+    pub(crate) record: bool,
+    pub(crate) webhook: Option<config::Webhook>,
+    /// `judge.extraTestDirs`, relative to `base_dir`.
+    pub(crate) extra_test_dirs: Vec<String>,
+    pub(crate) skip_compile: bool,
+    pub(crate) force_compile: bool,
+    pub(crate) diff: bool,
+    /// Prints [`json_summary`] to `stdout`, and the human-readable result to `stderr`.
+    pub(crate) json_output: bool,
 }
 
 pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Result<()> {
     let Args {
-        stdout,
+        mut stdout,
         mut stderr,
         stdin_process_redirection,
         stdout_process_redirection,
@@ -50,33 +80,167 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
         service,
         contest,
         problem,
+        variant,
         src,
         transpile,
         compile,
         run,
         test_case_names,
+        origin,
+        ignore_trailing_whitespace,
+        timelimit_scale,
+        stdin_echo,
+        export_env,
+        utf8_env,
         display_limit,
+        record,
+        webhook,
+        extra_test_dirs,
+        skip_compile,
+        force_compile,
+        diff,
+        json_output,
     } = args;
 
-    let test_suite_dir = base_dir
-        .join(".snowchains")
-        .join("tests")
-        .join(service.to_kebab_case_str())
-        .join(contest.as_deref().unwrap_or(""));
-    let test_suite_path = test_suite_dir.join(problem).with_extension("yml");
-
-    let test_cases = match crate::fs::read_yaml(&test_suite_path)? {
-        TestSuite::Batch(test_sutie) => {
-            test_sutie.load_test_cases(&test_suite_dir, test_case_names, |_| {
-                unimplemented!("`SystemTestCases` is not impelemented");
-            })?
+    // Hashed before building so that edits during the judge are not recorded as passed.
+    // The raw bytes, so that a source file in Shift_JIS or such can be judged.
+    let src_sha256 = sha256_hex(crate::fs::read(base_dir.join(&src))?);
+    // With `--skip-compile`, the binary may not be built from the current source.
+    let history_dir =
+        (test_case_names.is_none() && origin.is_none() && !skip_compile).then(|| base_dir.clone());
+    let run_log_dir = record.then(|| base_dir.clone());
+
+    let test_suite_path = test_suite_dir(&base_dir, service, contest.as_deref())
+        .join(&problem)
+        .with_extension("yml");
+    let test_suite_path = crate::suite_link::resolve(&test_suite_path)?;
+    let test_suite_dir = parent_dir(&test_suite_path);
+
+    let (mut test_cases, mut scoring) = match crate::fs::read_yaml(&test_suite_path)? {
+        TestSuite::Batch(mut test_sutie) => {
+            test_sutie.resolve_checker(&base_dir)?;
+
+            let extra = extra_test_dirs
+                .iter()
+                .map(|dir| {
+                    let path = base_dir.join(dir);
+                    let path = path
+                        .to_str()
+                        .with_context(|| format!("Non UTF-8 path: {:?}", path))?;
+                    Ok(Additional::Text {
+                        path: path.into(),
+                        r#in: "/in/*.txt".to_owned(),
+                        out: "/out/*.txt".to_owned(),
+                        timelimit: None,
+                        r#match: None,
+                        origin: Some(TestCaseOrigin::Manual),
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            if !extra.is_empty() {
+                let extra = BatchTestSuite {
+                    timelimit: None,
+                    r#match: test_sutie.r#match.clone(),
+                    float_tolerance: None,
+                    generator: None,
+                    cases: vec![],
+                    extend: extra,
+                };
+                let num_extra_cases = extra.test_case_sources(&test_suite_dir)?.len();
+
+                stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+                write!(stderr, "Extra test cases:")?;
+                stderr.reset()?;
+                writeln!(
+                    stderr,
+                    " {} from {}",
+                    num_extra_cases,
+                    extra_test_dirs.iter().format(", "),
+                )?;
+
+                test_sutie.extend.extend(extra.extend);
+            }
+
+            let test_cases =
+                test_sutie.load_test_cases(&test_suite_dir, test_case_names, |_| {
+                    unimplemented!("`SystemTestCases` is not impelemented");
+                })?;
+            (test_cases, None)
         }
-        _ => todo!("currently only `Batch` is supported"),
+        TestSuite::Scoring(test_suite) => {
+            ensure!(
+                origin.is_none() && !record,
+                "`--origin` and `--record` are not supported for `Scoring` test suites",
+            );
+            let mut inputs = test_suite.input_paths(&test_suite_dir)?;
+            if let Some(names) = &test_case_names {
+                inputs.retain(|(name, _)| names.contains(name));
+            }
+            ensure!(
+                !inputs.is_empty(),
+                "No input files for `{}`",
+                test_suite_path.display(),
+            );
+            (vec![], Some((test_suite, inputs)))
+        }
+        _ => todo!("currently only `Batch` and `Scoring` are supported"),
     };
 
+    if let Some(origin) = origin {
+        test_cases.retain(|test_case| test_case.origin == origin);
+        if test_cases.is_empty() {
+            bail!(
+                "No {} test cases in `{}`",
+                origin.as_str(),
+                test_suite_path.display(),
+            );
+        }
+    }
+
+    if ignore_trailing_whitespace {
+        for test_case in &mut test_cases {
+            test_case.output.ignore_trailing_whitespace();
+        }
+    }
+
+    for test_case in &test_cases {
+        if test_case.has_bom() {
+            stderr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+            write!(stderr, "warning:")?;
+            stderr.reset()?;
+            writeln!(
+                stderr,
+                " {:?} starts with a BOM (U+FEFF). It is ignored when comparing the output",
+                test_case.name.as_deref().unwrap_or(""),
+            )?;
+        }
+    }
+
+    for test_case in &mut test_cases {
+        if let Some(timelimit) = &mut test_case.timelimit {
+            *timelimit = timelimit.mul_f64(timelimit_scale);
+        }
+    }
+    if let Some((
+        ScoringTestSuite {
+            timelimit: Some(timelimit),
+            ..
+        },
+        _,
+    )) = &mut scoring
+    {
+        *timelimit = timelimit.mul_f64(timelimit_scale);
+    }
+
     let redirections = (
         stdin_process_redirection,
-        stdout_process_redirection,
+        // Keeps `stdout` for the JSON.
+        if json_output {
+            Stdio::null
+        } else {
+            stdout_process_redirection
+        },
         stderr_process_redirection,
     );
 
@@ -88,7 +252,31 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
                 writeln!(stderr)?;
             }
 
-            build(&mut stderr, &base_dir, &src, action, redirections, msg)?;
+            if skip_compile {
+                let output = Path::new(&action.output);
+                let output = base_dir.join(output.strip_prefix(".").unwrap_or(output));
+                ensure!(
+                    output.exists(),
+                    "`{}` does not exist. Remove `--skip-compile` to build it",
+                    output.display(),
+                );
+                writeln!(stderr, "Skipped building {}.", output.display())?;
+                stderr.flush()?;
+            } else if let Err(err) = build(
+                &mut stderr,
+                &base_dir,
+                &src,
+                action,
+                redirections,
+                msg,
+                force_compile,
+            ) {
+                if json_output {
+                    writeln!(stdout, "{}", json_summary("CE", &[]))?;
+                    stdout.flush()?;
+                }
+                return Err(err);
+            }
         }
     }
 
@@ -96,21 +284,280 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
         writeln!(stderr)?;
     }
     stderr.set_color(color_spec!(Bold))?;
-    write!(stderr, "Running the tests...")?;
+    write!(stderr, "{}", crate::messages::Message::RunningTests.get())?;
     stderr.reset()?;
     writeln!(stderr)?;
     stderr.flush()?;
 
-    let (cmd, tempfile) = match run {
+    let (cmd, tempfile) = command_expression(run, base_dir, utf8_env)?;
+
+    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    write!(stderr, "Test file:")?;
+    stderr.reset()?;
+    writeln!(stderr, " {}", test_suite_path.display())?;
+
+    if !variant.is_empty() {
+        stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+        write!(stderr, "Variant:")?;
+        stderr.reset()?;
+        writeln!(stderr, " {} ({})", variant, src)?;
+    }
+
+    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    write!(stderr, "Command:")?;
+    stderr.reset()?;
+    writeln!(stderr, " {}", shell_escape_args(&cmd.program, &cmd.args))?;
+
+    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    write!(stderr, "Working Directory:")?;
+    stderr.reset()?;
+    writeln!(stderr, " {}", cmd.cwd.display())?;
+
+    stderr.flush()?;
+
+    if let Some((test_suite, inputs)) = scoring {
+        ensure!(
+            !json_output,
+            "`--json` is not supported for `Scoring` test suites",
+        );
+
+        let outcome = snowchains_core::judge::score(
+            progress_draw_target,
+            crate::watch::ctrl_c_or_change,
+            &cmd,
+            &inputs,
+            test_suite.timelimit,
+            &test_suite.scorer,
+            export_env,
+        )?;
+
+        if let Some(tempfile) = tempfile {
+            tempfile.close()?;
+        }
+
+        writeln!(stderr)?;
+        stderr.flush()?;
+        outcome.print_pretty(&mut stdout)?;
+
+        let num_failures = outcome.num_failures();
+        let total = outcome.total();
+
+        // Partial runs and runs with failures are not compared with the best.
+        let score_history_dir = history_dir.filter(|_| num_failures == 0);
+
+        let key = format!(
+            "{}/{}/{}",
+            service.to_kebab_case_str(),
+            contest.as_deref().unwrap_or(""),
+            problem,
+        );
+
+        let best = match &score_history_dir {
+            Some(base_dir) => ScoreHistory::load(base_dir)?
+                .get(&key)
+                .filter(|best| best.num_cases == outcome.cases.len())
+                .cloned(),
+            None => None,
+        };
+
+        writeln!(stdout)?;
+        stdout.set_color(color_spec!(Bold))?;
+        write!(stdout, "Total:")?;
+        stdout.reset()?;
+        write!(stdout, " {}", total)?;
+
+        let improved = match &best {
+            Some(best) if test_suite.scorer.minimize => total < best.total,
+            Some(best) => total > best.total,
+            None => true,
+        };
+
+        if let Some(best) = &best {
+            write!(stdout, " (best: {} by {})", best.total, best.src)?;
+            if improved {
+                stdout.set_color(color_spec!(Bold, Fg(Color::Green)))?;
+                write!(stdout, " New best!")?;
+                stdout.reset()?;
+            }
+        }
+        writeln!(stdout)?;
+        stdout.flush()?;
+
+        notify(
+            &mut stderr,
+            webhook.as_ref(),
+            crate::notify::Notification {
+                event: config::WebhookEvent::Judge,
+                service,
+                contest: contest.as_deref(),
+                problem: &problem,
+                verdict: if num_failures == 0 {
+                    "OK".to_owned()
+                } else {
+                    format!("{}/{} failed", num_failures, outcome.cases.len())
+                },
+                score: Some(total.to_string()),
+                time: outcome.cases.iter().filter_map(|case| case.elapsed).max(),
+                url: None,
+            },
+        )?;
+
+        if num_failures > 0 {
+            bail!("{}/{} test cases failed", num_failures, outcome.cases.len());
+        }
+
+        if let Some(base_dir) = score_history_dir {
+            if improved {
+                let mut history = ScoreHistory::load(&base_dir)?;
+                history.record(key, total, outcome.cases.len(), &src);
+                history.save(&base_dir)?;
+            }
+
+            let mut history = JudgeHistory::load(&base_dir)?;
+            history.record(&src, src_sha256);
+            history.save(&base_dir)?;
+        }
+        return Ok(());
+    }
+
+    let outcome = snowchains_core::judge::judge(
+        progress_draw_target,
+        crate::watch::ctrl_c_or_change,
+        &cmd,
+        &test_cases,
+        stdin_echo,
+        export_env,
+    )?;
+
+    if let Some(tempfile) = tempfile {
+        tempfile.close()?;
+    }
+
+    writeln!(stderr)?;
+    stderr.flush()?;
+
+    let display_limit = Some(display_limit.into::<Byte>().value().saturating_as());
+
+    if json_output {
+        outcome.print_pretty(&mut stderr, display_limit, diff)?;
+        print_counts_by_origin(&mut stderr, &test_cases, &outcome.verdicts)?;
+    } else {
+        outcome.print_pretty(&mut stdout, display_limit, diff)?;
+        print_counts_by_origin(&mut stdout, &test_cases, &outcome.verdicts)?;
+    }
+
+    if let Some(base_dir) = run_log_dir {
+        let run_log = RunLog {
+            service,
+            contest: contest.clone(),
+            problem: problem.clone(),
+            src: src.clone(),
+            summary: outcome.summary(),
+        };
+        let path = run_log.save(&base_dir)?;
+
+        writeln!(stderr)?;
+        write!(stderr, "Recorded to ")?;
+        stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+        write!(stderr, "{}", path.display())?;
+        stderr.reset()?;
+        writeln!(stderr)?;
+        stderr.flush()?;
+    }
+
+    let mut counts = BTreeMap::<_, usize>::new();
+    for verdict in &outcome.verdicts {
+        *counts.entry(abbreviate(verdict)).or_default() += 1;
+    }
+    notify(
+        &mut stderr,
+        webhook.as_ref(),
+        crate::notify::Notification {
+            event: config::WebhookEvent::Judge,
+            service,
+            contest: contest.as_deref(),
+            problem: &problem,
+            verdict: match *counts.keys().copied().collect::<Vec<_>>() {
+                ["AC"] => "AC".to_owned(),
+                _ => counts
+                    .iter()
+                    .map(|(verdict, count)| format!("{} {}", count, verdict))
+                    .join(", "),
+            },
+            score: None,
+            time: outcome.verdicts.iter().filter_map(elapsed).max(),
+            url: None,
+        },
+    )?;
+
+    if json_output {
+        let cases = outcome
+            .verdicts
+            .iter()
+            .map(|verdict| {
+                (
+                    abbreviate(verdict),
+                    verdict.test_case_name(),
+                    elapsed(verdict),
+                )
+            })
+            .collect::<Vec<_>>();
+        let overall = cases
+            .iter()
+            .map(|&(verdict, _, _)| verdict)
+            .find(|&verdict| verdict != "AC")
+            .unwrap_or("AC");
+        writeln!(stdout, "{}", json_summary(overall, &cases))?;
+        stdout.flush()?;
+    }
+
+    outcome.error_on_fail()?;
+
+    if let Some(base_dir) = history_dir {
+        let mut history = JudgeHistory::load(&base_dir)?;
+        history.record(&src, src_sha256);
+        history.save(&base_dir)?;
+    }
+    Ok(())
+}
+
+/// Set for the solver so that its output does not depend on the locale of the machine. With
+/// `LC_ALL=C`, for instance, Python fails to print non-ASCII characters. `LC_ALL` is also set since
+/// it takes precedence over `LANG`.
+const UTF8_ENV: &[(&str, &str)] = &[
+    ("PYTHONIOENCODING", "utf-8"),
+    ("LANG", "C.UTF-8"),
+    ("LC_ALL", "C.UTF-8"),
+];
+
+/// Turns `run` into a command. For a `Script`, the returned temporary file has to be kept until
+/// the command finishes.
+///
+/// With `utf8_env`, the command gets [`UTF8_ENV`].
+fn command_expression(
+    run: config::Command,
+    base_dir: PathBuf,
+    utf8_env: bool,
+) -> io::Result<(CommandExpression, Option<NamedTempFile>)> {
+    let env = if utf8_env {
+        UTF8_ENV
+            .iter()
+            .map(|&(k, v)| (k.into(), v.into()))
+            .collect()
+    } else {
+        btreemap!()
+    };
+
+    match run {
         config::Command::Args(args) => {
             let cmd = CommandExpression {
                 program: args.get(0).cloned().unwrap_or_default().into(),
                 args: args.into_iter().skip(1).map(Into::into).collect(),
                 cwd: base_dir,
-                env: btreemap!(),
+                env,
             };
 
-            (cmd, None)
+            Ok((cmd, None))
         }
         config::Command::Script(config::Script {
             program,
@@ -128,49 +575,807 @@ pub(crate) fn judge(args: Args<impl WriteColor, impl WriteColor>) -> anyhow::Res
                 program: program.into(),
                 args: vec![tempfile.path().into()],
                 cwd: base_dir,
-                env: btreemap!(),
+                env,
             };
 
-            (cmd, Some(tempfile))
+            Ok((cmd, Some(tempfile)))
         }
+    }
+}
+
+pub(crate) struct StressArgs<W1, W2> {
+    pub(crate) stdout: W1,
+    pub(crate) stderr: W2,
+    pub(crate) stdin_process_redirection: fn() -> Stdio,
+    pub(crate) stdout_process_redirection: fn() -> Stdio,
+    pub(crate) stderr_process_redirection: fn() -> Stdio,
+    pub(crate) progress_draw_target: ProgressDrawTarget,
+    pub(crate) base_dir: PathBuf,
+    pub(crate) service: PlatformKind,
+    pub(crate) contest: Option<String>,
+    pub(crate) problem: String,
+    pub(crate) solution: config::Language,
+    pub(crate) brute: config::Language,
+    pub(crate) options: config::JudgeOptions,
+    /// `stress.generator`. `generator` of the test suite is used if this is empty.
+    pub(crate) generator: Vec<String>,
+    pub(crate) iterations: usize,
+    pub(crate) display_limit: Size,
+}
+
+/// Judges the solution on random inputs from `generator`, taking the outputs of `brute` as the
+/// expected ones, until they disagree.
+///
+/// The `match` and the checker of the test suite are used for the comparison. The first failing
+/// input is saved with [`save_stress_case`].
+pub(crate) fn stress(args: StressArgs<impl WriteColor, impl WriteColor>) -> anyhow::Result<()> {
+    let StressArgs {
+        mut stdout,
+        mut stderr,
+        stdin_process_redirection,
+        stdout_process_redirection,
+        stderr_process_redirection,
+        progress_draw_target,
+        base_dir,
+        service,
+        contest,
+        problem,
+        solution,
+        brute,
+        options,
+        generator,
+        iterations,
+        display_limit,
+    } = args;
+
+    let (test_suite, test_suite_dir, test_suite_path) =
+        read_batch_test_suite(&base_dir, service, contest.as_deref(), &problem)?;
+
+    let generator = if generator.is_empty() {
+        test_suite.generator.clone().unwrap_or_default()
+    } else {
+        generator
     };
 
-    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
-    write!(stderr, "Test file:")?;
-    stderr.reset()?;
-    writeln!(stderr, " {}", test_suite_path.display())?;
+    ensure!(
+        !generator.is_empty(),
+        "Neither `stress.generator` in `snowchains.dhall` nor `generator` in `{}` is set",
+        test_suite_path.display(),
+    );
 
-    stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
-    write!(stderr, "Command:")?;
-    stderr.reset()?;
-    writeln!(stderr, " {}", shell_escape_args(&cmd.program, &cmd.args))?;
+    let timelimit = test_suite
+        .timelimit
+        .map(|timelimit| timelimit.mul_f64(options.timelimitScale));
+
+    let redirections = (
+        stdin_process_redirection,
+        stdout_process_redirection,
+        stderr_process_redirection,
+    );
+
+    for language in &[&solution, &brute] {
+        for (action, msg) in &[
+            (&language.transpile, "Transpiling..."),
+            (&language.compile, "Compiling..."),
+        ] {
+            if let Some(action) = action {
+                build(
+                    &mut stderr,
+                    &base_dir,
+                    &language.src,
+                    action,
+                    redirections,
+                    msg,
+                    false,
+                )?;
+                writeln!(stderr)?;
+            }
+        }
+    }
+
+    let (cmd, tempfile) = command_expression(solution.run, base_dir.clone(), options.utf8Env)?;
+    let (brute_cmd, brute_tempfile) =
+        command_expression(brute.run, base_dir.clone(), options.utf8Env)?;
+
+    for (label, cmd) in &[("Solution:", &cmd), ("Brute force:", &brute_cmd)] {
+        stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+        write!(stderr, "{}", label)?;
+        stderr.reset()?;
+        writeln!(stderr, " {}", shell_escape_args(&cmd.program, &cmd.args))?;
+    }
 
     stderr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
-    write!(stderr, "Working Directory:")?;
+    write!(stderr, "Generator:")?;
     stderr.reset()?;
-    writeln!(stderr, " {}", cmd.cwd.display())?;
-
+    writeln!(
+        stderr,
+        " {} <ITERATION>",
+        shell_escape_args(&generator[0], &generator[1..])
+    )?;
     stderr.flush()?;
 
-    let outcome = snowchains_core::judge::judge(
-        progress_draw_target,
-        tokio::signal::ctrl_c,
-        &cmd,
-        &test_cases,
-    )?;
+    let progress = ProgressBar::with_draw_target(iterations as u64, progress_draw_target);
 
-    if let Some(tempfile) = tempfile {
+    for i in 1..=iterations {
+        let input = run_to_end(
+            &generator_command(&generator, i, &base_dir),
+            "",
+            stderr_process_redirection,
+        )
+        .with_context(|| format!("The generator failed for iteration {}", i))?;
+        let expected = run_to_end(&brute_cmd, &input, stderr_process_redirection)
+            .with_context(|| format!("The brute force solution failed for iteration {}", i))?;
+
+        let test_cases = BatchTestSuite {
+            timelimit,
+            r#match: test_suite.r#match.clone(),
+            float_tolerance: test_suite.float_tolerance,
+            generator: None,
+            cases: vec![PartialBatchTestCase {
+                name: Some(format!("stress {}", i)),
+                r#in: input.as_str().into(),
+                out: Some(expected.as_str().into()),
+                timelimit: None,
+                r#match: None,
+                origin: Some(TestCaseOrigin::Generated),
+                note: None,
+            }],
+            extend: vec![],
+        }
+        .load_test_cases(&test_suite_dir, None::<HashSet<String>>, |_| {
+            unreachable!("no `SystemTestCases`")
+        })?;
+
+        let outcome = snowchains_core::judge::judge(
+            ProgressDrawTarget::hidden(),
+            crate::watch::ctrl_c_or_change,
+            &cmd,
+            &test_cases,
+            options.stdinEcho,
+            options.exportEnv,
+        )?;
+
+        progress.inc(1);
+
+        if !matches!(outcome.verdicts[..], [Verdict::Accepted { .. }]) {
+            progress.finish_and_clear();
+
+            outcome.print_pretty(
+                &mut stdout,
+                Some(display_limit.into::<Byte>().value().saturating_as()),
+                true,
+            )?;
+            stdout.flush()?;
+
+            let path = save_stress_case(
+                &test_suite_path,
+                &test_suite_dir,
+                &problem,
+                &input,
+                &expected,
+            )?;
+
+            writeln!(stderr)?;
+            write!(stderr, "Saved the input to ")?;
+            stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+            write!(stderr, "{}", path.display())?;
+            stderr.reset()?;
+            writeln!(stderr)?;
+            stderr.flush()?;
+
+            bail!(
+                "The solution and the brute force solution disagree at iteration {}/{}",
+                i,
+                iterations,
+            );
+        }
+    }
+
+    progress.finish_and_clear();
+
+    for tempfile in tempfile.into_iter().chain(brute_tempfile) {
         tempfile.close()?;
     }
 
-    writeln!(stderr)?;
+    stderr.set_color(color_spec!(Bold, Fg(Color::Green)))?;
+    write!(stderr, "OK")?;
+    stderr.reset()?;
+    writeln!(stderr, ": {} iterations", iterations)?;
     stderr.flush()?;
-    outcome.print_pretty(
-        stdout,
-        Some(display_limit.into::<Byte>().value().saturating_as()),
+    Ok(())
+}
+
+/// Saves a failing input of `stress` and the output of the brute force solution as
+/// `{problem}-stress/{in,out}/{n}.txt` next to the test suite, and adds the directory to `extend`
+/// of the test suite if it is not there yet.
+///
+/// Returns the path of the input.
+fn save_stress_case(
+    test_suite_path: &Path,
+    test_suite_dir: &Path,
+    problem: &str,
+    input: &str,
+    expected: &str,
+) -> anyhow::Result<PathBuf> {
+    let dir_name = format!("{}-stress", problem);
+    let dir = test_suite_dir.join(&dir_name);
+
+    let file_name = (1..)
+        .map(|n| format!("{}.txt", n))
+        .find(|file_name| !dir.join("in").join(file_name).exists())
+        .expect("should be found");
+
+    let in_path = dir.join("in").join(&file_name);
+    crate::fs::write(&in_path, input, true)?;
+    crate::fs::write(dir.join("out").join(&file_name), expected, true)?;
+
+    let mut test_suite = crate::fs::read_yaml::<TestSuite, _>(test_suite_path)?;
+
+    if let TestSuite::Batch(BatchTestSuite { extend, .. }) = &mut test_suite {
+        let path = format!("./{}", dir_name);
+
+        if !extend
+            .iter()
+            .any(|a| matches!(a, Additional::Text { path: p, .. } if p.as_str() == path))
+        {
+            extend.push(Additional::Text {
+                path: path.into(),
+                r#in: "/in/*.txt".to_owned(),
+                out: "/out/*.txt".to_owned(),
+                timelimit: None,
+                r#match: None,
+                origin: Some(TestCaseOrigin::Generated),
+            });
+            crate::fs::write(test_suite_path, test_suite.to_yaml_pretty(), true)?;
+        }
+    }
+
+    Ok(in_path)
+}
+
+/// `generator` with `index` appended, run in `base_dir`.
+fn generator_command(generator: &[String], index: usize, base_dir: &Path) -> CommandExpression {
+    CommandExpression {
+        program: generator[0].clone().into(),
+        args: generator[1..]
+            .iter()
+            .cloned()
+            .chain(iter::once(index.to_string()))
+            .map(Into::into)
+            .collect(),
+        cwd: base_dir.to_owned(),
+        env: btreemap!(),
+    }
+}
+
+/// Runs `cmd` with `input`, and returns what it writes to stdout. Fails unless it exits
+/// successfully.
+fn run_to_end(
+    cmd: &CommandExpression,
+    input: &str,
+    stderr_process_redirection: fn() -> Stdio,
+) -> anyhow::Result<String> {
+    let shell_escaped = shell_escape_args(&cmd.program, &cmd.args);
+
+    let mut child = std::process::Command::new(&cmd.program)
+        .args(&cmd.args)
+        .current_dir(&cmd.cwd)
+        .envs(&cmd.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(stderr_process_redirection())
+        .spawn()
+        .with_context(|| format!("Could not execute {}", shell_escaped))?;
+
+    let mut stdin = child.stdin.take().expect("should be piped");
+    let input = input.to_owned();
+    // Written in another thread so that a large output does not block a large input.
+    let writer = thread::spawn(move || stdin.write_all(input.as_ref()));
+
+    let output = child.wait_with_output()?;
+    // The program may exit without reading all of the input.
+    let _ = writer.join();
+
+    if !output.status.success() {
+        bail!("{} {}", shell_escaped, describe_exit_status(output.status));
+    }
+
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("{} wrote invalid UTF-8", shell_escaped))
+}
+
+fn describe_exit_status(status: ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        format!("exited with code {}", code)
+    } else {
+        "was terminated by signal".to_owned()
+    }
+}
+
+/// Posts to the webhook if any, reporting a failure as a warning.
+fn notify(
+    mut stderr: impl WriteColor,
+    webhook: Option<&config::Webhook>,
+    notification: crate::notify::Notification<'_>,
+) -> io::Result<()> {
+    if let Some(webhook) = webhook {
+        if let Err(err) = crate::notify::post(webhook, &notification) {
+            stderr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+            write!(stderr, "warning:")?;
+            stderr.reset()?;
+            writeln!(stderr, " {:#}", err)?;
+            stderr.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints the number of each verdict for each origin, if the test cases have more than one.
+fn print_counts_by_origin(
+    mut wtr: impl WriteColor,
+    test_cases: &[BatchTestCase],
+    verdicts: &[Verdict],
+) -> io::Result<()> {
+    let origins = test_cases
+        .iter()
+        .map(|test_case| test_case.origin)
+        .collect::<BTreeSet<_>>();
+
+    if origins.len() <= 1 {
+        return Ok(());
+    }
+
+    writeln!(wtr)?;
+
+    for origin in origins {
+        let mut counts = BTreeMap::<_, usize>::new();
+        for (test_case, verdict) in test_cases.iter().zip(verdicts) {
+            if test_case.origin == origin {
+                *counts.entry(abbreviate(verdict)).or_default() += 1;
+            }
+        }
+
+        wtr.set_color(color_spec!(Bold))?;
+        write!(wtr, "{}:", origin.as_str())?;
+        wtr.reset()?;
+        writeln!(
+            wtr,
+            " {}",
+            counts
+                .iter()
+                .map(|(verdict, count)| format!("{} {}", count, verdict))
+                .format(", "),
+        )?;
+    }
+
+    wtr.flush()
+}
+
+/// The output of `judge --json`. `cases` are `(verdict, name, elapsed)`.
+fn json_summary(
+    verdict: &str,
+    cases: &[(&str, Option<&str>, Option<Duration>)],
+) -> serde_json::Value {
+    json!({
+        "verdict": verdict,
+        "cases": cases
+            .iter()
+            .enumerate()
+            .map(|(index, &(verdict, name, elapsed))| {
+                json!({
+                    "index": index,
+                    "name": name,
+                    "verdict": verdict,
+                    "elapsed_ms": elapsed.map(|elapsed| elapsed.as_millis() as u64),
+                })
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// `None` for `Time Limit Exceeded`, which is killed at the time limit.
+fn elapsed(verdict: &Verdict) -> Option<Duration> {
+    match verdict {
+        Verdict::Accepted { elapsed, .. }
+        | Verdict::WrongAnswer { elapsed, .. }
+        | Verdict::RuntimeError { elapsed, .. } => Some(*elapsed),
+        Verdict::TimelimitExceeded { .. } => None,
+    }
+}
+
+fn abbreviate(verdict: &Verdict) -> &'static str {
+    match verdict {
+        Verdict::Accepted { .. } => "AC",
+        Verdict::WrongAnswer { .. } => "WA",
+        Verdict::TimelimitExceeded { .. } => "TLE",
+        Verdict::RuntimeError { .. } => "RE",
+    }
+}
+
+/// A judge run recorded by `judge --record`.
+///
+/// Stored in `.snowchains/runs/{time}-{problem}/summary.json`.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct RunLog {
+    pub(crate) service: PlatformKind,
+    pub(crate) contest: Option<String>,
+    pub(crate) problem: String,
+    pub(crate) src: String,
+    #[serde(flatten)]
+    pub(crate) summary: JudgeSummary,
+}
+
+impl RunLog {
+    fn save(&self, base_dir: &Path) -> anyhow::Result<PathBuf> {
+        let dir_name = format!(
+            "{}-{}",
+            chrono::Local::now().format("%Y%m%dT%H%M%S%.3f"),
+            self.problem,
+        );
+        let path = runs_dir(base_dir).join(dir_name).join("summary.json");
+        crate::fs::write_json(&path, self, true)?;
+        Ok(path)
+    }
+
+    /// Loads the most recently written one.
+    pub(crate) fn load_latest(base_dir: &Path) -> anyhow::Result<(PathBuf, Self)> {
+        let runs_dir = runs_dir(base_dir);
+
+        let entries = std::fs::read_dir(&runs_dir).with_context(|| {
+            format!(
+                "Could not read `{}`. Run `judge --record` first",
+                runs_dir.display(),
+            )
+        })?;
+
+        let mut latest = None;
+        for entry in entries {
+            let path = entry?.path().join("summary.json");
+            if let Ok(modified) = path.metadata().and_then(|m| m.modified()) {
+                if !matches!(&latest, Some((t, _)) if *t >= modified) {
+                    latest = Some((modified, path));
+                }
+            }
+        }
+
+        let (_, path) =
+            latest.with_context(|| format!("No runs recorded in `{}`", runs_dir.display()))?;
+        let run_log = crate::fs::read_json(&path)?;
+        Ok((path, run_log))
+    }
+}
+
+fn runs_dir(base_dir: &Path) -> PathBuf {
+    base_dir.join(".snowchains").join("runs")
+}
+
+/// The source files that passed all of the test cases last time.
+///
+/// Stored in `.snowchains/judge-history.yml` as `{src: {sha256, passedAt}}` where `passedAt` is
+/// in seconds since the Unix epoch. `submit --no-judge` compares the source with it.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub(crate) struct JudgeHistory(BTreeMap<String, PassedJudge>);
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PassedJudge {
+    pub(crate) sha256: String,
+    pub(crate) passed_at: u64,
+}
+
+impl PassedJudge {
+    pub(crate) fn elapsed(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH + Duration::from_secs(self.passed_at))
+            .unwrap_or_default()
+    }
+}
+
+impl JudgeHistory {
+    pub(crate) fn load(base_dir: &Path) -> anyhow::Result<Self> {
+        let path = Self::path(base_dir);
+        if path.exists() {
+            crate::fs::read_yaml(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    fn save(&self, base_dir: &Path) -> anyhow::Result<()> {
+        crate::fs::write(Self::path(base_dir), serde_yaml::to_string(self)?, true)
+    }
+
+    fn record(&mut self, src: &str, sha256: String) {
+        let passed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.0
+            .insert(src.to_owned(), PassedJudge { sha256, passed_at });
+    }
+
+    pub(crate) fn get(&self, src: &str) -> Option<&PassedJudge> {
+        self.0.get(src)
+    }
+
+    fn path(base_dir: &Path) -> PathBuf {
+        base_dir.join(".snowchains").join("judge-history.yml")
+    }
+}
+
+/// The best totals of `Scoring` test suites.
+///
+/// Stored in `.snowchains/score-history.yml` as `{"{service}/{contest}/{problem}": {total,
+/// numCases, src, recordedAt}}`. A total is compared only with the one of the same number of
+/// cases.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub(crate) struct ScoreHistory(BTreeMap<String, BestScore>);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BestScore {
+    pub(crate) total: f64,
+    pub(crate) num_cases: usize,
+    pub(crate) src: String,
+    pub(crate) recorded_at: u64,
+}
+
+impl ScoreHistory {
+    pub(crate) fn load(base_dir: &Path) -> anyhow::Result<Self> {
+        let path = Self::path(base_dir);
+        if path.exists() {
+            crate::fs::read_yaml(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    fn save(&self, base_dir: &Path) -> anyhow::Result<()> {
+        crate::fs::write(Self::path(base_dir), serde_yaml::to_string(self)?, true)
+    }
+
+    fn record(&mut self, key: String, total: f64, num_cases: usize, src: &str) {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.0.insert(
+            key,
+            BestScore {
+                total,
+                num_cases,
+                src: src.to_owned(),
+                recorded_at,
+            },
+        );
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&BestScore> {
+        self.0.get(key)
+    }
+
+    fn path(base_dir: &Path) -> PathBuf {
+        base_dir.join(".snowchains").join("score-history.yml")
+    }
+}
+
+pub(crate) fn sha256_hex(content: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(content.as_ref()))
+}
+
+/// Formats a duration roughly, e.g. "14 minutes".
+pub(crate) fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    let (n, unit) = match secs {
+        0..=59 => (secs, "second"),
+        60..=3599 => (secs / 60, "minute"),
+        3600..=86399 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" })
+}
+
+pub(crate) fn test_suite_dir(
+    base_dir: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+) -> PathBuf {
+    base_dir
+        .join(".snowchains")
+        .join("tests")
+        .join(service.to_kebab_case_str())
+        .join(contest.unwrap_or(""))
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or_else(|| Path::new("")).to_owned()
+}
+
+/// Loads the `nth` (1-based, as shown by `judge`) test case of a batch test suite.
+pub(crate) fn nth_test_case(
+    base_dir: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: &str,
+    nth: usize,
+) -> anyhow::Result<BatchTestCase> {
+    let (test_suite, test_suite_dir, test_suite_path) =
+        read_batch_test_suite(base_dir, service, contest, problem)?;
+
+    let test_cases =
+        test_suite.load_test_cases(&test_suite_dir, None::<HashSet<String>>, |_| {
+            bail!("`SystemTestCases` is not supported")
+        })?;
+
+    pick_nth(test_cases, nth, &test_suite_path)
+}
+
+/// Locates the `nth` test case of a batch test suite without reading its text files.
+///
+/// `nth` points the same test case as [`nth_test_case`].
+pub(crate) fn nth_test_case_source(
+    base_dir: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: &str,
+    nth: usize,
+) -> anyhow::Result<TestCaseSource> {
+    let (test_suite, test_suite_dir, test_suite_path) =
+        read_batch_test_suite(base_dir, service, contest, problem)?;
+
+    let sources = test_suite.test_case_sources(&test_suite_dir)?;
+
+    pick_nth(sources, nth, &test_suite_path)
+}
+
+fn read_batch_test_suite(
+    base_dir: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: &str,
+) -> anyhow::Result<(BatchTestSuite, PathBuf, PathBuf)> {
+    let test_suite_path = test_suite_path(base_dir, service, contest, problem)?;
+    let test_suite_dir = parent_dir(&test_suite_path);
+
+    match crate::fs::read_yaml(&test_suite_path)? {
+        TestSuite::Batch(mut test_suite) => {
+            test_suite.resolve_checker(base_dir)?;
+            Ok((test_suite, test_suite_dir, test_suite_path))
+        }
+        _ => bail!("`{}` is not a batch test suite", test_suite_path.display()),
+    }
+}
+
+fn test_suite_path(
+    base_dir: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: &str,
+) -> anyhow::Result<PathBuf> {
+    let test_suite_path = test_suite_dir(base_dir, service, contest)
+        .join(problem)
+        .with_extension("yml");
+    crate::suite_link::resolve(&test_suite_path)
+}
+
+/// Runs `generator` of a batch test suite `count` times, and appends the outputs to `cases` as
+/// `generated` test cases without expected outputs.
+///
+/// The generator gets the 1-based number of the generated test case, counting the ones already in
+/// `cases`, so that running this again does not repeat the same seeds. Returns the path of the
+/// test suite.
+pub(crate) fn generate(
+    base_dir: &Path,
+    service: PlatformKind,
+    contest: Option<&str>,
+    problem: &str,
+    count: usize,
+    stderr_process_redirection: fn() -> Stdio,
+) -> anyhow::Result<PathBuf> {
+    let test_suite_path = test_suite_path(base_dir, service, contest, problem)?;
+
+    // Not `read_batch_test_suite`, which makes `checker` absolute.
+    let mut test_suite = match crate::fs::read_yaml(&test_suite_path)? {
+        TestSuite::Batch(test_suite) => test_suite,
+        _ => bail!("`{}` is not a batch test suite", test_suite_path.display()),
+    };
+
+    let generator = test_suite
+        .generator
+        .clone()
+        .filter(|generator| !generator.is_empty())
+        .with_context(|| format!("`generator` is not set in `{}`", test_suite_path.display()))?;
+
+    let num_generated = test_suite
+        .cases
+        .iter()
+        .filter(|case| case.origin == Some(TestCaseOrigin::Generated))
+        .count();
+
+    for n in num_generated + 1..=num_generated + count {
+        let input = run_to_end(
+            &generator_command(&generator, n, base_dir),
+            "",
+            stderr_process_redirection,
+        )
+        .with_context(|| format!("The generator failed for test case {}", n))?;
+
+        test_suite.cases.push(PartialBatchTestCase {
+            name: Some(format!("Generated {}", n)),
+            r#in: input.into(),
+            out: None,
+            timelimit: None,
+            r#match: None,
+            origin: Some(TestCaseOrigin::Generated),
+            note: None,
+        });
+    }
+
+    crate::fs::write(
+        &test_suite_path,
+        TestSuite::Batch(test_suite).to_yaml_pretty(),
+        true,
     )?;
+    Ok(test_suite_path)
+}
+
+fn pick_nth<T>(mut items: Vec<T>, nth: usize, test_suite_path: &Path) -> anyhow::Result<T> {
+    let num_test_cases = items.len();
 
-    outcome.error_on_fail()
+    nth.checked_sub(1)
+        .filter(|&i| i < num_test_cases)
+        .map(|i| items.swap_remove(i))
+        .with_context(|| {
+            format!(
+                "`nth` must be in 1..={} for `{}`",
+                num_test_cases,
+                test_suite_path.display(),
+            )
+        })
+}
+
+/// Writes a text to `out`, or to `stdout` if `out` is `None`.
+///
+/// Files are streamed, and nothing is added to or stripped from the text.
+pub(crate) fn copy_text_source(
+    source: &TextSource,
+    out: Option<&Path>,
+    mut stdout: impl io::Write,
+) -> anyhow::Result<()> {
+    match (source, out) {
+        (TextSource::Inline(text), Some(out)) => crate::fs::write(out, &**text, true),
+        (TextSource::File(path), Some(out)) => {
+            if let Some(parent) = out.parent() {
+                crate::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(path, out).with_context(|| {
+                format!("Could not copy `{}` to `{}`", path.display(), out.display())
+            })?;
+            Ok(())
+        }
+        (TextSource::Inline(text), None) => {
+            stdout.write_all(text.as_bytes())?;
+            stdout.flush().map_err(Into::into)
+        }
+        (TextSource::File(path), None) => {
+            let mut file =
+                File::open(path).with_context(|| format!("Could not open `{}`", path.display()))?;
+            io::copy(&mut file, &mut stdout)
+                .with_context(|| format!("Could not read `{}`", path.display()))?;
+            stdout.flush().map_err(Into::into)
+        }
+    }
+}
+
+pub(crate) fn read_text_source(source: &TextSource) -> anyhow::Result<Vec<u8>> {
+    match source {
+        TextSource::Inline(text) => Ok(text.as_bytes().to_owned()),
+        TextSource::File(path) => {
+            std::fs::read(path).with_context(|| format!("Could not read `{}`", path.display()))
+        }
+    }
 }
 
 pub(crate) fn transpile(
@@ -193,9 +1398,11 @@ pub(crate) fn transpile(
             stderr_process_redirection,
         ),
         "Transpiling...",
+        false,
     )
 }
 
+/// Skipped if `output` is newer than `src`, unless `force`.
 #[allow(clippy::type_complexity)]
 fn build(
     mut stderr: impl WriteColor,
@@ -204,6 +1411,7 @@ fn build(
     build_action: &config::Compile,
     redirections: (fn() -> Stdio, fn() -> Stdio, fn() -> Stdio),
     msg: &'static str,
+    force: bool,
 ) -> anyhow::Result<()> {
     let src_modified = {
         let src = Path::new(&src);
@@ -219,7 +1427,7 @@ fn build(
     let (stdin_process_redirection, stdout_process_redirection, stderr_process_redirection) =
         redirections;
 
-    if output.exists() && crate::fs::metadata(&output)?.modified()? > src_modified {
+    if !force && output.exists() && crate::fs::metadata(&output)?.modified()? > src_modified {
         writeln!(stderr, "{} is up to date.", output.display())?;
         stderr.flush()?;
     } else {
@@ -321,15 +1529,7 @@ fn run_command<S1: AsRef<OsStr>, S2: AsRef<OsStr>, I: IntoIterator<Item = S2>, W
         .status()?;
 
     if !status.success() {
-        bail!(
-            "{} {}",
-            shell_escaped,
-            if let Some(code) = status.code() {
-                format!("exited with code {}", code)
-            } else {
-                "was terminated by signal".to_owned()
-            },
-        );
+        bail!("{} {}", shell_escaped, describe_exit_status(status));
     }
 
     Ok(())
@@ -344,3 +1544,112 @@ fn shell_escape_args(program: impl AsRef<OsStr>, args: &[impl AsRef<OsStr>]) ->
             .format(" "),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use snowchains_core::testsuite::{Additional, BatchTestSuite, TestSuite};
+    use std::{fs, mem, time::Duration};
+
+    #[cfg(unix)]
+    #[test]
+    fn utf8_env() -> anyhow::Result<()> {
+        use crate::config::Command;
+        use std::process::Stdio;
+
+        // `env -i` strips the environment of this process, as with a bare `LC_ALL=C` machine.
+        let run = |utf8_env| -> anyhow::Result<_> {
+            let (mut cmd, _) = super::command_expression(
+                Command::Args(vec![
+                    "sh".to_owned(),
+                    "-c".to_owned(),
+                    "printf '%s あ' \"$PYTHONIOENCODING $LANG $LC_ALL\"".to_owned(),
+                ]),
+                std::env::current_dir()?,
+                utf8_env,
+            )?;
+            let mut args = vec!["-i".into()];
+            args.extend(cmd.env.iter().map(|(k, v)| {
+                let mut kv = k.clone();
+                kv.push("=");
+                kv.push(v);
+                kv
+            }));
+            args.push(mem::take(&mut cmd.program));
+            args.append(&mut cmd.args);
+            cmd.program = "env".into();
+            cmd.args = args;
+            cmd.env.clear();
+            super::run_to_end(&cmd, "", Stdio::inherit)
+        };
+
+        assert_eq!("utf-8 C.UTF-8 C.UTF-8 あ", run(true)?);
+        assert_eq!("   あ", run(false)?);
+        Ok(())
+    }
+
+    #[test]
+    fn json_summary() {
+        assert_eq!(
+            json!({
+                "verdict": "WA",
+                "cases": [
+                    { "index": 0, "name": "sample1", "verdict": "AC", "elapsed_ms": 42 },
+                    { "index": 1, "name": null, "verdict": "WA", "elapsed_ms": 5 },
+                    { "index": 2, "name": "large", "verdict": "TLE", "elapsed_ms": null },
+                ],
+            }),
+            super::json_summary(
+                "WA",
+                &[
+                    ("AC", Some("sample1"), Some(Duration::from_millis(42))),
+                    ("WA", None, Some(Duration::from_micros(5_900))),
+                    ("TLE", Some("large"), None),
+                ],
+            ),
+        );
+        assert_eq!(
+            json!({ "verdict": "CE", "cases": [] }),
+            super::json_summary("CE", &[]),
+        );
+    }
+
+    #[test]
+    fn save_stress_case() -> anyhow::Result<()> {
+        let dir = tempfile::Builder::new()
+            .prefix("snowchains-judge-tests-")
+            .tempdir()?;
+        let test_suite_path = dir.path().join("a.yml");
+
+        fs::write(
+            &test_suite_path,
+            "---\ntype: Batch\ntimelimit: 2s\nmatch: Lines\n\ncases: []\n\nextend: []\n",
+        )?;
+
+        for (input, expected) in &[("1\n", "2\n"), ("3\n", "4\n")] {
+            super::save_stress_case(&test_suite_path, dir.path(), "a", input, expected)?;
+        }
+
+        assert_eq!(
+            "3\n",
+            fs::read_to_string(dir.path().join("a-stress/in/2.txt"))?
+        );
+        assert_eq!(
+            "4\n",
+            fs::read_to_string(dir.path().join("a-stress/out/2.txt"))?
+        );
+
+        let extend = match crate::fs::read_yaml(&test_suite_path)? {
+            TestSuite::Batch(BatchTestSuite { extend, .. }) => extend,
+            test_suite => panic!("expected `Batch`: {:?}", test_suite),
+        };
+        assert!(
+            matches!(&*extend, [Additional::Text { path, .. }] if path.as_str() == "./a-stress"),
+            "{:?}",
+            extend,
+        );
+
+        dir.close()?;
+        Ok(())
+    }
+}
@@ -2,15 +2,35 @@ mod commands;
 mod config;
 mod fs;
 mod judge;
+mod messages;
+mod notify;
+mod outbox;
 pub mod shell;
+mod suite_link;
+mod wait;
+mod watch;
 mod web;
 
 pub use crate::commands::{
-    init::OptInit, judge::OptJudge, login::OptLogin, participate::OptParticipate,
-    retrieve_languages::OptRetrieveLanguages,
+    completions::OptCompletions, flush_outbox::OptFlushOutbox, generate::OptGenerate,
+    init::OptInit, judge::OptJudge, lang_set::OptLangSet, login::OptLogin,
+    migrate_cookies::OptMigrateCookies, modify_match::OptModifyMatch,
+    modify_timelimit::OptModifyTimelimit, open::OptOpen, participate::OptParticipate,
+    retrieve_languages::OptRetrieveLanguages, retrieve_problems::OptRetrieveProblems,
     retrieve_submission_summaries::OptRetrieveSubmissionSummaries,
-    retrieve_testcases::OptRetrieveTestcases, submit::OptSubmit,
-    watch_submissions::OptWatchSubmissions, xtask::OptXtask,
+    retrieve_testcases::OptRetrieveTestcases, show_all_config_keys::OptShowAllConfigKeys,
+    show_compile_flags::OptShowCompileFlags, show_cookie_expiry::OptShowCookieExpiry,
+    show_expected::OptShowExpected, show_in::OptShowIn,
+    show_last_judge_summary::OptShowLastJudgeSummary,
+    show_problem_category::OptShowProblemCategory,
+    show_problem_difficulty::OptShowProblemDifficulty, show_rated_range::OptShowRatedRange,
+    show_recent_problems::OptShowRecentProblems, show_submission_code::OptShowSubmissionCode,
+    show_submission_score::OptShowSubmissionScore, show_test_case::OptShowTestCase,
+    show_test_case_hash::OptShowTestCaseHash, show_test_case_time_limit::OptShowTestCaseTimeLimit,
+    show_top_submissions::OptShowTopSubmissions, show_total_ac_problems::OptShowTotalAcProblems,
+    show_unreachable_languages::OptShowUnreachableLanguages, show_wasm_size::OptShowWasmSize,
+    stress::OptStress, submit::OptSubmit, validate::OptValidate,
+    watch_submission::OptWatchSubmission, watch_submissions::OptWatchSubmissions, xtask::OptXtask,
 };
 use std::{env, io::BufRead, path::PathBuf};
 use structopt::{
@@ -48,17 +68,57 @@ pub enum Opt {
     #[structopt(author, visible_alias("w"))]
     Watch(OptWatch),
 
+    /// Shows information
+    #[structopt(author)]
+    Show(OptShow),
+
+    /// Opens the problem pages in the browser without retrieving test cases
+    #[structopt(author, visible_alias("o"))]
+    Open(OptOpen),
+
+    /// Modifies test suites
+    #[structopt(author)]
+    Modify(OptModify),
+
+    /// Manages languages pinned to problems
+    #[structopt(author)]
+    Lang(OptLang),
+
     /// Tests code
     #[structopt(author, visible_aliases(&["j", "test", "t"]))]
     Judge(OptJudge),
 
+    /// Adds test cases generated by `generator` of the test suite
+    #[structopt(author)]
+    Generate(OptGenerate),
+
+    /// Compares the solution with a brute force solution on generated inputs
+    #[structopt(author)]
+    Stress(OptStress),
+
     /// Submits code
     #[structopt(author, visible_alias("s"))]
     Submit(OptSubmit),
 
+    /// Submits the code queued by `submit --queue`
+    #[structopt(author)]
+    FlushOutbox(OptFlushOutbox),
+
+    /// Checks that the config file evaluates for every service and that the commands are in `$PATH`
+    #[structopt(author, visible_alias("v"))]
+    Validate(OptValidate),
+
+    /// Converts data written by other versions
+    #[structopt(author)]
+    Migrate(OptMigrate),
+
     /// Runs a custom subcommand written in the config file
     #[structopt(author, visible_alias("x"), setting = AppSettings::TrailingVarArg)]
     Xtask(OptXtask),
+
+    /// Prints a completion script for a shell
+    #[structopt(author)]
+    Completions(OptCompletions),
 }
 
 #[derive(StructOpt, Debug)]
@@ -71,6 +131,10 @@ pub enum OptRetrieve {
     #[structopt(author, visible_alias("t"))]
     Testcases(OptRetrieveTestcases),
 
+    /// Retrieves the problems of a contest without saving test cases
+    #[structopt(author, visible_alias("p"))]
+    Problems(OptRetrieveProblems),
+
     /// Retrieves submission summaries
     #[structopt(author, visible_alias("ss"))]
     SubmissionSummaries(OptRetrieveSubmissionSummaries),
@@ -81,6 +145,118 @@ pub enum OptWatch {
     /// Watches your submissions
     #[structopt(author, visible_alias("s"))]
     Submissions(OptWatchSubmissions),
+
+    /// Watches your most recent submission until it is judged. Fails unless it is AC
+    #[structopt(author)]
+    Submission(OptWatchSubmission),
+}
+
+#[derive(StructOpt, Debug)]
+pub enum OptShow {
+    /// Alias for `retrieve problems`
+    #[structopt(author)]
+    Problems(OptRetrieveProblems),
+
+    /// Shows when each stored cookie expires
+    #[structopt(author)]
+    CookieExpiry(OptShowCookieExpiry),
+
+    /// Guesses the category of a problem from its statement
+    #[structopt(author)]
+    ProblemCategory(OptShowProblemCategory),
+
+    /// Shows the difficulty of a problem estimated by AtCoder Problems
+    #[structopt(author)]
+    ProblemDifficulty(OptShowProblemDifficulty),
+
+    /// Shows the rated range of an AtCoder contest
+    #[structopt(author)]
+    RatedRange(OptShowRatedRange),
+
+    /// Shows the fastest accepted submissions for a problem
+    #[structopt(author)]
+    TopSubmissions(OptShowTopSubmissions),
+
+    /// Prints the score of your latest submission for a problem
+    #[structopt(author)]
+    SubmissionScore(OptShowSubmissionScore),
+
+    /// Prints the code of your latest accepted submission for a problem
+    #[structopt(author)]
+    SubmissionCode(OptShowSubmissionCode),
+
+    /// Counts the distinct problems you have solved on AtCoder
+    #[structopt(author)]
+    TotalAcProblems(OptShowTotalAcProblems),
+
+    /// Prints a test case
+    #[structopt(author)]
+    TestCase(OptShowTestCase),
+
+    /// Prints the input of a test case as is
+    #[structopt(author)]
+    In(OptShowIn),
+
+    /// Prints the expected output of a test case as is
+    #[structopt(author)]
+    Expected(OptShowExpected),
+
+    /// Shows the result of the last `judge --record`
+    #[structopt(author)]
+    LastJudgeSummary(OptShowLastJudgeSummary),
+
+    /// Prints the SHA-256 of the input of a test case
+    #[structopt(author)]
+    TestCaseHash(OptShowTestCaseHash),
+
+    /// Prints the time limit of a test case
+    #[structopt(author)]
+    TestCaseTimeLimit(OptShowTestCaseTimeLimit),
+
+    /// Shows the latest problems
+    #[structopt(author)]
+    RecentProblems(OptShowRecentProblems),
+
+    /// Prints the compile command for a problem
+    #[structopt(author)]
+    CompileFlags(OptShowCompileFlags),
+
+    /// Shows the size of the `.wasm` file next to `compile.output`
+    #[structopt(author)]
+    WasmSize(OptShowWasmSize),
+
+    /// Lists the `languageId`s in the config that are not available on the service
+    #[structopt(author)]
+    UnreachableLanguages(OptShowUnreachableLanguages),
+
+    /// Lists every key read from `snowchains.dhall` with its type
+    #[structopt(author)]
+    AllConfigKeys(OptShowAllConfigKeys),
+}
+
+#[derive(StructOpt, Debug)]
+pub enum OptModify {
+    /// Modifies the `match` of test suites
+    #[structopt(author)]
+    Match(OptModifyMatch),
+
+    /// Modifies the timelimit of test suites
+    #[structopt(author)]
+    Timelimit(OptModifyTimelimit),
+}
+
+#[derive(StructOpt, Debug)]
+pub enum OptMigrate {
+    /// Converts the cookie jar into the format of another version
+    #[structopt(author)]
+    Cookies(OptMigrateCookies),
+}
+
+#[derive(StructOpt, Debug)]
+pub enum OptLang {
+    /// Pins a language to a problem
+    #[structopt(author)]
+    Set(OptLangSet),
 }
 
 impl Opt {
@@ -113,14 +289,49 @@ impl Opt {
             | Self::Participate(OptParticipate { color, .. })
             | Self::Retrieve(OptRetrieve::Languages(OptRetrieveLanguages { color, .. }))
             | Self::Retrieve(OptRetrieve::Testcases(OptRetrieveTestcases { color, .. }))
+            | Self::Retrieve(OptRetrieve::Problems(OptRetrieveProblems { color, .. }))
             | Self::Retrieve(OptRetrieve::SubmissionSummaries(OptRetrieveSubmissionSummaries {
                 color,
                 ..
             }))
             | Self::Download(OptRetrieveTestcases { color, .. })
             | Self::Watch(OptWatch::Submissions(OptWatchSubmissions { color, .. }))
+            | Self::Watch(OptWatch::Submission(OptWatchSubmission { color, .. }))
+            | Self::Open(OptOpen { color, .. })
+            | Self::Show(OptShow::Problems(OptRetrieveProblems { color, .. }))
+            | Self::Show(OptShow::CookieExpiry(OptShowCookieExpiry { color, .. }))
+            | Self::Show(OptShow::ProblemCategory(OptShowProblemCategory { color, .. }))
+            | Self::Show(OptShow::ProblemDifficulty(OptShowProblemDifficulty { color, .. }))
+            | Self::Show(OptShow::RatedRange(OptShowRatedRange { color, .. }))
+            | Self::Show(OptShow::TopSubmissions(OptShowTopSubmissions { color, .. }))
+            | Self::Show(OptShow::SubmissionScore(OptShowSubmissionScore { color, .. }))
+            | Self::Show(OptShow::SubmissionCode(OptShowSubmissionCode { color, .. }))
+            | Self::Show(OptShow::TotalAcProblems(OptShowTotalAcProblems { color, .. }))
+            | Self::Show(OptShow::TestCase(OptShowTestCase { color, .. }))
+            | Self::Show(OptShow::In(OptShowIn { color, .. }))
+            | Self::Show(OptShow::Expected(OptShowExpected { color, .. }))
+            | Self::Show(OptShow::LastJudgeSummary(OptShowLastJudgeSummary { color, .. }))
+            | Self::Show(OptShow::TestCaseHash(OptShowTestCaseHash { color, .. }))
+            | Self::Show(OptShow::TestCaseTimeLimit(OptShowTestCaseTimeLimit { color, .. }))
+            | Self::Show(OptShow::RecentProblems(OptShowRecentProblems { color, .. }))
+            | Self::Show(OptShow::CompileFlags(OptShowCompileFlags { color, .. }))
+            | Self::Show(OptShow::WasmSize(OptShowWasmSize { color, .. }))
+            | Self::Show(OptShow::UnreachableLanguages(OptShowUnreachableLanguages {
+                color,
+                ..
+            }))
+            | Self::Show(OptShow::AllConfigKeys(OptShowAllConfigKeys { color, .. }))
+            | Self::Modify(OptModify::Match(OptModifyMatch { color, .. }))
+            | Self::Modify(OptModify::Timelimit(OptModifyTimelimit { color, .. }))
+            | Self::Lang(OptLang::Set(OptLangSet { color, .. }))
             | Self::Judge(OptJudge { color, .. })
-            | Self::Submit(OptSubmit { color, .. }) => color,
+            | Self::Generate(OptGenerate { color, .. })
+            | Self::Stress(OptStress { color, .. })
+            | Self::Submit(OptSubmit { color, .. })
+            | Self::FlushOutbox(OptFlushOutbox { color, .. })
+            | Self::Validate(OptValidate { color, .. })
+            | Self::Migrate(OptMigrate::Cookies(OptMigrateCookies { color, .. }))
+            | Self::Completions(OptCompletions { color, .. }) => color,
             Self::Xtask(_) => crate::ColorChoice::Auto,
         }
     }
@@ -149,13 +360,53 @@ pub fn run<R: BufRead, W1: WriteColor, W2: WriteColor>(
         Opt::Participate(opt) => commands::participate::run(opt, ctx),
         Opt::Retrieve(OptRetrieve::Languages(opt)) => commands::retrieve_languages::run(opt, ctx),
         Opt::Retrieve(OptRetrieve::Testcases(opt)) => commands::retrieve_testcases::run(opt, ctx),
+        Opt::Retrieve(OptRetrieve::Problems(opt)) => commands::retrieve_problems::run(opt, ctx),
         Opt::Retrieve(OptRetrieve::SubmissionSummaries(opt)) => {
             commands::retrieve_submission_summaries::run(opt, ctx)
         }
         Opt::Download(opt) => commands::retrieve_testcases::run(opt, ctx),
         Opt::Watch(OptWatch::Submissions(opt)) => commands::watch_submissions::run(opt, ctx),
+        Opt::Watch(OptWatch::Submission(opt)) => commands::watch_submission::run(opt, ctx),
+        Opt::Open(opt) => commands::open::run(opt, ctx),
+        Opt::Show(OptShow::Problems(opt)) => commands::retrieve_problems::run(opt, ctx),
+        Opt::Show(OptShow::CookieExpiry(opt)) => commands::show_cookie_expiry::run(opt, ctx),
+        Opt::Show(OptShow::ProblemCategory(opt)) => commands::show_problem_category::run(opt, ctx),
+        Opt::Show(OptShow::ProblemDifficulty(opt)) => {
+            commands::show_problem_difficulty::run(opt, ctx)
+        }
+        Opt::Show(OptShow::RatedRange(opt)) => commands::show_rated_range::run(opt, ctx),
+        Opt::Show(OptShow::TopSubmissions(opt)) => commands::show_top_submissions::run(opt, ctx),
+        Opt::Show(OptShow::SubmissionScore(opt)) => commands::show_submission_score::run(opt, ctx),
+        Opt::Show(OptShow::SubmissionCode(opt)) => commands::show_submission_code::run(opt, ctx),
+        Opt::Show(OptShow::TotalAcProblems(opt)) => commands::show_total_ac_problems::run(opt, ctx),
+        Opt::Show(OptShow::TestCase(opt)) => commands::show_test_case::run(opt, ctx),
+        Opt::Show(OptShow::In(opt)) => commands::show_in::run(opt, ctx),
+        Opt::Show(OptShow::Expected(opt)) => commands::show_expected::run(opt, ctx),
+        Opt::Show(OptShow::LastJudgeSummary(opt)) => {
+            commands::show_last_judge_summary::run(opt, ctx)
+        }
+        Opt::Show(OptShow::TestCaseHash(opt)) => commands::show_test_case_hash::run(opt, ctx),
+        Opt::Show(OptShow::TestCaseTimeLimit(opt)) => {
+            commands::show_test_case_time_limit::run(opt, ctx)
+        }
+        Opt::Show(OptShow::RecentProblems(opt)) => commands::show_recent_problems::run(opt, ctx),
+        Opt::Show(OptShow::CompileFlags(opt)) => commands::show_compile_flags::run(opt, ctx),
+        Opt::Show(OptShow::WasmSize(opt)) => commands::show_wasm_size::run(opt, ctx),
+        Opt::Show(OptShow::UnreachableLanguages(opt)) => {
+            commands::show_unreachable_languages::run(opt, ctx)
+        }
+        Opt::Show(OptShow::AllConfigKeys(opt)) => commands::show_all_config_keys::run(opt, ctx),
+        Opt::Modify(OptModify::Match(opt)) => commands::modify_match::run(opt, ctx),
+        Opt::Modify(OptModify::Timelimit(opt)) => commands::modify_timelimit::run(opt, ctx),
+        Opt::Lang(OptLang::Set(opt)) => commands::lang_set::run(opt, ctx),
         Opt::Judge(opt) => commands::judge::run(opt, ctx),
+        Opt::Generate(opt) => commands::generate::run(opt, ctx),
+        Opt::Stress(opt) => commands::stress::run(opt, ctx),
         Opt::Submit(opt) => commands::submit::run(opt, ctx),
+        Opt::FlushOutbox(opt) => commands::flush_outbox::run(opt, ctx),
+        Opt::Validate(opt) => commands::validate::run(opt, ctx),
+        Opt::Migrate(OptMigrate::Cookies(opt)) => commands::migrate_cookies::run(opt, ctx),
         Opt::Xtask(opt) => commands::xtask::run(opt, ctx),
+        Opt::Completions(opt) => commands::completions::run(opt, ctx),
     }
 }
@@ -42,7 +42,9 @@ fn main() {
         })();
 
         if let Err(err) = result {
-            for (i, s) in format!("{:?}", err).splitn(2, "Caused by:\n").enumerate() {
+            let err = snowchains_core::web::redact(&format!("{:?}", err)).into_owned();
+
+            for (i, s) in err.splitn(2, "Caused by:\n").enumerate() {
                 let _ = stderr.set_color(color_spec!(Bold, Fg(Color::Red)));
 
                 if i == 0 {
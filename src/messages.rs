@@ -0,0 +1,142 @@
+//! User-facing messages in English and Japanese.
+//!
+//! Error messages and their chains are not translated.
+
+use std::env;
+use strum::EnumIter;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Lang {
+    En,
+    Ja,
+}
+
+impl Lang {
+    /// Reads `SNOWCHAINS_LANG` (`en` or `ja`), then the locale from `LC_ALL`, `LC_MESSAGES`, and
+    /// `LANG`.
+    pub(crate) fn detect() -> Self {
+        let var = |name| env::var(name).ok().filter(|v| !v.is_empty());
+
+        match var("SNOWCHAINS_LANG").as_deref() {
+            Some("ja") => return Self::Ja,
+            Some("en") => return Self::En,
+            _ => {}
+        }
+
+        match var("LC_ALL")
+            .or_else(|| var("LC_MESSAGES"))
+            .or_else(|| var("LANG"))
+        {
+            Some(locale) if locale.starts_with("ja") => Self::Ja,
+            _ => Self::En,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum Message {
+    UsernamePrompt,
+    HandleOrEmailPrompt,
+    PasswordPrompt,
+    CodeforcesApiKeyPrompt,
+    CodeforcesApiSecretPrompt,
+    YukicoderApiKeyPrompt,
+    Warning,
+    SubmitAnywayPrompt,
+    ReplaceWithLinkPrompt,
+    OverwritePrompt,
+    RunningTests,
+    SavedTo,
+    SavedCheckerTo,
+    NoCheckerFound,
+    NoTestCases,
+    OneTestCase,
+    /// `{}` is the number.
+    TestCases,
+    InteractiveProblem,
+    UnsubmittableProblem,
+    ScoringProblem,
+}
+
+impl Message {
+    pub(crate) fn get(self) -> &'static str {
+        self.localize(Lang::detect())
+    }
+
+    /// Replaces the first `{}` with `arg`.
+    pub(crate) fn format(self, arg: impl ToString) -> String {
+        self.get().replacen("{}", &arg.to_string(), 1)
+    }
+
+    fn localize(self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::En => self.en(),
+            Lang::Ja => self.ja().unwrap_or_else(|| self.en()),
+        }
+    }
+
+    fn en(self) -> &'static str {
+        match self {
+            Self::UsernamePrompt => "Username: ",
+            Self::HandleOrEmailPrompt => "Handle/Email: ",
+            Self::PasswordPrompt => "Password: ",
+            Self::CodeforcesApiKeyPrompt => "Codeforces `api_key`: ",
+            Self::CodeforcesApiSecretPrompt => "Codeforces `api_secret`: ",
+            Self::YukicoderApiKeyPrompt => "yukicoder API key: ",
+            Self::Warning => "warning:",
+            Self::SubmitAnywayPrompt => "Submit anyway? [y/N] ",
+            Self::ReplaceWithLinkPrompt => "Replace it with a link to the shared one? [y/N] ",
+            Self::OverwritePrompt => "Overwrite it? [y/N] ",
+            Self::RunningTests => "Running the tests...",
+            Self::SavedTo => "Saved to",
+            Self::SavedCheckerTo => "Saved the checker to",
+            Self::NoCheckerFound => "No checker found",
+            Self::NoTestCases => "no test cases",
+            Self::OneTestCase => "1 test case",
+            Self::TestCases => "{} test cases",
+            Self::InteractiveProblem => "interactive problem",
+            Self::UnsubmittableProblem => "unsubmittable problem",
+            Self::ScoringProblem => "scoring problem",
+        }
+    }
+
+    /// `None` falls back to English.
+    fn ja(self) -> Option<&'static str> {
+        match self {
+            Self::UsernamePrompt => Some("ユーザー名: "),
+            Self::HandleOrEmailPrompt => Some("ハンドルまたはメールアドレス: "),
+            Self::PasswordPrompt => Some("パスワード: "),
+            Self::CodeforcesApiKeyPrompt => Some("Codeforces の `api_key`: "),
+            Self::CodeforcesApiSecretPrompt => Some("Codeforces の `api_secret`: "),
+            Self::YukicoderApiKeyPrompt => Some("yukicoder の API キー: "),
+            Self::Warning => Some("警告:"),
+            Self::SubmitAnywayPrompt => Some("このまま提出しますか? [y/N] "),
+            Self::ReplaceWithLinkPrompt => Some("共有のファイルへのリンクに置き換えますか? [y/N] "),
+            Self::OverwritePrompt => Some("上書きしますか? [y/N] "),
+            Self::RunningTests => Some("テストを実行しています..."),
+            Self::SavedTo => Some("保存先:"),
+            Self::SavedCheckerTo => Some("チェッカーの保存先:"),
+            Self::NoCheckerFound => Some("チェッカーが見つかりません"),
+            Self::NoTestCases => Some("テストケースなし"),
+            Self::OneTestCase => Some("テストケース 1 個"),
+            Self::TestCases => Some("テストケース {} 個"),
+            Self::InteractiveProblem => Some("インタラクティブ問題"),
+            Self::UnsubmittableProblem => Some("提出できない問題"),
+            Self::ScoringProblem => Some("スコア問題"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lang, Message};
+    use strum::IntoEnumIterator as _;
+
+    #[test]
+    fn placeholders() {
+        for message in Message::iter() {
+            let count = |lang| message.localize(lang).matches("{}").count();
+            assert_eq!(count(Lang::En), count(Lang::Ja), "{:?}", message);
+        }
+    }
+}
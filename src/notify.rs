@@ -0,0 +1,172 @@
+//! Webhooks configured in `notifications.webhook`.
+
+use crate::config::{Webhook, WebhookEvent, WebhookFormat};
+use anyhow::{anyhow, ensure};
+use serde_json::json;
+use snowchains_core::web::PlatformKind;
+use std::time::Duration;
+use url::Url;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub(crate) struct Notification<'a> {
+    pub(crate) event: WebhookEvent,
+    pub(crate) service: PlatformKind,
+    pub(crate) contest: Option<&'a str>,
+    pub(crate) problem: &'a str,
+    pub(crate) verdict: String,
+    pub(crate) score: Option<String>,
+    pub(crate) time: Option<Duration>,
+    pub(crate) url: Option<&'a Url>,
+}
+
+/// Posts `notification` if `webhook` subscribes to its event.
+///
+/// Errors are meant to be shown as warnings. They do not contain the URL of the webhook, which
+/// usually has a secret in it.
+pub(crate) fn post(webhook: &Webhook, notification: &Notification<'_>) -> anyhow::Result<()> {
+    if !webhook.events.contains(&notification.event) {
+        return Ok(());
+    }
+
+    let url = redact_url(&webhook.url);
+
+    let res = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .build()?
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload(webhook.format, notification).to_string())
+        .send()
+        .map_err(|err| {
+            // The `Display` of `reqwest::Error` contains the full URL.
+            let reason = if err.is_timeout() {
+                "timed out"
+            } else if err.is_connect() {
+                "could not connect"
+            } else {
+                "request failed"
+            };
+            anyhow!("Could not notify `{}`: {}", url, reason)
+        })?;
+
+    ensure!(
+        res.status().is_success(),
+        "Could not notify `{}`: {}",
+        url,
+        res.status(),
+    );
+    Ok(())
+}
+
+fn payload(format: WebhookFormat, notification: &Notification<'_>) -> serde_json::Value {
+    let Notification {
+        event,
+        service,
+        contest,
+        problem,
+        verdict,
+        score,
+        time,
+        url,
+    } = notification;
+
+    let summary = || {
+        let mut text = match contest {
+            Some(contest) => format!("{}/{}: {}", contest, problem, verdict),
+            None => format!("{}: {}", problem, verdict),
+        };
+        if let Some(score) = score {
+            text += &format!(", score {}", score);
+        }
+        if let Some(time) = time {
+            text += &format!(", {} ms", time.as_millis());
+        }
+        text
+    };
+
+    match format {
+        WebhookFormat::Discord => {
+            let mut text = summary();
+            if let Some(url) = url {
+                text += &format!(" <{}>", url);
+            }
+            json!({ "content": text })
+        }
+        WebhookFormat::Slack => {
+            let mut text = summary();
+            if let Some(url) = url {
+                text += &format!(" <{}|submission>", url);
+            }
+            json!({ "text": text })
+        }
+        WebhookFormat::Json => json!({
+            "event": match event {
+                WebhookEvent::Submit => "submit",
+                WebhookEvent::Judge => "judge",
+            },
+            "service": service.to_kebab_case_str(),
+            "contest": contest,
+            "problem": problem,
+            "verdict": verdict,
+            "score": score,
+            "time_ms": time.map(|t| t.as_millis() as u64),
+            "url": url.map(Url::as_str),
+        }),
+    }
+}
+
+/// Keeps only the scheme and the host, since webhook URLs carry their tokens in the path.
+pub(crate) fn redact_url(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(url) => format!("{}://{}/***", url.scheme(), url.host_str().unwrap_or("")),
+        Err(_) => "***".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{WebhookEvent, WebhookFormat};
+    use serde_json::json;
+    use snowchains_core::web::PlatformKind;
+    use std::time::Duration;
+
+    #[test]
+    fn redact_url() {
+        assert_eq!(
+            super::redact_url("https://discord.com/api/webhooks/1234/s3cr3t"),
+            "https://discord.com/***",
+        );
+        assert_eq!(super::redact_url("s3cr3t"), "***");
+    }
+
+    #[test]
+    fn payload() {
+        let url = "https://atcoder.jp/contests/abc100/submissions/1"
+            .parse()
+            .unwrap();
+        let notification = super::Notification {
+            event: WebhookEvent::Submit,
+            service: PlatformKind::Atcoder,
+            contest: Some("abc100"),
+            problem: "a",
+            verdict: "AC".to_owned(),
+            score: Some("100".to_owned()),
+            time: Some(Duration::from_millis(12)),
+            url: Some(&url),
+        };
+
+        assert_eq!(
+            super::payload(WebhookFormat::Discord, &notification),
+            json!({
+                "content": "abc100/a: AC, score 100, 12 ms \
+                            <https://atcoder.jp/contests/abc100/submissions/1>",
+            }),
+        );
+        assert_eq!(
+            super::payload(WebhookFormat::Json, &notification)["time_ms"],
+            json!(12),
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Submissions queued by `submit --queue`.
+//!
+//! Each one is stored as `.snowchains/outbox/{time}-{service}-{contest}-{problem}.json`. It is
+//! moved to `sending/` while being submitted, and then to `sent/` with the submission URL, or back
+//! if the submission fails. It is moved to `expired/` instead if the contest has ended.
+//!
+//! Only `submit` and `flush-outbox` flush the outbox. Other commands that access the network do
+//! not. A process flushing it holds `.snowchains/outbox/.lock`.
+
+use anyhow::{bail, Context as _};
+use chrono::{DateTime, Local, Utc};
+use fs2::FileExt as _;
+use serde::{Deserialize, Serialize};
+use snowchains_core::{color_spec, web::PlatformKind};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+};
+use termcolor::{Color, WriteColor};
+use url::Url;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QueuedSubmission {
+    pub(crate) service: PlatformKind,
+    pub(crate) contest: Option<String>,
+    pub(crate) problem: String,
+    pub(crate) src: String,
+    pub(crate) language_id: String,
+    /// The exact code to submit.
+    pub(crate) code: String,
+    pub(crate) queued_at: DateTime<Utc>,
+    /// Recorded when queued. `None` means that there is no known end, which is warned both when
+    /// queueing and when flushing.
+    pub(crate) contest_end: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) submission_url: Option<Url>,
+}
+
+impl QueuedSubmission {
+    pub(crate) fn save(&self, workspace: &Path) -> anyhow::Result<PathBuf> {
+        let file_name = format!(
+            "{}-{}-{}-{}.json",
+            self.queued_at
+                .with_timezone(&Local)
+                .format("%Y%m%dT%H%M%S%.3f"),
+            self.service.to_kebab_case_str(),
+            self.contest.as_deref().unwrap_or(""),
+            self.problem,
+        );
+        let path = outbox_dir(workspace).join(file_name);
+        crate::fs::write_json(&path, self, true)?;
+        Ok(path)
+    }
+
+    fn target(&self) -> String {
+        format!(
+            "{} {}{}",
+            self.service.to_kebab_case_str(),
+            self.contest
+                .as_ref()
+                .map(|c| format!("{}/", c))
+                .unwrap_or_default(),
+            self.problem,
+        )
+    }
+}
+
+/// Submits the queued submissions in order, stopping at the first failure.
+///
+/// The failed one and the rest stay in the outbox.
+pub(crate) fn flush(
+    workspace: &Path,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Sized, impl WriteColor>,
+) -> anyhow::Result<()> {
+    flush_with(workspace, Utc::now(), shell, |queued, shell| {
+        let outcome = crate::commands::submit::submit(
+            queued.service,
+            queued.contest.clone(),
+            queued.problem.clone(),
+            queued.language_id.clone(),
+            queued.code.clone(),
+            false,
+            shell,
+        )?;
+        Ok(outcome.submission_url)
+    })
+}
+
+/// [`flush`] for `submit`. Failures are only warned.
+pub(crate) fn flush_if_any(
+    workspace: &Path,
+    shell: &mut crate::shell::Shell<impl BufRead, impl Sized, impl WriteColor>,
+) -> io::Result<()> {
+    if !matches!(queued(workspace), Ok(queued) if !queued.is_empty()) {
+        return Ok(());
+    }
+    if let Err(err) = flush(workspace, shell) {
+        shell.warn(format!("{:#}", err))?;
+    }
+    Ok(())
+}
+
+fn flush_with<R: BufRead, W1, W2: WriteColor>(
+    workspace: &Path,
+    now: DateTime<Utc>,
+    shell: &mut crate::shell::Shell<R, W1, W2>,
+    mut submit: impl FnMut(
+        &QueuedSubmission,
+        &mut crate::shell::Shell<R, W1, W2>,
+    ) -> anyhow::Result<Url>,
+) -> anyhow::Result<()> {
+    let _lock = OutboxLock::acquire(workspace)?;
+
+    for path in json_files(&outbox_dir(workspace).join("sending"))? {
+        shell.warn(format!(
+            "`{}` may have been submitted by an interrupted flush. Check it, and move it back to \
+             `{}` to submit it again",
+            path.display(),
+            outbox_dir(workspace).display(),
+        ))?;
+    }
+
+    for path in queued(workspace)? {
+        let mut queued = crate::fs::read_json::<QueuedSubmission, _>(&path)?;
+
+        match queued.contest_end {
+            Some(end) if end <= now => {
+                let dest = move_to(workspace, &path, "expired")?;
+                shell.warn(format!(
+                    "{} was not submitted since the contest has ended. Moved to `{}`",
+                    queued.target(),
+                    dest.display(),
+                ))?;
+                continue;
+            }
+            Some(_) => {}
+            None => shell.warn(format!(
+                "{} has no recorded contest end. Submitting it anyway",
+                queued.target(),
+            ))?,
+        }
+
+        // Moved out of the outbox before submitting, so that a process killed while submitting it
+        // does not leave it to be submitted again. An error from `submit` is regarded as not
+        // submitted.
+        let sending = move_to(workspace, &path, "sending")?;
+
+        let submission_url = match submit(&queued, shell) {
+            Ok(submission_url) => submission_url,
+            Err(err) => {
+                crate::fs::rename(&sending, &path)?;
+                return Err(err.context(format!(
+                    "Could not submit {}. It stays in `{}`",
+                    queued.target(),
+                    path.display(),
+                )));
+            }
+        };
+
+        let dest = move_to(workspace, &sending, "sent")?;
+        queued.submission_url = Some(submission_url.clone());
+        if let Err(err) = crate::fs::write_json(&dest, &queued, false) {
+            shell.warn(format!(
+                "Could not record the submission URL in `{}`: {:#}",
+                dest.display(),
+                err,
+            ))?;
+        }
+
+        shell
+            .stderr
+            .set_color(color_spec!(Bold, Fg(Color::Green)))?;
+        write!(shell.stderr, "Submitted")?;
+        shell.stderr.reset()?;
+        writeln!(
+            shell.stderr,
+            " {} queued at {}: {}",
+            queued.target(),
+            queued.queued_at.with_timezone(&Local),
+            submission_url,
+        )?;
+        shell.stderr.flush()?;
+    }
+    Ok(())
+}
+
+/// Paths of the queued submissions, oldest first.
+fn queued(workspace: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    json_files(&outbox_dir(workspace))
+}
+
+fn json_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut paths = vec![];
+    for entry in
+        std::fs::read_dir(dir).with_context(|| format!("Could not read `{}`", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() && path.extension() == Some("json".as_ref()) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn move_to(workspace: &Path, path: &Path, subdir: &str) -> anyhow::Result<PathBuf> {
+    let dir = outbox_dir(workspace).join(subdir);
+    crate::fs::create_dir_all(&dir)?;
+    let dest = dir.join(path.file_name().unwrap_or_default());
+    crate::fs::rename(path, &dest)?;
+    Ok(dest)
+}
+
+fn outbox_dir(workspace: &Path) -> PathBuf {
+    workspace.join(".snowchains").join("outbox")
+}
+
+/// Exclusive lock on the outbox, released on drop.
+struct OutboxLock(File);
+
+impl OutboxLock {
+    fn acquire(workspace: &Path) -> anyhow::Result<Self> {
+        let path = outbox_dir(workspace).join(".lock");
+
+        crate::fs::create_dir_all(outbox_dir(workspace))?;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Could not open `{}`", path.display()))?;
+
+        if let Err(err) = file.try_lock_exclusive() {
+            if err.kind() != fs2::lock_contended_error().kind() {
+                return Err(err).with_context(|| format!("Could not lock `{}`", path.display()));
+            }
+            bail!("Another process is flushing the outbox");
+        }
+
+        Ok(Self(file))
+    }
+}
+
+impl Drop for OutboxLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutboxLock, QueuedSubmission};
+    use crate::shell::{Shell, TtyOrPiped};
+    use anyhow::anyhow;
+    use chrono::{DateTime, Duration, TimeZone as _, Utc};
+    use snowchains_core::web::PlatformKind;
+    use std::{path::Path, process::Stdio};
+    use termcolor::NoColor;
+
+    fn now() -> DateTime<Utc> {
+        Utc.ymd(2021, 3, 20).and_hms(13, 0, 0)
+    }
+
+    fn queue(workspace: &Path, problem: &str, minutes_ago: i64, end: Option<DateTime<Utc>>) {
+        QueuedSubmission {
+            service: PlatformKind::Atcoder,
+            contest: Some("abc196".to_owned()),
+            problem: problem.to_owned(),
+            src: format!("{}.rs", problem),
+            language_id: "4050".to_owned(),
+            code: "fn main() {}\n".to_owned(),
+            queued_at: now() - Duration::minutes(minutes_ago),
+            contest_end: end,
+            submission_url: None,
+        }
+        .save(workspace)
+        .unwrap();
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .map(|e| e.unwrap().path())
+                    .filter(|p| p.is_file() && p.extension() == Some("json".as_ref()))
+                    .map(|p| p.file_name().unwrap().to_str().unwrap().to_owned())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    fn flush(
+        workspace: &Path,
+        mut submit: impl FnMut(&QueuedSubmission) -> anyhow::Result<()>,
+    ) -> (anyhow::Result<()>, String) {
+        let mut shell = Shell {
+            stdin: TtyOrPiped::Piped(&b""[..]),
+            stdout: Vec::<u8>::new(),
+            stderr: NoColor::new(vec![]),
+            stderr_tty: false,
+            stdin_process_redirection: Stdio::null,
+            stdout_process_redirection: Stdio::null,
+            stderr_process_redirection: Stdio::null,
+        };
+        let result = super::flush_with(workspace, now(), &mut shell, |queued, _| {
+            submit(queued)?;
+            Ok(format!(
+                "https://atcoder.jp/contests/abc196/submissions/{}",
+                queued.problem
+            )
+            .parse()?)
+        });
+        let stderr = String::from_utf8(shell.stderr.into_inner()).unwrap();
+        (result, stderr)
+    }
+
+    #[test]
+    fn submits_in_order() {
+        let workspace = tempfile::tempdir().unwrap();
+        let end = Some(now() + Duration::hours(1));
+        queue(workspace.path(), "b", 2, end);
+        queue(workspace.path(), "c", 1, end);
+        queue(workspace.path(), "a", 3, end);
+
+        let mut submitted = vec![];
+        let (result, _) = flush(workspace.path(), |queued| {
+            submitted.push(queued.problem.clone());
+            Ok(())
+        });
+        result.unwrap();
+
+        assert_eq!(["a", "b", "c"], *submitted);
+
+        let outbox = workspace.path().join(".snowchains").join("outbox");
+        assert!(file_names(&outbox).is_empty());
+
+        let sent = file_names(&outbox.join("sent"));
+        assert_eq!(3, sent.len());
+        let first = crate::fs::read_json::<QueuedSubmission, _>(outbox.join("sent").join(&sent[0]))
+            .unwrap();
+        assert_eq!(
+            "https://atcoder.jp/contests/abc196/submissions/a",
+            first.submission_url.unwrap().as_str(),
+        );
+    }
+
+    #[test]
+    fn expires_after_contest_end() {
+        let workspace = tempfile::tempdir().unwrap();
+        queue(workspace.path(), "a", 2, Some(now() - Duration::minutes(1)));
+        queue(workspace.path(), "b", 1, None);
+
+        let mut submitted = vec![];
+        let (result, stderr) = flush(workspace.path(), |queued| {
+            submitted.push(queued.problem.clone());
+            Ok(())
+        });
+        result.unwrap();
+
+        assert_eq!(["b"], *submitted);
+        assert!(stderr.contains("the contest has ended"), "{}", stderr);
+        assert!(stderr.contains("no recorded contest end"), "{}", stderr);
+
+        let outbox = workspace.path().join(".snowchains").join("outbox");
+        assert_eq!(1, file_names(&outbox.join("expired")).len());
+        assert_eq!(1, file_names(&outbox.join("sent")).len());
+    }
+
+    #[test]
+    fn stays_queued_on_failure() {
+        let workspace = tempfile::tempdir().unwrap();
+        let end = Some(now() + Duration::hours(1));
+        queue(workspace.path(), "a", 3, end);
+        queue(workspace.path(), "b", 2, end);
+        queue(workspace.path(), "c", 1, end);
+
+        let mut submitted = vec![];
+        let (result, _) = flush(workspace.path(), |queued| {
+            if queued.problem == "b" {
+                return Err(anyhow!("offline"));
+            }
+            submitted.push(queued.problem.clone());
+            Ok(())
+        });
+        assert!(result.is_err());
+        assert_eq!(["a"], *submitted);
+
+        let outbox = workspace.path().join(".snowchains").join("outbox");
+        assert_eq!(2, file_names(&outbox).len());
+        assert_eq!(1, file_names(&outbox.join("sent")).len());
+        assert!(file_names(&outbox.join("sending")).is_empty());
+    }
+
+    #[test]
+    fn moved_before_submitting() {
+        let workspace = tempfile::tempdir().unwrap();
+        queue(workspace.path(), "a", 1, Some(now() + Duration::hours(1)));
+
+        let outbox = workspace.path().join(".snowchains").join("outbox");
+
+        let (result, _) = flush(workspace.path(), |_| {
+            assert!(file_names(&outbox).is_empty());
+            assert_eq!(1, file_names(&outbox.join("sending")).len());
+            Ok(())
+        });
+        result.unwrap();
+
+        // Left by a process killed while submitting.
+        std::fs::rename(
+            outbox
+                .join("sent")
+                .join(&file_names(&outbox.join("sent"))[0]),
+            outbox.join("sending").join("interrupted.json"),
+        )
+        .unwrap();
+
+        let (result, stderr) = flush(workspace.path(), |_| panic!("should not be submitted"));
+        result.unwrap();
+        assert!(stderr.contains("interrupted.json"), "{}", stderr);
+    }
+
+    #[test]
+    fn locked() {
+        let workspace = tempfile::tempdir().unwrap();
+        queue(workspace.path(), "a", 1, Some(now() + Duration::hours(1)));
+
+        let _lock = OutboxLock::acquire(workspace.path()).unwrap();
+
+        let (result, _) = flush(workspace.path(), |_| panic!("should not be submitted"));
+        assert!(result.is_err());
+    }
+}
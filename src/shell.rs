@@ -1,5 +1,8 @@
 use indicatif::ProgressDrawTarget;
-use snowchains_core::{color_spec, web::StatusCodeColor};
+use snowchains_core::{
+    color_spec,
+    web::{redact, StatusCodeColor},
+};
 use std::{
     env, fmt,
     io::{self, BufRead, Stdin, StdinLock, Write},
@@ -41,7 +44,7 @@ impl<R, W1, W2: WriteColor> Shell<R, W1, W2> {
     pub(crate) fn warn(&mut self, message: impl fmt::Display) -> io::Result<()> {
         self.stderr
             .set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
-        write!(self.stderr, "warning:")?;
+        write!(self.stderr, "{}", crate::messages::Message::Warning.get())?;
         self.stderr.reset()?;
         writeln!(self.stderr, " {}", message)?;
         self.stderr.flush()
@@ -55,6 +58,12 @@ impl<R: BufRead, W1, W2: Write> Shell<R, W1, W2> {
         self.stdin.read_reply()
     }
 
+    /// Asks a yes/no question. Anything other than "y" or "yes" is a no.
+    pub(crate) fn confirm(&mut self, prompt: &'static str) -> io::Result<bool> {
+        let reply = self.read_reply(prompt)?;
+        Ok(matches!(&*reply.trim().to_lowercase(), "y" | "yes"))
+    }
+
     pub(crate) fn read_password(&mut self, prompt: &'static str) -> io::Result<String> {
         write!(self.stderr, "{}", prompt)?;
         self.stderr.flush()?;
@@ -93,7 +102,7 @@ impl<R, W1, W2: WriteColor> snowchains_core::web::Shell for Shell<R, W1, W2> {
         write!(self.stderr, " ")?;
 
         self.stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
-        write!(self.stderr, "{}", req.url())?;
+        write!(self.stderr, "{}", redact(req.url().as_str()))?;
         self.stderr.reset()?;
 
         write!(self.stderr, " ... ")?;
@@ -0,0 +1,199 @@
+//! Test suites shared between contests.
+//!
+//! AtCoder often holds an ABC and an ARC with common problems. A problem whose screen name belongs
+//! to another contest (e.g. `arc165_a` in `abc322`) is stored once as
+//! `.snowchains/tests/{service}/_shared/{screen_name}.yml`, and the path in each contest links to
+//! it. The link is a symlink, or on Windows a pointer file with a single `pointer` field.
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use snowchains_core::web::PlatformKind;
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+const SHARED_DIR_NAME: &str = "_shared";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Pointer {
+    pointer: PathBuf,
+}
+
+pub(crate) fn shared_path(base_dir: &Path, service: PlatformKind, screen_name: &str) -> PathBuf {
+    crate::judge::test_suite_dir(base_dir, service, Some(SHARED_DIR_NAME))
+        .join(screen_name)
+        .with_extension("yml")
+}
+
+/// Whether the problem `screen_name` in `contest` comes from another contest.
+pub(crate) fn is_shared(contest: &str, screen_name: &str) -> bool {
+    let prefix = format!("{}_", contest.to_lowercase().replace('-', "_"));
+    !screen_name.to_lowercase().starts_with(&prefix)
+}
+
+/// Guesses the path in the contest that `screen_name` comes from (e.g. `arc165/a.yml`).
+pub(crate) fn home_path(
+    base_dir: &Path,
+    service: PlatformKind,
+    screen_name: &str,
+) -> Option<PathBuf> {
+    let i = screen_name.rfind('_')?;
+    let (contest, index) = (&screen_name[..i], &screen_name[i + 1..]);
+    Some(
+        crate::judge::test_suite_dir(base_dir, service, Some(contest))
+            .join(index)
+            .with_extension("yml"),
+    )
+}
+
+/// Returns the file that `path` links to, or `None` if `path` is a real file or does not exist.
+pub(crate) fn link_target(path: &Path) -> anyhow::Result<Option<PathBuf>> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Could not read `{}`", path.display()))
+        }
+    };
+
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+
+    if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(path)
+            .with_context(|| format!("Could not read the link `{}`", path.display()))?;
+        return Ok(Some(normalize(&parent.join(target))));
+    }
+
+    let content = crate::fs::read_to_string(path)?;
+    Ok(serde_yaml::from_str::<Pointer>(&content)
+        .ok()
+        .map(|Pointer { pointer }| normalize(&parent.join(pointer))))
+}
+
+/// Follows the link if `path` is one.
+pub(crate) fn resolve(path: &Path) -> anyhow::Result<PathBuf> {
+    Ok(link_target(path)?.unwrap_or_else(|| path.to_owned()))
+}
+
+/// Makes `path` link to `target`, replacing the link already there.
+///
+/// `path` must not be a real file.
+pub(crate) fn link(path: &Path, target: &Path) -> anyhow::Result<()> {
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let relative = relative(target, parent);
+
+    if link_target(path)?.is_some() {
+        crate::fs::remove_file(path)?;
+    }
+    crate::fs::create_dir_all(parent)?;
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&relative, path)
+            .with_context(|| format!("Could not create a link at `{}`", path.display()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let pointer = Pointer { pointer: relative };
+        crate::fs::write(path, serde_yaml::to_string(&pointer)?, false)
+    }
+}
+
+/// Lists the links under `dir` with their targets, in the path order.
+pub(crate) fn find_links(dir: &Path) -> anyhow::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut links = vec![];
+    let mut dirs = vec![dir.to_owned()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("Could not read `{}`", dir.display()))
+            }
+        };
+
+        for entry in entries {
+            let entry = entry.with_context(|| format!("Could not read `{}`", dir.display()))?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                dirs.push(path);
+            } else if matches!(path.extension(), Some(e) if e == "yml") {
+                if let Some(target) = link_target(&path)? {
+                    links.push((path, target));
+                }
+            }
+        }
+    }
+
+    links.sort();
+    Ok(links)
+}
+
+/// `target` relative to `dir`. Both of them are expected to be under the same workspace.
+fn relative(target: &Path, dir: &Path) -> PathBuf {
+    let (target, dir) = (normalize(target), normalize(dir));
+
+    let common = target
+        .components()
+        .zip(dir.components())
+        .take_while(|(c1, c2)| c1 == c2)
+        .count();
+
+    dir.components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .chain(target.components().skip(common))
+        .collect()
+}
+
+/// Removes `.` and `..` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut ret = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(ret.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                ret.pop();
+            }
+            component => ret.push(component),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn is_shared() {
+        assert!(!super::is_shared("abc322", "abc322_a"));
+        assert!(!super::is_shared("ABC322", "abc322_a"));
+        assert!(!super::is_shared("cf17-final-open", "cf17_final_open_a"));
+        assert!(super::is_shared("abc322", "arc165_a"));
+    }
+
+    #[test]
+    fn relative() {
+        let f = |target, dir| super::relative(Path::new(target), Path::new(dir));
+        assert_eq!(
+            f(
+                "/ws/tests/atcoder/_shared/arc165_a.yml",
+                "/ws/tests/atcoder/abc322"
+            ),
+            Path::new("../_shared/arc165_a.yml"),
+        );
+        assert_eq!(
+            f(
+                "/ws/tests/atcoder/abc322/../_shared/x.yml",
+                "/ws/tests/atcoder/abc322/."
+            ),
+            Path::new("../_shared/x.yml"),
+        );
+    }
+}
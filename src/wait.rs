@@ -0,0 +1,120 @@
+use anyhow::bail;
+use chrono::{DateTime, Local, Utc};
+use snowchains_core::color_spec;
+use std::{
+    cmp,
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+use termcolor::{Color, WriteColor};
+
+/// Blocks until `deadline` while showing a countdown.
+///
+/// Ctrl-C interrupts the wait with an error. Once the wait is over, Ctrl-C exits the process
+/// immediately as usual.
+pub(crate) fn wait_until(
+    deadline: DateTime<Utc>,
+    mut stderr: impl WriteColor,
+    tty: bool,
+) -> anyhow::Result<()> {
+    if Utc::now() >= deadline {
+        return Ok(());
+    }
+
+    let rx = ctrl_c_channel()?;
+
+    stderr.set_color(color_spec!(Bold))?;
+    write!(stderr, "Waiting until {}", deadline.with_timezone(&Local))?;
+    stderr.reset()?;
+    writeln!(stderr)?;
+    stderr.flush()?;
+
+    loop {
+        let remaining = match (deadline - Utc::now()).to_std() {
+            Ok(remaining) if remaining > Duration::from_secs(0) => remaining,
+            _ => break,
+        };
+
+        if tty {
+            write!(stderr, "\r\x1b[2K")?;
+            stderr.set_color(color_spec!(Fg(Color::Cyan)))?;
+            write!(stderr, "{}", format_remaining(remaining))?;
+            stderr.reset()?;
+            write!(stderr, " left (Ctrl-C to cancel)")?;
+            stderr.flush()?;
+        }
+
+        let timeout = cmp::min(remaining, Duration::from_secs(1));
+
+        match rx.recv_timeout(timeout) {
+            Ok(()) => {
+                if tty {
+                    writeln!(stderr)?;
+                    stderr.flush()?;
+                }
+                bail!("Interrupted while waiting");
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // Failed to listen for Ctrl-C.
+            Err(RecvTimeoutError::Disconnected) => thread::sleep(timeout),
+        }
+    }
+
+    if tty {
+        write!(stderr, "\r\x1b[2K")?;
+        stderr.flush()?;
+    }
+    Ok(())
+}
+
+/// Sends `()` on each Ctrl-C.
+///
+/// The signal handler stays registered for the rest of the process. Once the receiver is dropped,
+/// Ctrl-C exits the process immediately as usual.
+pub(crate) fn ctrl_c_channel() -> anyhow::Result<Receiver<()>> {
+    let (tx, rx) = mpsc::channel();
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()?;
+
+    rt.spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if tx.send(()).is_err() {
+                std::process::exit(130);
+            }
+        }
+    });
+
+    Box::leak(Box::new(rt));
+    Ok(rx)
+}
+
+fn format_remaining(remaining: Duration) -> String {
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[test]
+    fn format_remaining() {
+        let f = |millis| super::format_remaining(Duration::from_millis(millis));
+        assert_eq!(f(1), "1s");
+        assert_eq!(f(59_000), "59s");
+        assert_eq!(f(60_000), "1m 00s");
+        assert_eq!(f(3_599_500), "1h 00m 00s");
+        assert_eq!(f(3_723_000), "1h 02m 03s");
+    }
+}
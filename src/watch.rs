@@ -0,0 +1,127 @@
+//! `judge --watch`.
+//!
+//! Files are polled for their modification times, which is enough for a handful of source files.
+
+use snowchains_core::color_spec;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{mpsc::RecvTimeoutError, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+use termcolor::{Color, WriteColor};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The watched files and their modification times when the current run started.
+static WATCHED: Mutex<Vec<(PathBuf, Option<SystemTime>)>> = Mutex::new(Vec::new());
+
+/// Passed to the judge in place of `tokio::signal::ctrl_c`.
+///
+/// Also completes with an error when a watched file changes, which cancels the running test cases.
+/// Nothing is watched outside of `judge --watch`.
+pub(crate) async fn ctrl_c_or_change() -> io::Result<()> {
+    let mut ctrl_c = Box::pin(tokio::signal::ctrl_c());
+
+    loop {
+        if let Ok(result) = tokio::time::timeout(POLL_INTERVAL, &mut ctrl_c).await {
+            return result;
+        }
+        if changed() {
+            return Err(io::Error::other("A watched file changed"));
+        }
+    }
+}
+
+/// Runs `judge` now and again whenever one of `paths` changes, until Ctrl-C.
+///
+/// The screen is cleared before each run unless the previous one failed, so that the failure
+/// stays visible above the next run.
+pub(crate) fn judge_on_change<W1: WriteColor, W2: WriteColor>(
+    paths: Vec<PathBuf>,
+    mut stdout: W1,
+    mut stderr: W2,
+    tty: bool,
+    mut judge: impl FnMut(&mut W1, &mut W2) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let ctrl_c = crate::wait::ctrl_c_channel()?;
+
+    let mut last_failed = false;
+
+    loop {
+        *WATCHED.lock().unwrap() = paths.iter().map(|p| (p.clone(), modified(p))).collect();
+
+        if tty && !last_failed {
+            write!(stderr, "\x1b[2J\x1b[H")?;
+        } else {
+            writeln!(stderr)?;
+        }
+        stderr.flush()?;
+
+        let result = judge(&mut stdout, &mut stderr);
+
+        last_failed = false;
+
+        match result {
+            Err(_) if changed() => {}
+            Err(_) if ctrl_c.try_recv().is_ok() => return Ok(()),
+            Err(err) => {
+                last_failed = true;
+                stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+                write!(stderr, "Error:")?;
+                stderr.reset()?;
+                writeln!(stderr, " {:#}", err)?;
+            }
+            Ok(()) => {}
+        }
+
+        if !changed() {
+            writeln!(stderr)?;
+            stderr.set_color(color_spec!(Bold))?;
+            write!(stderr, "Watching")?;
+            stderr.reset()?;
+            writeln!(
+                stderr,
+                " {} (Ctrl-C to quit)",
+                paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )?;
+            stderr.flush()?;
+
+            while !changed() {
+                match ctrl_c.recv_timeout(POLL_INTERVAL) {
+                    Ok(()) => return Ok(()),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // Failed to listen for Ctrl-C.
+                    Err(RecvTimeoutError::Disconnected) => thread::sleep(POLL_INTERVAL),
+                }
+            }
+        }
+
+        // Waits for the editor to finish writing.
+        loop {
+            let before = paths.iter().map(|p| modified(p)).collect::<Vec<_>>();
+            thread::sleep(DEBOUNCE);
+            if paths.iter().map(|p| modified(p)).eq(before) {
+                break;
+            }
+        }
+    }
+}
+
+fn changed() -> bool {
+    WATCHED
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(path, modified)| self::modified(path) != *modified)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
@@ -15,13 +15,13 @@ pub(crate) fn cookie_store_path() -> anyhow::Result<PathBuf> {
 pub(crate) fn atcoder_username_and_password<'a, R: BufRead, W1, W2: Write>(
     shell: &'a RefCell<&'a mut crate::shell::Shell<R, W1, W2>>,
 ) -> impl FnMut() -> anyhow::Result<(String, String)> + 'a {
-    username_and_password(shell, "Username: ")
+    username_and_password(shell, crate::messages::Message::UsernamePrompt.get())
 }
 
 pub(crate) fn codeforces_username_and_password<'a, R: BufRead, W1, W2: Write>(
     shell: &'a RefCell<&'a mut crate::shell::Shell<R, W1, W2>>,
 ) -> impl FnMut() -> anyhow::Result<(String, String)> + 'a {
-    username_and_password(shell, "Handle/Email: ")
+    username_and_password(shell, crate::messages::Message::HandleOrEmailPrompt.get())
 }
 
 pub(crate) fn username_and_password<'a, R: BufRead, W1, W2: Write>(
@@ -31,7 +31,7 @@ pub(crate) fn username_and_password<'a, R: BufRead, W1, W2: Write>(
     move || -> _ {
         let mut shell = shell.borrow_mut();
         let username = shell.read_reply(username_prompt)?;
-        let password = shell.read_password("Password: ")?;
+        let password = shell.read_password(crate::messages::Message::PasswordPrompt.get())?;
         Ok((username, password))
     }
 }
@@ -41,6 +41,7 @@ pub(crate) fn dropbox_access_token() -> anyhow::Result<String> {
 
     let Dropbox { access_token } = crate::fs::read_json(&path)
         .with_context(|| format!("First, save the access token to `{}`", path.display()))?;
+    crate::fs::restrict_permissions(&path)?;
 
     return Ok(access_token);
 
@@ -61,15 +62,17 @@ pub(crate) fn codeforces_api_key_and_secret(
     } = if path.exists() {
         crate::fs::read_json(path)?
     } else {
-        let api_key = shell.read_password("Codeforces `api_key`: ")?;
-        let api_secret = shell.read_password("Codeforces `api_secret`: ")?;
+        let api_key =
+            shell.read_password(crate::messages::Message::CodeforcesApiKeyPrompt.get())?;
+        let api_secret =
+            shell.read_password(crate::messages::Message::CodeforcesApiSecretPrompt.get())?;
 
         let pair = Codeforces {
             api_key,
             api_secret,
         };
 
-        crate::fs::write_json(path, &pair, true)?;
+        crate::fs::write_json_secret(path, &pair, true)?;
         pair
     };
 
@@ -90,8 +93,8 @@ pub(crate) fn yukicoder_api_key(
     if path.exists() {
         crate::fs::read_json(path)
     } else {
-        let api_key = shell.read_password("yukicoder API key: ")?;
-        crate::fs::write_json(path, &api_key, true)?;
+        let api_key = shell.read_password(crate::messages::Message::YukicoderApiKeyPrompt.get())?;
+        crate::fs::write_json_secret(path, &api_key, true)?;
         Ok(api_key)
     }
 }
@@ -1,8 +1,11 @@
 pub(crate) mod credentials;
 
+use anyhow::{ensure, Context as _};
 use heck::{CamelCase as _, KebabCase as _, MixedCase as _, SnakeCase as _};
 use serde::Serialize;
+use snowchains_core::web::{Shell, StatusCodeColor};
 use std::time::Duration;
+use url::Url;
 
 pub(crate) const SESSION_TIMEOUT: Duration = Duration::from_secs(30);
 
@@ -31,3 +34,27 @@ impl CaseConversions {
         }
     }
 }
+
+/// Downloads a file without any cookies.
+pub(crate) fn download(url: Url, shell: &mut impl Shell) -> anyhow::Result<Vec<u8>> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(SESSION_TIMEOUT)
+        .build()?;
+
+    let req = client.get(url.clone()).build()?;
+    shell.on_request(&req)?;
+
+    let res = client
+        .execute(req)
+        .with_context(|| format!("Could not download `{}`", url))?;
+
+    let color = if res.status().is_success() {
+        StatusCodeColor::Ok
+    } else {
+        StatusCodeColor::Error
+    };
+    shell.on_response(&res, color)?;
+
+    ensure!(res.status().is_success(), "Could not download `{}`", url);
+    Ok(res.bytes()?.to_vec())
+}
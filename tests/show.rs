@@ -0,0 +1,156 @@
+use snowchains::{
+    shell::{Shell, TtyOrPiped},
+    Context, Opt,
+};
+use std::{fs, process::Stdio, thread};
+use structopt::StructOpt as _;
+use termcolor::NoColor;
+
+/// `show` commands evaluate nothing but the detection functions and the optional `judge`.
+static SNOWCHAINS_DHALL: &str = r"{ detectServiceFromRelativePathSegments = λ(_ : List Text) → None Text
+, detectContestFromRelativePathSegments = λ(_ : List Text) → None Text
+, detectProblemFromRelativePathSegments = λ(_ : List Text) → None Text
+, detectLanguageFromRelativePathSegments = λ(_ : List Text) → None Text
+}
+";
+
+/// The input has no trailing newline so that any added formatting shows up.
+static TEST_SUITE: &str = r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases:
+  - name: Sample 1
+    in: "1 2"
+    out: |
+      3
+
+extend: []
+"#;
+
+#[test]
+fn show_in() -> anyhow::Result<()> {
+    assert_eq!(("1 2".to_owned(), "".to_owned()), show("in", &[])?);
+    assert_eq!(
+        ("MSAy\n".to_owned(), "".to_owned()),
+        show("in", &["--base64"])?
+    );
+    assert_eq!(
+        ("MSAy".to_owned(), "".to_owned()),
+        show("in", &["--base64", "--raw"])?
+    );
+    Ok(())
+}
+
+#[test]
+fn show_expected() -> anyhow::Result<()> {
+    assert_eq!(("3\n".to_owned(), "".to_owned()), show("expected", &[])?);
+    Ok(())
+}
+
+#[test]
+fn show_test_case_hash() -> anyhow::Result<()> {
+    let (stdout, stderr) = show("test-case-hash", &[])?;
+    assert!(stdout.ends_with('\n'), "{:?}", stdout);
+    assert_eq!("", stderr);
+
+    let (raw, stderr) = show("test-case-hash", &["--raw"])?;
+    assert_eq!(stdout.trim_end(), raw);
+    assert_eq!(16, raw.len());
+    assert_eq!("", stderr);
+    Ok(())
+}
+
+#[test]
+fn show_test_case_time_limit() -> anyhow::Result<()> {
+    assert_eq!(
+        ("2000ms (2000ms × 1)\n".to_owned(), "".to_owned()),
+        show("test-case-time-limit", &[])?,
+    );
+
+    let config = format!("{} // {{ judge.timelimitScale = 1.5 }}", SNOWCHAINS_DHALL);
+    assert_eq!(
+        ("3000ms (2000ms × 1.5)\n".to_owned(), "".to_owned()),
+        run(&config, show_args("test-case-time-limit", &[]).into_iter())?,
+    );
+    Ok(())
+}
+
+/// Runs `show <subcommand>` for the first test case of AtCoder ABC100 A in a new workspace, and
+/// returns what it wrote to stdout and stderr.
+fn show<'a>(subcommand: &'a str, flags: &[&'a str]) -> anyhow::Result<(String, String)> {
+    run(SNOWCHAINS_DHALL, show_args(subcommand, flags).into_iter())
+}
+
+fn show_args<'a>(subcommand: &'a str, flags: &[&'a str]) -> Vec<&'a str> {
+    let mut args = vec![
+        "snowchains",
+        "show",
+        subcommand,
+        "-s",
+        "atcoder",
+        "-c",
+        "abc100",
+        "a",
+        "1",
+    ];
+    args.extend(flags);
+    args
+}
+
+fn run<'a>(
+    snowchains_dhall: &str,
+    args: impl Iterator<Item = &'a str>,
+) -> anyhow::Result<(String, String)> {
+    let workspace = tempfile::Builder::new()
+        .prefix("snowchains-tests-")
+        .tempdir()?;
+
+    fs::write(workspace.path().join("snowchains.dhall"), snowchains_dhall)?;
+
+    let test_suite_path = workspace
+        .path()
+        .join(".snowchains")
+        .join("tests")
+        .join("atcoder")
+        .join("abc100")
+        .join("a.yml");
+    fs::create_dir_all(test_suite_path.parent().unwrap())?;
+    fs::write(test_suite_path, TEST_SUITE)?;
+
+    let opt = Opt::from_iter_safe(args)?;
+    let cwd = workspace.path().to_owned();
+
+    // Evaluating Dhall takes a large stack.
+    let (stdout, stderr) = thread::Builder::new()
+        .name("with-stack-size".to_owned())
+        .stack_size(snowchains::STACK_SIZE)
+        .spawn(move || -> anyhow::Result<_> {
+            let mut stdout = NoColor::new(vec![]);
+            let mut stderr = NoColor::new(vec![]);
+
+            snowchains::run(
+                opt,
+                Context {
+                    cwd,
+                    shell: Shell {
+                        stdin: TtyOrPiped::Piped(&b""[..]),
+                        stdout: &mut stdout,
+                        stderr: &mut stderr,
+                        stderr_tty: false,
+                        stdin_process_redirection: Stdio::null,
+                        stdout_process_redirection: Stdio::null,
+                        stderr_process_redirection: Stdio::null,
+                    },
+                },
+            )?;
+
+            Ok((stdout.into_inner(), stderr.into_inner()))
+        })?
+        .join()
+        .unwrap()?;
+
+    workspace.close()?;
+    Ok((String::from_utf8(stdout)?, String::from_utf8(stderr)?))
+}